@@ -0,0 +1,36 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn standard_env(c: &mut Criterion) {
+    c.bench_function("standard_env", |b| b.iter(|| black_box(vow_core::standard_env())));
+}
+
+fn eval_small_program(c: &mut Criterion) {
+    c.bench_function("eval_small_program", |b| {
+        b.iter(|| {
+            let (mut env_tree, env_id) = vow_core::standard_env();
+            vow_core::eval_str(
+                black_box("(define (square x) (* x x)) (square 12)"),
+                &mut env_tree,
+                env_id,
+            )
+        })
+    });
+}
+
+criterion_group!(benches, standard_env, eval_small_program);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reads and evaluates arbitrary input in a fresh standard environment. Any
+//! error is an expected outcome; only a panic is a failure.
+//!
+//! Programs can loop forever, so run this target with libFuzzer's `-timeout`
+//! option. They can also write files through the port procedures, so run it
+//! in a scratch directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let (mut env_tree, env_id) = vow_core::standard_env();
+        let _ = vow_core::eval_str(input, &mut env_tree, env_id);
+    }
+});
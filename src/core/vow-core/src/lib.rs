@@ -0,0 +1,30 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The Vow interpreter, usable as a library.
+//!
+//! ```
+//! let (mut env_tree, env_id) = vow_core::standard_env();
+//! let values = vow_core::eval_str("(define x 2) (* x 21)", &mut env_tree, env_id).unwrap();
+//! assert_eq!(values[1].to_string(), "42");
+//! ```
+
+pub mod runner;
+
+pub use runner::{
+    defined_symbols, eval, eval_located, eval_str, load, parse_spanned, parse_str, pretty_print,
+    sandboxed_eval, set_command_line, set_recursion_limit, standard_env, trace, untrace, Atom, Env,
+    EnvId, EnvTree, Exp, Located, SandboxConfig, Span, Spanned, VowError, WriteFormat,
+    DEFAULT_RECURSION_LIMIT, PRETTY_PRINT_WIDTH,
+};
@@ -0,0 +1,5985 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![allow(dead_code)]
+
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::error::Error;
+use std::f64::consts;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{fmt, iter, ptr};
+
+use num_complex::Complex64;
+use regex::Regex;
+use slotmap::{DefaultKey, SlotMap};
+
+type Symbol = String;
+type Number = f64;
+type Integer = i64;
+type Bool = bool;
+
+/// Errors produced while reading or evaluating a program.
+#[derive(Debug)]
+pub enum VowError {
+    /// Invalid syntax, along with where it was found if it was detected while
+    /// reading.
+    ParseError(String, Option<Span>),
+    TypeError {
+        expected: &'static str,
+        got: String,
+    },
+    UndefinedSymbol(String),
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    Custom(String),
+    /// An escape continuation was invoked. This unwinds evaluation back to the
+    /// matching `call/cc`, and is only reported as an error if the
+    /// continuation is invoked after that `call/cc` has returned.
+    Escape(Escape),
+    /// A value raised by `raise` or `error` which was not caught by a
+    /// `guard`.
+    Raised(Raised),
+    /// The program called `exit` with this status code. This unwinds
+    /// evaluation to the top level and cannot be caught.
+    Exit(i32),
+    /// Evaluation nested more deeply than the recursion limit, which is
+    /// reported before the Rust stack overflows.
+    RecursionDepthExceeded(usize),
+    /// Evaluation by [sandboxed_eval] took longer than its time limit, in
+    /// milliseconds. This unwinds evaluation to the top level and cannot be
+    /// caught.
+    TimeLimitExceeded(u64),
+    /// The operating system reported an error while accessing the file at
+    /// this path.
+    IoError(String, io::Error),
+    /// An error raised while evaluating source code, along with where it was
+    /// raised. Errors are only wrapped while they unwind through the
+    /// interpreter: the functions of this crate return the error itself, and
+    /// [eval_located] returns where it was raised separately.
+    Located(Box<Located>),
+}
+
+/// An error returned by [eval_located], along with the location of the
+/// expression which raised it.
+#[derive(Debug)]
+pub struct Located {
+    pub error: VowError,
+    /// The innermost expression read from source which was being evaluated
+    /// when the error was raised. Errors raised by expressions which were not
+    /// read from source have no location, nor do errors such as
+    /// [VowError::Exit] which stop evaluation without being reported.
+    pub span: Option<Span>,
+    /// The call sites which led to `span`, innermost first. Each one is the
+    /// expression which a frame of the stack was evaluating, such as a
+    /// procedure call, before any tail calls it made, and repeated call sites
+    /// are listed once.
+    pub trace: Vec<Span>,
+}
+
+/// The value passed to an escape continuation, along with the identity of the
+/// continuation it was passed to.
+#[derive(Debug)]
+pub struct Escape {
+    id: u64,
+    value: Box<Exp>,
+}
+
+/// A value raised as an exception.
+#[derive(Debug)]
+pub struct Raised {
+    value: Box<Exp>,
+}
+
+impl fmt::Display for VowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VowError::ParseError(message, Some(span)) => {
+                write!(f, "parse error at {span}: {message}")
+            }
+            VowError::ParseError(message, None) => write!(f, "parse error: {message}"),
+            VowError::TypeError { expected, got } => {
+                write!(f, "type error: expected {expected}, got {got}")
+            }
+            VowError::UndefinedSymbol(symbol) => write!(f, "undefined symbol '{symbol}'"),
+            VowError::ArityMismatch { name, expected, got } => {
+                write!(f, "{name}: expected {expected} arguments, got {got}")
+            }
+            VowError::Custom(message) => write!(f, "{message}"),
+            VowError::Exit(code) => write!(f, "exit with code {code}"),
+            VowError::RecursionDepthExceeded(limit) => {
+                write!(f, "maximum recursion depth of {limit} exceeded")
+            }
+            VowError::IoError(path, error) => write!(f, "{path}: {error}"),
+            VowError::Located(located) => write!(f, "{}", located.error),
+            VowError::TimeLimitExceeded(limit) => {
+                write!(f, "evaluation exceeded the time limit of {limit}ms")
+            }
+            VowError::Escape(_) => {
+                write!(f, "continuation invoked outside of the extent of its call/cc")
+            }
+            VowError::Raised(raised) => match raised.value.as_ref() {
+                Exp::ErrorObject { message, irritants } => {
+                    write!(f, "{message}")?;
+                    for irritant in irritants {
+                        write!(f, " {}", WriteFormat(irritant))?;
+                    }
+                    Ok(())
+                }
+                value => write!(f, "uncaught exception: {}", WriteFormat(value)),
+            },
+        }
+    }
+}
+
+impl Error for VowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            VowError::IoError(_, error) => Some(error),
+            VowError::Located(located) => located.error.source(),
+            _ => None,
+        }
+    }
+}
+
+impl VowError {
+    /// Returns this error without the location at which it was raised.
+    fn unlocated(&self) -> &VowError {
+        match self {
+            VowError::Located(located) => &located.error,
+            error => error,
+        }
+    }
+
+    /// Converts this error into the error raised, without its location.
+    fn into_unlocated(self) -> VowError {
+        match self {
+            VowError::Located(located) => located.error,
+            error => error,
+        }
+    }
+
+    /// Records that this error unwound through a stack frame which was
+    /// evaluating the expression at `span`, having begun with the one at
+    /// `call_site`. The innermost such frame is where the error was raised.
+    ///
+    /// Errors which unwind evaluation without being reported, such as
+    /// escapes to a continuation, are not located, nor are errors which
+    /// would otherwise collect a trace as deep as the recursion limit.
+    fn located(self, span: &Span, call_site: &Span) -> VowError {
+        let mut located = match self {
+            VowError::Escape(_)
+            | VowError::Exit(_)
+            | VowError::TimeLimitExceeded(_)
+            | VowError::RecursionDepthExceeded(_) => return self,
+            VowError::Located(located) => located,
+            error => Box::new(Located { error, span: Some(span.clone()), trace: vec![] }),
+        };
+        if located.trace.last().or(located.span.as_ref()) != Some(call_site) {
+            located.trace.push(call_site.clone());
+        }
+        VowError::Located(located)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Atom {
+    Symbol(Symbol),
+    /// An exact integer.
+    Integer(Integer),
+    /// An inexact real number.
+    Number(Number),
+    Complex(Complex64),
+    Bool(Bool),
+    /// A mutable string. Copies of a string share the same storage, so
+    /// `string-set!` is visible through every reference.
+    String(Rc<RefCell<String>>),
+    Char(char),
+    /// The end-of-file object returned when reading past the end of a port.
+    Eof,
+}
+
+type List = Vec<Exp>;
+
+/// A Scheme value, which is also the representation of source code.
+#[derive(Clone)]
+pub enum Exp {
+    Atom(Atom),
+    /// A proper list. Lists are stored by value rather than as chains of
+    /// shared pairs, so unlike R7RS pairs they have no identity: `eq?` and
+    /// `eqv?` are false for any two non-empty lists, even two references to
+    /// the same one.
+    List(List),
+    /// A pair whose cdr is not a proper list, written `(car . cdr)`.
+    ///
+    /// Pairs whose cdr is a proper list are always represented as
+    /// [Exp::List] instead, so chains of pairs ending in a non-list value
+    /// form improper lists such as `(1 2 . 3)`.
+    Pair(Box<Exp>, Box<Exp>),
+    /// A mutable vector, written `#(a b c)`. Copies of a vector share the same
+    /// storage, so mutations are visible through every reference.
+    Vector(Rc<RefCell<Vec<Exp>>>),
+    /// A mutable vector of bytes, written `#u8(1 2 3)`. Copies of a
+    /// bytevector share the same storage.
+    Bytevector(Rc<RefCell<Vec<u8>>>),
+    InputPort(InputPort),
+    OutputPort(OutputPort),
+    Function(fn(&mut EnvTree, List) -> Result<Exp, VowError>),
+    /// The built-in `apply` procedure. Unlike other built-ins it calls its
+    /// procedure argument in tail position.
+    Apply,
+    Procedure(Rc<Procedure>),
+    /// An escape continuation created by `call/cc`, identified by a unique id.
+    Continuation(u64),
+    /// Zero or several values returned together by `values`. A single value
+    /// is never wrapped.
+    Values(List),
+    /// A promise created by `delay`, `delay-force` or `make-promise`.
+    Promise(Rc<RefCell<PromiseState>>),
+    /// A macro transformer created by `syntax-rules`.
+    Transformer(Rc<SyntaxTransformer>),
+    /// A record type created by `define-record-type`.
+    RecordType(Rc<RecordType>),
+    /// An instance of a record type. Copies of a record share the same
+    /// fields, so mutations are visible through every reference.
+    Record {
+        record_type: Rc<RecordType>,
+        fields: Rc<RefCell<Vec<Exp>>>,
+    },
+    /// A constructor, predicate, accessor or modifier created by
+    /// `define-record-type`.
+    RecordProcedure(Rc<RecordProcedure>),
+    /// An error object created by `error`, or describing an error detected by
+    /// the interpreter.
+    ErrorObject {
+        message: String,
+        irritants: List,
+    },
+    /// A parameter object created by `make-parameter`.
+    Parameter(Rc<Parameter>),
+    /// A mutable hash table. Copies of a hash table share the same storage.
+    HashTable(Rc<RefCell<HashTable>>),
+    /// An environment in which `eval` can evaluate expressions.
+    Environment(EnvId),
+    /// A procedure bound to this name whose calls and returns are printed,
+    /// installed by [trace].
+    Traced(Symbol, Box<Exp>),
+    /// A list or symbol read from source code, along with where it was
+    /// written. Evaluating it evaluates the expression, and errors raised by
+    /// that evaluation are located here. Quoted data never contains spans.
+    Spanned(Rc<Spanned<Exp>>),
+    /// Placeholder value for a `letrec` binding whose initializer has not
+    /// been evaluated yet.
+    Uninitialized,
+}
+
+impl Exp {
+    pub fn num(number: Number) -> Self {
+        Self::Atom(Atom::Number(number))
+    }
+
+    pub fn int(integer: Integer) -> Self {
+        Self::Atom(Atom::Integer(integer))
+    }
+
+    /// Returns a complex number, or an inexact real number if its imaginary
+    /// part is zero.
+    pub fn complex(complex: Complex64) -> Self {
+        if complex.im == 0.0 {
+            Self::num(complex.re)
+        } else {
+            Self::Atom(Atom::Complex(complex))
+        }
+    }
+
+    pub fn bool(b: Bool) -> Self {
+        Self::Atom(Atom::Bool(b))
+    }
+
+    pub fn symbol(s: impl Into<String>) -> Self {
+        Self::Atom(Atom::Symbol(s.into()))
+    }
+
+    /// Returns `values` as the result of an expression, unwrapping a single
+    /// value.
+    pub fn values(mut values: List) -> Self {
+        if values.len() == 1 {
+            values.pop().unwrap()
+        } else {
+            Exp::Values(values)
+        }
+    }
+
+    /// Returns the values produced by an expression, which are several values
+    /// only if it returned `Exp::Values`.
+    fn into_values(self) -> List {
+        match self {
+            Exp::Values(values) => values,
+            exp => vec![exp],
+        }
+    }
+
+    pub fn string(s: impl Into<String>) -> Self {
+        Self::Atom(Atom::String(Rc::new(RefCell::new(s.into()))))
+    }
+
+    pub fn vector(elements: Vec<Exp>) -> Self {
+        Self::Vector(Rc::new(RefCell::new(elements)))
+    }
+
+    pub fn bytevector(bytes: Vec<u8>) -> Self {
+        Self::Bytevector(Rc::new(RefCell::new(bytes)))
+    }
+
+    /// Constructs the pair `(car . cdr)`.
+    pub fn cons(car: Exp, cdr: Exp) -> Self {
+        match cdr {
+            Exp::List(list) => Exp::List(iter::once(car).chain(list).collect()),
+            Exp::Spanned(cdr) => Exp::cons(car, cdr.value.clone()),
+            cdr => Exp::Pair(Box::new(car), Box::new(cdr)),
+        }
+    }
+
+    fn car(&self) -> Result<Exp, VowError> {
+        match self.unspanned() {
+            Exp::List(list) if !list.is_empty() => Ok(list[0].clone()),
+            Exp::Pair(car, _) => Ok(car.as_ref().clone()),
+            _ => Err(self.type_error("pair")),
+        }
+    }
+
+    fn cdr(&self) -> Result<Exp, VowError> {
+        match self.unspanned() {
+            Exp::List(list) if !list.is_empty() => Ok(Exp::List(list[1..].to_vec())),
+            Exp::Pair(_, cdr) => Ok(cdr.as_ref().clone()),
+            _ => Err(self.type_error("pair")),
+        }
+    }
+
+    /// Returns the expression read from source without its location.
+    fn unspanned(&self) -> &Exp {
+        match self {
+            Exp::Spanned(spanned) => &spanned.value,
+            exp => exp,
+        }
+    }
+
+    /// Returns this expression as a datum, without the locations of any of
+    /// its parts.
+    fn strip_spans(&self) -> Exp {
+        match self {
+            Exp::Spanned(spanned) => spanned.value.strip_spans(),
+            Exp::List(list) => Exp::List(list.iter().map(Exp::strip_spans).collect()),
+            Exp::Pair(car, cdr) => Exp::cons(car.strip_spans(), cdr.strip_spans()),
+            exp => exp.clone(),
+        }
+    }
+
+    fn type_error(&self, expected: &'static str) -> VowError {
+        VowError::TypeError { expected, got: WriteFormat(self).to_string() }
+    }
+
+    fn as_symbol(&self) -> Result<Symbol, VowError> {
+        match self.unspanned() {
+            Exp::Atom(Atom::Symbol(s)) => Ok(s.clone()),
+            _ => Err(self.type_error("symbol")),
+        }
+    }
+
+    /// Returns true if this is the symbol `symbol`, or an alias of it
+    /// introduced by a macro expansion.
+    fn is_symbol(&self, symbol: &str) -> bool {
+        match self.unspanned() {
+            Exp::Atom(Atom::Symbol(s)) => unaliased(s) == symbol,
+            _ => false,
+        }
+    }
+
+    fn as_exp_list(&self) -> Result<Vec<Exp>, VowError> {
+        match self.unspanned() {
+            Exp::List(list) => Ok(list.clone()),
+            _ => Err(self.type_error("list")),
+        }
+    }
+
+    fn as_symbol_list(&self) -> Result<Vec<Symbol>, VowError> {
+        match self.unspanned() {
+            Exp::List(list) => list.iter().map(|e| e.as_symbol()).collect(),
+            _ => Err(self.type_error("list")),
+        }
+    }
+
+    /// Returns this number as an `f64`, converting exact integers to inexact
+    /// numbers.
+    fn as_number(&self) -> Result<Number, VowError> {
+        match self {
+            Exp::Atom(Atom::Integer(n)) => Ok(*n as Number),
+            Exp::Atom(Atom::Number(n)) => Ok(*n),
+            _ => Err(self.type_error("number")),
+        }
+    }
+
+    /// Returns this number as a real number, rejecting complex numbers.
+    fn as_real(&self) -> Result<Number, VowError> {
+        match self {
+            Exp::Atom(Atom::Complex(_)) => Err(self.type_error("real number")),
+            _ => self.as_number(),
+        }
+    }
+
+    /// Returns this number as a complex number, converting real numbers to
+    /// complex numbers with no imaginary part.
+    fn as_complex(&self) -> Result<Complex64, VowError> {
+        match self {
+            Exp::Atom(Atom::Complex(n)) => Ok(*n),
+            _ => Ok(Complex64::new(self.as_number()?, 0.0)),
+        }
+    }
+
+    fn as_integer(&self) -> Result<Integer, VowError> {
+        match self {
+            Exp::Atom(Atom::Integer(n)) => Ok(*n),
+            _ => Err(self.type_error("exact integer")),
+        }
+    }
+
+    fn is_complex(&self) -> bool {
+        matches!(self, Exp::Atom(Atom::Complex(..)))
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Exp::Atom(Atom::Integer(..) | Atom::Number(..)))
+    }
+
+    /// Returns a copy of the contents of this string.
+    fn as_string(&self) -> Result<String, VowError> {
+        Ok(self.as_string_cell()?.borrow().clone())
+    }
+
+    /// Returns the shared storage of this string, for procedures which mutate
+    /// it.
+    fn as_string_cell(&self) -> Result<Rc<RefCell<String>>, VowError> {
+        match self {
+            Exp::Atom(Atom::String(s)) => Ok(Rc::clone(s)),
+            _ => Err(self.type_error("string")),
+        }
+    }
+
+    fn as_vector(&self) -> Result<Rc<RefCell<Vec<Exp>>>, VowError> {
+        match self {
+            Exp::Vector(v) => Ok(Rc::clone(v)),
+            _ => Err(self.type_error("vector")),
+        }
+    }
+
+    /// Returns this value as an index which must be less than `len`, or no
+    /// greater than `len` if `inclusive` is true.
+    fn as_index(&self, len: usize, inclusive: bool) -> Result<usize, VowError> {
+        match usize::try_from(self.as_integer()?) {
+            Ok(index) if index < len || (inclusive && index == len) => Ok(index),
+            _ => Err(VowError::Custom(format!("index {} out of range", WriteFormat(self)))),
+        }
+    }
+
+    /// Returns the shared byte buffer of this bytevector.
+    fn as_bytevector(&self) -> Result<Rc<RefCell<Vec<u8>>>, VowError> {
+        match self {
+            Exp::Bytevector(v) => Ok(Rc::clone(v)),
+            _ => Err(self.type_error("bytevector")),
+        }
+    }
+
+    /// Returns this exact integer as a byte.
+    fn as_byte(&self) -> Result<u8, VowError> {
+        u8::try_from(self.as_integer()?).map_err(|_| self.type_error("byte"))
+    }
+
+    fn as_input_port(&self) -> Result<InputPort, VowError> {
+        match self {
+            Exp::InputPort(port) => Ok(port.clone()),
+            _ => Err(self.type_error("input port")),
+        }
+    }
+
+    /// Returns the id of this environment, checking that it belongs to
+    /// `env_tree`.
+    fn as_environment(&self, env_tree: &EnvTree) -> Result<EnvId, VowError> {
+        match self {
+            Exp::Environment(env_id) if env_tree.contains_key(*env_id) => Ok(*env_id),
+            Exp::Environment(_) => Err(VowError::Custom("environment does not exist".to_string())),
+            _ => Err(self.type_error("environment")),
+        }
+    }
+
+    fn as_output_port(&self) -> Result<OutputPort, VowError> {
+        match self {
+            Exp::OutputPort(port) => Ok(port.clone()),
+            _ => Err(self.type_error("output port")),
+        }
+    }
+
+    fn as_hash_table(&self) -> Result<Rc<RefCell<HashTable>>, VowError> {
+        match self {
+            Exp::HashTable(table) => Ok(Rc::clone(table)),
+            _ => Err(self.type_error("hash table")),
+        }
+    }
+
+    fn is_procedure(&self) -> bool {
+        matches!(
+            self,
+            Exp::Function(..)
+                | Exp::Apply
+                | Exp::Procedure(..)
+                | Exp::Continuation(..)
+                | Exp::RecordProcedure(..)
+                | Exp::Parameter(..)
+                | Exp::Traced(..)
+        )
+    }
+
+    /// Returns a short description of the type of this value.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Exp::Atom(Atom::Symbol(_)) => "symbol",
+            Exp::Atom(Atom::Integer(_)) => "integer",
+            Exp::Atom(Atom::Number(_)) => "real",
+            Exp::Atom(Atom::Complex(_)) => "complex",
+            Exp::Atom(Atom::Bool(_)) => "boolean",
+            Exp::Atom(Atom::String(_)) => "string",
+            Exp::Atom(Atom::Char(_)) => "char",
+            Exp::Atom(Atom::Eof) => "eof-object",
+            Exp::List(list) if list.is_empty() => "empty list",
+            Exp::List(_) => "list",
+            Exp::Pair(..) => "pair",
+            Exp::Vector(_) => "vector",
+            Exp::Bytevector(_) => "bytevector",
+            Exp::InputPort(_) => "input port",
+            Exp::OutputPort(_) => "output port",
+            Exp::Function(_) | Exp::Apply => "built-in procedure",
+            Exp::Procedure(_) | Exp::RecordProcedure(_) => "procedure",
+            Exp::Continuation(_) => "continuation",
+            Exp::Values(_) => "multiple values",
+            Exp::Promise(_) => "promise",
+            Exp::Transformer(_) => "syntax",
+            Exp::RecordType(_) => "record type",
+            Exp::Record { .. } => "record",
+            Exp::ErrorObject { .. } => "error object",
+            Exp::Parameter(_) => "parameter",
+            Exp::HashTable(_) => "hash table",
+            Exp::Environment(_) => "environment",
+            Exp::Traced(_, procedure) => procedure.type_name(),
+            Exp::Spanned(spanned) => spanned.value.type_name(),
+            Exp::Uninitialized => "uninitialized",
+        }
+    }
+
+    fn as_bool(&self) -> Result<Bool, VowError> {
+        match self {
+            Exp::Atom(Atom::Bool(b)) => Ok(*b),
+            _ => Err(self.type_error("boolean")),
+        }
+    }
+
+    fn as_char(&self) -> Result<char, VowError> {
+        match self {
+            Exp::Atom(Atom::Char(c)) => Ok(*c),
+            _ => Err(self.type_error("char")),
+        }
+    }
+
+    /// Returns whether this value counts as true in a conditional. `#f` and
+    /// the empty list are false, and every other value is true.
+    fn is_true(&self) -> bool {
+        match self {
+            Exp::Atom(Atom::Bool(b)) => *b,
+            Exp::List(list) => !list.is_empty(),
+            _ => true,
+        }
+    }
+
+    fn invoke(&self, env_tree: &mut EnvTree, args: List) -> Result<Exp, VowError> {
+        match self.apply(env_tree, args)? {
+            EvalResult::Value(exp) => Ok(exp),
+            EvalResult::TailCall(exp, env_id) => eval_inner(exp, env_tree, env_id),
+        }
+    }
+
+    /// Applies this function to `args`, returning the body of a procedure as
+    /// a tail call instead of evaluating it.
+    fn apply(&self, env_tree: &mut EnvTree, args: List) -> Result<EvalResult, VowError> {
+        match self {
+            Exp::Function(f) => Ok(EvalResult::Value(f(env_tree, args)?)),
+            Exp::Apply => {
+                let (procedure, args) = spread_arguments(args)?;
+                procedure.apply(env_tree, args)
+            }
+            Exp::Procedure(p) => p.apply(env_tree, args),
+            Exp::RecordProcedure(p) => Ok(EvalResult::Value(p.call(args)?)),
+            Exp::Parameter(p) => Ok(EvalResult::Value(p.call(args)?)),
+            Exp::Traced(name, procedure) => {
+                Ok(EvalResult::Value(call_traced(env_tree, name, procedure, args)?))
+            }
+            Exp::Continuation(id) => {
+                let value = Box::new(Exp::values(args));
+                Err(VowError::Escape(Escape { id: *id, value }))
+            }
+            _ => Err(self.type_error("procedure")),
+        }
+    }
+}
+
+/// The state of a promise, which is updated in place when it is forced.
+#[derive(Clone, Debug)]
+pub enum PromiseState {
+    /// The promise has been forced, and always produces this value.
+    Forced(Exp),
+    /// Created by `delay`: the promise's value is the result of evaluating
+    /// this expression.
+    Pending(Exp, EnvId),
+    /// Created by `delay-force`: evaluating this expression produces another
+    /// promise, which is forced in place of this one.
+    PendingForce(Exp, EnvId),
+    /// The pending state of this promise was taken over by another promise
+    /// while forcing a `delay-force` chain, and the value is found there.
+    Forwarded(Rc<RefCell<PromiseState>>),
+}
+
+/// Forces `promise`, evaluating its expression unless it has been forced
+/// before.
+///
+/// Chains of `delay-force` promises are forced iteratively: each promise in
+/// the chain takes over the state of the promise its expression produced, so
+/// lazy loops run in constant stack space.
+fn force(env_tree: &mut EnvTree, mut promise: Rc<RefCell<PromiseState>>) -> Result<Exp, VowError> {
+    loop {
+        let state = promise.borrow().clone();
+        match state {
+            PromiseState::Forced(value) => return Ok(value),
+            PromiseState::Forwarded(next) => promise = next,
+            PromiseState::Pending(exp, env_id) => {
+                let value = eval_inner(exp, env_tree, env_id)?;
+                // Evaluating the expression may have forced this promise
+                // re-entrantly, in which case the first value wins.
+                if let PromiseState::Forced(v) = &*promise.borrow() {
+                    return Ok(v.clone());
+                }
+                *promise.borrow_mut() = PromiseState::Forced(value.clone());
+                return Ok(value);
+            }
+            PromiseState::PendingForce(exp, env_id) => {
+                let result = eval_inner(exp, env_tree, env_id)?;
+                if let PromiseState::Forced(v) = &*promise.borrow() {
+                    return Ok(v.clone());
+                }
+                match result {
+                    Exp::Promise(next) if !Rc::ptr_eq(&next, &promise) => {
+                        let forwarded = PromiseState::Forwarded(Rc::clone(&promise));
+                        let state = std::mem::replace(&mut *next.borrow_mut(), forwarded);
+                        *promise.borrow_mut() = state;
+                    }
+                    Exp::Promise(_) => {
+                        return Err(VowError::Custom(
+                            "force: promise depends on itself".to_string(),
+                        ));
+                    }
+                    value => *promise.borrow_mut() = PromiseState::Forced(value),
+                }
+            }
+        }
+    }
+}
+
+/// A parameter object, whose value can be rebound for the dynamic extent of a
+/// `parameterize` form.
+#[derive(Debug)]
+pub struct Parameter {
+    pub value: RefCell<Exp>,
+    /// A procedure applied to the initial value of the parameter and to each
+    /// value given to it by `parameterize`.
+    pub converter: Option<Exp>,
+}
+
+impl Parameter {
+    /// Calling a parameter with no arguments returns its value, and calling
+    /// it with one argument sets its value.
+    pub fn call(&self, mut arguments: List) -> Result<Exp, VowError> {
+        check_arity_range("parameter", &arguments, 0, 1)?;
+        match arguments.pop() {
+            Some(value) => {
+                *self.value.borrow_mut() = value;
+                Ok(Exp::List(vec![]))
+            }
+            None => Ok(self.value.borrow().clone()),
+        }
+    }
+}
+
+/// A port shared between every reference to it, which can no longer be used
+/// once it has been closed.
+pub struct Port<T>(Rc<RefCell<Option<T>>>);
+
+type InputPort = Port<InPort<Box<dyn Read>>>;
+type OutputPort = Port<Output>;
+
+/// The destination of an output port: either a stream such as a file or
+/// standard output, or a buffer read back with `get-output-string`.
+pub enum Output {
+    Stream(Box<dyn Write>),
+    String(Vec<u8>),
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stream(writer) => writer.write(buf),
+            Output::String(bytes) => bytes.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stream(writer) => writer.flush(),
+            Output::String(_) => Ok(()),
+        }
+    }
+}
+
+impl<T> Port<T> {
+    fn new(port: T) -> Self {
+        Port(Rc::new(RefCell::new(Some(port))))
+    }
+
+    /// Calls `f` with the underlying port, or returns an error if this port
+    /// has been closed.
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> Result<R, VowError>) -> Result<R, VowError> {
+        match self.0.borrow_mut().as_mut() {
+            Some(port) => f(port),
+            None => Err(VowError::Custom("port is closed".to_string())),
+        }
+    }
+
+    fn close(&self) {
+        self.0.borrow_mut().take();
+    }
+}
+
+impl<T> Clone for Port<T> {
+    fn clone(&self) -> Self {
+        Port(Rc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for Port<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Port")
+    }
+}
+
+/// Compares two values using the semantics of `equal?`: lists, pairs, vectors
+/// and strings are compared by their contents, and other values with the
+/// semantics of `eqv?`.
+impl PartialEq for Exp {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Exp::Atom(Atom::String(a)), Exp::Atom(Atom::String(b))) => a == b,
+            (Exp::List(a), Exp::List(b)) => a == b,
+            (Exp::Pair(a_car, a_cdr), Exp::Pair(b_car, b_cdr)) => a_car == b_car && a_cdr == b_cdr,
+            (Exp::Vector(a), Exp::Vector(b)) => Rc::ptr_eq(a, b) || *a.borrow() == *b.borrow(),
+            (Exp::Bytevector(a), Exp::Bytevector(b)) => a == b,
+            _ => is_eqv(self, other),
+        }
+    }
+}
+
+/// Compares two values using the semantics of `eqv?`, which are also those of
+/// `eq?` here.
+///
+/// Symbols, booleans, characters and numbers are compared by value, where
+/// numbers must also have the same exactness and inexact numbers the same
+/// bits, so that `0.0` and `-0.0` differ while NaN is equivalent to itself.
+/// Strings, vectors, procedures and other heap objects are compared by
+/// identity.
+///
+/// Lists have no identity, as described on [Exp::List]: the empty list is
+/// equivalent only to itself, and non-empty lists and pairs are never
+/// equivalent.
+fn is_eqv(a: &Exp, b: &Exp) -> bool {
+    match (a, b) {
+        (Exp::Atom(Atom::Number(a)), Exp::Atom(Atom::Number(b))) => a.to_bits() == b.to_bits(),
+        (Exp::Atom(Atom::Complex(a)), Exp::Atom(Atom::Complex(b))) => {
+            a.re.to_bits() == b.re.to_bits() && a.im.to_bits() == b.im.to_bits()
+        }
+        (Exp::Atom(Atom::String(a)), Exp::Atom(Atom::String(b))) => Rc::ptr_eq(a, b),
+        (Exp::Atom(a), Exp::Atom(b)) => a == b,
+        (Exp::List(a), Exp::List(b)) => a.is_empty() && b.is_empty(),
+        (Exp::Function(a), Exp::Function(b)) => ptr::fn_addr_eq(*a, *b),
+        (Exp::Apply, Exp::Apply) => true,
+        (Exp::Procedure(a), Exp::Procedure(b)) => Rc::ptr_eq(a, b),
+        (Exp::RecordProcedure(a), Exp::RecordProcedure(b)) => Rc::ptr_eq(a, b),
+        (Exp::Traced(_, a), Exp::Traced(_, b)) => is_eqv(a, b),
+        (Exp::Transformer(a), Exp::Transformer(b)) => Rc::ptr_eq(a, b),
+        (Exp::Vector(a), Exp::Vector(b)) => Rc::ptr_eq(a, b),
+        (Exp::Bytevector(a), Exp::Bytevector(b)) => Rc::ptr_eq(a, b),
+        (Exp::Continuation(a), Exp::Continuation(b)) => a == b,
+        (Exp::Promise(a), Exp::Promise(b)) => Rc::ptr_eq(a, b),
+        (Exp::RecordType(a), Exp::RecordType(b)) => Rc::ptr_eq(a, b),
+        (Exp::Record { fields: a, .. }, Exp::Record { fields: b, .. }) => Rc::ptr_eq(a, b),
+        (Exp::Parameter(a), Exp::Parameter(b)) => Rc::ptr_eq(a, b),
+        (Exp::HashTable(a), Exp::HashTable(b)) => Rc::ptr_eq(a, b),
+        (Exp::Environment(a), Exp::Environment(b)) => a == b,
+        (Exp::InputPort(a), Exp::InputPort(b)) => Rc::ptr_eq(&a.0, &b.0),
+        (Exp::OutputPort(a), Exp::OutputPort(b)) => Rc::ptr_eq(&a.0, &b.0),
+        _ => false,
+    }
+}
+
+/// Implements `member`, `memq` and `memv`: returns the first tail of the list
+/// whose car is equivalent to the search value under `equivalent`, or `#f`.
+fn find_member(
+    name: &str,
+    list: &[Exp],
+    equivalent: fn(&Exp, &Exp) -> bool,
+) -> Result<Exp, VowError> {
+    check_arity(name, list, 2)?;
+    let elements = list[1].as_exp_list()?;
+    Ok(match elements.iter().position(|exp| equivalent(&list[0], exp)) {
+        Some(index) => Exp::List(elements[index..].to_vec()),
+        None => Exp::bool(false),
+    })
+}
+
+/// Implements `assoc`, `assq` and `assv`: returns the first pair in the
+/// association list whose car is equivalent to the key under `equivalent`, or
+/// `#f`.
+fn find_association(
+    name: &str,
+    list: &[Exp],
+    equivalent: fn(&Exp, &Exp) -> bool,
+) -> Result<Exp, VowError> {
+    check_arity(name, list, 2)?;
+    for entry in list[1].as_exp_list()? {
+        if equivalent(&list[0], &entry.car()?) {
+            return Ok(entry);
+        }
+    }
+    Ok(Exp::bool(false))
+}
+
+/// Returns the tail of `list` after its first `k` pairs, as `list-tail` does.
+fn list_tail(list: &Exp, k: &Exp) -> Result<Exp, VowError> {
+    let out_of_range = || VowError::Custom(format!("index {} out of range", WriteFormat(k)));
+    let mut count = usize::try_from(k.as_integer()?).map_err(|_| out_of_range())?;
+    let mut tail = list;
+    while count > 0 {
+        match tail {
+            Exp::Pair(_, cdr) => {
+                tail = cdr;
+                count -= 1;
+            }
+            Exp::List(elements) => {
+                return elements
+                    .get(count..)
+                    .map(|rest| Exp::List(rest.to_vec()))
+                    .ok_or_else(out_of_range)
+            }
+            _ => return Err(out_of_range()),
+        }
+    }
+    Ok(tail.clone())
+}
+
+/// Returns a list of the first `k` elements of `list`, which may be improper.
+fn list_head(list: &Exp, k: &Exp) -> Result<Exp, VowError> {
+    let out_of_range = || VowError::Custom(format!("index {} out of range", WriteFormat(k)));
+    let count = usize::try_from(k.as_integer()?).map_err(|_| out_of_range())?;
+    let mut result = vec![];
+    let mut tail = list;
+    while result.len() < count {
+        match tail {
+            Exp::Pair(car, cdr) => {
+                result.push(car.as_ref().clone());
+                tail = cdr;
+            }
+            Exp::List(elements) => {
+                let head = elements.get(..count - result.len()).ok_or_else(out_of_range)?;
+                result.extend_from_slice(head);
+            }
+            _ => return Err(out_of_range()),
+        }
+    }
+    Ok(Exp::List(result))
+}
+
+/// Returns the argument lists for applying a procedure element-wise across
+/// `lists`, as `map` and `for-each` do. Iteration stops at the end of the
+/// shortest list.
+fn zip_lists(lists: &[Exp]) -> Result<Vec<List>, VowError> {
+    zip_with(lists, Exp::as_exp_list)
+}
+
+/// Returns the argument lists for applying a procedure element-wise across
+/// `sequences`, whose elements are returned by `elements`. Iteration stops at
+/// the end of the shortest sequence.
+fn zip_with(
+    sequences: &[Exp],
+    elements: impl Fn(&Exp) -> Result<List, VowError>,
+) -> Result<Vec<List>, VowError> {
+    let sequences = sequences.iter().map(elements).collect::<Result<Vec<_>, _>>()?;
+    let length = sequences.iter().map(Vec::len).min().unwrap_or(0);
+    Ok((0..length)
+        .map(|i| sequences.iter().map(|elements| elements[i].clone()).collect())
+        .collect())
+}
+
+/// Returns the characters of the string `exp`.
+fn string_chars(exp: &Exp) -> Result<List, VowError> {
+    Ok(exp.as_string()?.chars().map(|c| Exp::Atom(Atom::Char(c))).collect())
+}
+
+/// Returns the elements of the vector `exp`.
+fn vector_elements(exp: &Exp) -> Result<List, VowError> {
+    Ok(exp.as_vector()?.borrow().clone())
+}
+
+/// Splits the arguments to `(apply procedure arg ... list)` into the procedure
+/// and the arguments to call it with: the individual arguments followed by the
+/// elements of the final list.
+fn spread_arguments(mut list: List) -> Result<(Exp, List), VowError> {
+    check_min_arity("apply", &list, 2)?;
+    let spread = list.pop().unwrap().as_exp_list()?;
+    let procedure = list.remove(0);
+    list.extend(spread);
+    Ok((procedure, list))
+}
+
+/// Sorts `elements` with a stable merge sort, calling the Scheme procedure
+/// `less` to compare them.
+fn merge_sort(env_tree: &mut EnvTree, mut elements: List, less: &Exp) -> Result<List, VowError> {
+    if elements.len() <= 1 {
+        return Ok(elements);
+    }
+    let right = merge_sort(env_tree, elements.split_off(elements.len() / 2), less)?;
+    let left = merge_sort(env_tree, elements, less)?;
+    let mut result = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        let comparison = less.invoke(env_tree, vec![b.clone(), a.clone()])?;
+        let Exp::Atom(Atom::Bool(right_first)) = comparison else {
+            return Err(VowError::Custom(format!(
+                "sort: comparator returned {}, expected a boolean",
+                WriteFormat(&comparison)
+            )));
+        };
+        result.push(if right_first { right.next() } else { left.next() }.unwrap());
+    }
+    result.extend(left.chain(right));
+    Ok(result)
+}
+
+/// Returns the radix given as the optional second argument to `name`, which
+/// must be 2, 8, 10 or 16, or 10 if it is absent.
+fn optional_radix(name: &str, list: &[Exp]) -> Result<u32, VowError> {
+    match list.get(1).map(Exp::as_integer).transpose()? {
+        None => Ok(10),
+        Some(radix @ (2 | 8 | 10 | 16)) => Ok(radix as u32),
+        Some(radix) => {
+            Err(VowError::Custom(format!("{name}: radix must be 2, 8, 10 or 16, got {radix}")))
+        }
+    }
+}
+
+/// Implements `odd?` and `even?`, which accept exact integers and inexact
+/// numbers with no fractional part.
+fn is_odd(name: &str, list: &[Exp]) -> Result<bool, VowError> {
+    check_arity(name, list, 1)?;
+    match &list[0] {
+        Exp::Atom(Atom::Integer(n)) => Ok(n % 2 != 0),
+        Exp::Atom(Atom::Number(n)) if n.fract() == 0.0 => Ok(n % 2.0 != 0.0),
+        exp => Err(exp.type_error("integer")),
+    }
+}
+
+/// Returns an error unless exactly `expected` arguments were provided to the
+/// function `name`.
+fn check_arity(name: &str, list: &[Exp], expected: usize) -> Result<(), VowError> {
+    if list.len() == expected {
+        Ok(())
+    } else {
+        Err(VowError::ArityMismatch { name: name.to_string(), expected, got: list.len() })
+    }
+}
+
+/// Returns an error unless at least `min` arguments were provided to the
+/// function `name`.
+fn check_min_arity(name: &str, list: &[Exp], min: usize) -> Result<(), VowError> {
+    if list.len() >= min {
+        Ok(())
+    } else {
+        Err(VowError::ArityMismatch { name: name.to_string(), expected: min, got: list.len() })
+    }
+}
+
+/// Returns true if `relation` holds for every consecutive pair of numbers in
+/// `list`. Exact integers are compared exactly, and comparisons involving NaN
+/// are always false.
+fn compare_numbers(
+    name: &str,
+    list: &[Exp],
+    relation: fn(Ordering) -> bool,
+) -> Result<Exp, VowError> {
+    check_min_arity(name, list, 2)?;
+    let mut result = true;
+    for pair in list.windows(2) {
+        let ordering = match (&pair[0], &pair[1]) {
+            (Exp::Atom(Atom::Integer(a)), Exp::Atom(Atom::Integer(b))) => Some(a.cmp(b)),
+            // Complex numbers can only be compared for equality.
+            (a, b) if name == "=" && (a.is_complex() || b.is_complex()) => {
+                (a.as_complex()? == b.as_complex()?).then_some(Ordering::Equal)
+            }
+            (a, b) => a.as_number()?.partial_cmp(&b.as_number()?),
+        };
+        result &= ordering.is_some_and(relation);
+    }
+    Ok(Exp::bool(result))
+}
+
+/// Combines `init` with each number in `list` in turn. Exact integers are
+/// combined with `integer_op`, falling back to `float_op` when either operand
+/// is inexact or `integer_op` returns `None` because the result is not
+/// representable as an exact integer. Complex operands are combined with
+/// `complex_op`, or are an error if it is `None`.
+fn fold_numbers(
+    init: Exp,
+    list: &[Exp],
+    integer_op: fn(Integer, Integer) -> Option<Integer>,
+    float_op: fn(Number, Number) -> Number,
+    complex_op: Option<fn(Complex64, Complex64) -> Complex64>,
+) -> Result<Exp, VowError> {
+    list.iter().try_fold(init, |acc, exp| match (&acc, exp) {
+        (Exp::Atom(Atom::Integer(a)), Exp::Atom(Atom::Integer(b))) => Ok(integer_op(*a, *b)
+            .map(Exp::int)
+            .unwrap_or_else(|| Exp::num(float_op(*a as Number, *b as Number)))),
+        (a, b) if a.is_complex() || b.is_complex() => match complex_op {
+            Some(complex_op) => Ok(Exp::complex(complex_op(a.as_complex()?, b.as_complex()?))),
+            None => Err((if a.is_complex() { a } else { b }).type_error("real")),
+        },
+        _ => Ok(Exp::num(float_op(acc.as_number()?, exp.as_number()?))),
+    })
+}
+
+/// Applies `float_op` to an inexact argument, returning exact integers
+/// unchanged.
+fn round_number(name: &str, list: &[Exp], float_op: fn(Number) -> Number) -> Result<Exp, VowError> {
+    check_arity(name, list, 1)?;
+    match &list[0] {
+        Exp::Atom(Atom::Integer(n)) => Ok(Exp::int(*n)),
+        exp => Ok(Exp::num(float_op(exp.as_number()?))),
+    }
+}
+
+/// Converts the number `exp` to an exact integer. Since there are no exact
+/// rationals, an inexact number is rounded to the nearest integer, with ties
+/// rounded to even.
+fn to_exact(name: &str, exp: &Exp) -> Result<Exp, VowError> {
+    if let Exp::Atom(Atom::Integer(..)) = exp {
+        return Ok(exp.clone());
+    }
+    let n = exp.as_number()?.round_ties_even();
+    if !n.is_finite() || n < Integer::MIN as Number || n >= Integer::MAX as Number {
+        return Err(VowError::Custom(format!(
+            "{name}: {} has no exact representation",
+            WriteFormat(exp)
+        )));
+    }
+    Ok(Exp::int(n as Integer))
+}
+
+/// How [divide_integers] rounds a quotient which is not an integer.
+#[derive(Clone, Copy)]
+enum Rounding {
+    /// Round toward negative infinity, so the remainder has the sign of the
+    /// divisor.
+    Floor,
+    /// Round toward zero, so the remainder has the sign of the dividend.
+    Truncate,
+}
+
+/// Divides two integers, returning the quotient and remainder. The result is
+/// exact if both arguments are exact, and an error if the divisor is zero.
+fn divide_integers(name: &str, list: &[Exp], rounding: Rounding) -> Result<(Exp, Exp), VowError> {
+    check_arity(name, list, 2)?;
+    for exp in list {
+        if !matches!(exp, Exp::Atom(Atom::Integer(..)))
+            && exp.as_number().map_or(true, |n| n.fract() != 0.0)
+        {
+            return Err(exp.type_error("integer"));
+        }
+    }
+    if list[1].as_number()? == 0.0 {
+        return Err(VowError::Custom(format!("{name}: division by zero")));
+    }
+    if let (Exp::Atom(Atom::Integer(a)), Exp::Atom(Atom::Integer(b))) = (&list[0], &list[1]) {
+        if let (Some(mut quotient), Some(mut remainder)) = (a.checked_div(*b), a.checked_rem(*b)) {
+            if matches!(rounding, Rounding::Floor) && remainder != 0 && (remainder < 0) != (*b < 0)
+            {
+                quotient -= 1;
+                remainder += b;
+            }
+            return Ok((Exp::int(quotient), Exp::int(remainder)));
+        }
+    }
+    let (a, b) = (list[0].as_number()?, list[1].as_number()?);
+    let quotient = match rounding {
+        Rounding::Floor => (a / b).floor(),
+        Rounding::Truncate => (a / b).trunc(),
+    };
+    Ok((Exp::num(quotient), Exp::num(a - b * quotient)))
+}
+
+/// Folds the integer arguments in `list` with `integer_op`, starting from
+/// `identity`. Inexact integers, or a result which overflows, make the rest
+/// of the fold inexact using `float_op`.
+fn fold_integers(
+    list: &[Exp],
+    identity: Integer,
+    integer_op: fn(Integer, Integer) -> Option<Integer>,
+    float_op: fn(Number, Number) -> Number,
+) -> Result<Exp, VowError> {
+    let mut result = Exp::int(identity);
+    for exp in list {
+        result = match (&result, exp) {
+            (Exp::Atom(Atom::Integer(a)), Exp::Atom(Atom::Integer(b))) => {
+                match integer_op(*a, *b) {
+                    Some(n) => Exp::int(n),
+                    None => Exp::num(float_op(*a as Number, *b as Number)),
+                }
+            }
+            (_, exp) => match exp.as_number() {
+                Ok(n) if n.fract() == 0.0 => Exp::num(float_op(result.as_number()?, n)),
+                _ => return Err(exp.type_error("integer")),
+            },
+        };
+    }
+    Ok(result)
+}
+
+/// Returns the non-negative greatest common divisor of `a` and `b`, or `None`
+/// if it overflows.
+fn gcd(a: Integer, b: Integer) -> Option<Integer> {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    Integer::try_from(a).ok()
+}
+
+/// Returns the greatest common divisor of two inexact integers.
+fn gcd_inexact(a: Number, b: Number) -> Number {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0.0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the non-negative least common multiple of `a` and `b`, or `None`
+/// if it overflows.
+fn lcm(a: Integer, b: Integer) -> Option<Integer> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+pub type EnvId = DefaultKey;
+
+/// Every environment created by a program, which refer to their enclosing
+/// environments by [EnvId].
+pub type EnvTree = SlotMap<EnvId, Env>;
+
+#[derive(Clone, Default)]
+pub struct Env {
+    outer: Option<EnvId>,
+    symbols: HashMap<Symbol, Exp>,
+}
+
+impl Env {
+    pub fn insert_into(
+        env_tree: &mut EnvTree,
+        parameters: Vec<Symbol>,
+        arguments: List,
+        outer: Option<EnvId>,
+    ) -> EnvId {
+        let mut result = Self::default();
+        result.symbols.extend(parameters.iter().cloned().zip(arguments.iter().cloned()));
+        result.outer = outer;
+        env_tree.insert(result)
+    }
+
+    pub fn insert(&mut self, symbol: impl Into<String>, exp: Exp) {
+        self.symbols.insert(symbol.into(), exp);
+    }
+
+    pub fn insert_fn(
+        &mut self,
+        symbol: impl Into<String>,
+        function: fn(&mut EnvTree, List) -> Result<Exp, VowError>,
+    ) {
+        self.insert(symbol, Exp::Function(function))
+    }
+
+    pub fn get(&self, symbol: impl Into<String>) -> Option<Exp> {
+        self.symbols.get(&symbol.into()).cloned()
+    }
+
+    /// Returns the value bound to `symbol` in this environment or the nearest
+    /// enclosing environment which binds it.
+    pub fn resolve(&self, env_tree: &EnvTree, symbol: &str) -> Option<Exp> {
+        let mut current = self;
+        loop {
+            if let Some(exp) = current.symbols.get(symbol) {
+                return Some(exp.clone());
+            }
+            current = env_tree.get(current.outer?)?;
+        }
+    }
+
+    /// Returns the id of the environment which binds `symbol`, starting from
+    /// this environment, whose id is `current`.
+    pub fn find(&self, env_tree: &EnvTree, symbol: &str, current: EnvId) -> Option<EnvId> {
+        let (mut env, mut env_id) = (self, current);
+        while !env.symbols.contains_key(symbol) {
+            env_id = env.outer?;
+            env = env_tree.get(env_id)?;
+        }
+        Some(env_id)
+    }
+}
+
+/// Finds the environment which binds `symbol` as seen from `env_id`, returning
+/// it along with the name of the binding.
+///
+/// An alias introduced by a macro which is not bound by the macro's expansion
+/// refers to the identifier it renames, as seen from the environment in which
+/// the macro was defined.
+fn lookup(env_tree: &EnvTree, env_id: EnvId, symbol: &str) -> Result<(EnvId, Symbol), VowError> {
+    let mut env_id = env_id;
+    let mut symbol = symbol.to_string();
+    loop {
+        if let Some(found) = env_tree.get(env_id).unwrap().find(env_tree, &symbol, env_id) {
+            return Ok((found, symbol));
+        }
+        match alias_target(&symbol) {
+            Some((renamed, macro_env)) => {
+                symbol = renamed;
+                env_id = macro_env;
+            }
+            None => return Err(VowError::UndefinedSymbol(symbol)),
+        }
+    }
+}
+
+/// Returns the value bound to `symbol` as seen from `env_id`.
+fn resolve(env_tree: &EnvTree, env_id: EnvId, symbol: &str) -> Result<Exp, VowError> {
+    match env_tree.get(env_id).unwrap().resolve(env_tree, symbol) {
+        Some(exp) => Ok(exp),
+        None if alias_target(symbol).is_some() => {
+            let (found, symbol) = lookup(env_tree, env_id, symbol)?;
+            let value = env_tree.get(found).unwrap().resolve(env_tree, &symbol);
+            value.ok_or(VowError::UndefinedSymbol(symbol))
+        }
+        None => Err(VowError::UndefinedSymbol(symbol.to_string())),
+    }
+}
+
+/// Returns the names of every binding visible from `env_id`, in alphabetical
+/// order. Identifiers renamed by macro expansions are omitted.
+pub fn defined_symbols(env_tree: &EnvTree, env_id: EnvId) -> Vec<Symbol> {
+    let mut result = vec![];
+    let mut current = env_tree.get(env_id);
+    while let Some(env) = current {
+        result.extend(env.symbols.keys().filter(|s| !s.contains(ALIAS_SEPARATOR)).cloned());
+        current = env.outer.and_then(|outer| env_tree.get(outer));
+    }
+    result.sort();
+    result.dedup();
+    result
+}
+
+#[derive(Clone, Debug)]
+pub struct Procedure {
+    pub parameters: ParameterList,
+    pub body: Exp,
+    pub env: EnvId,
+}
+
+impl Procedure {
+    pub fn new(parameters: ParameterList, body: Exp, env: EnvId) -> Self {
+        Self { parameters, body, env }
+    }
+
+    fn apply(&self, env_tree: &mut EnvTree, arguments: List) -> Result<EvalResult, VowError> {
+        let (names, values) = self.parameters.bind(arguments)?;
+        let env_id = Env::insert_into(env_tree, names, values, Some(self.env));
+        Ok(EvalResult::TailCall(self.body.clone(), env_id))
+    }
+}
+
+/// The formal parameters of a procedure.
+#[derive(Clone, Debug)]
+pub enum ParameterList {
+    /// `(lambda (x y) ...)`: exactly one argument per parameter.
+    Fixed(Vec<Symbol>),
+    /// `(lambda (x y . rest) ...)`: `rest` receives a list of the arguments
+    /// after the fixed parameters.
+    Variadic { fixed: Vec<Symbol>, rest: Symbol },
+    /// `(lambda rest ...)`: `rest` receives a list of all arguments.
+    Rest(Symbol),
+}
+
+impl ParameterList {
+    /// Parses a parameter list of the form `(name ...)`, `(name ... . rest)`,
+    /// or `rest`.
+    pub fn parse(parameters: &Exp) -> Result<Self, VowError> {
+        let mut fixed = vec![];
+        let mut tail = parameters.unspanned();
+        while let Exp::Pair(car, cdr) = tail {
+            fixed.push(car.as_symbol()?);
+            tail = cdr;
+        }
+        match tail {
+            Exp::Atom(Atom::Symbol(rest)) if fixed.is_empty() => {
+                Ok(ParameterList::Rest(rest.clone()))
+            }
+            Exp::Atom(Atom::Symbol(rest)) => {
+                Ok(ParameterList::Variadic { fixed, rest: rest.clone() })
+            }
+            Exp::List(_) if fixed.is_empty() => Ok(ParameterList::Fixed(tail.as_symbol_list()?)),
+            _ => Err(VowError::ParseError(
+                format!("Invalid parameter list {}", WriteFormat(parameters)),
+                None,
+            )),
+        }
+    }
+
+    /// Matches `arguments` against these parameters, returning the names and
+    /// values to bind.
+    pub fn bind(&self, mut arguments: List) -> Result<(Vec<Symbol>, List), VowError> {
+        match self {
+            ParameterList::Fixed(names) => {
+                check_arity("lambda", &arguments, names.len())?;
+                Ok((names.clone(), arguments))
+            }
+            ParameterList::Variadic { fixed, rest } => {
+                if arguments.len() < fixed.len() {
+                    return Err(VowError::ArityMismatch {
+                        name: "lambda".to_string(),
+                        expected: fixed.len(),
+                        got: arguments.len(),
+                    });
+                }
+                let rest_arguments = arguments.split_off(fixed.len());
+                arguments.push(Exp::List(rest_arguments));
+                let names = fixed.iter().chain(iter::once(rest)).cloned().collect();
+                Ok((names, arguments))
+            }
+            ParameterList::Rest(rest) => Ok((vec![rest.clone()], vec![Exp::List(arguments)])),
+        }
+    }
+}
+
+/// Creates a procedure closing over `env_id` from a parameter list and a
+/// sequence of body expressions.
+///
+/// The body is evaluated in the environment created for each call, so
+/// internal `define` forms bind names local to the call which are visible to
+/// the rest of the body, including any procedures defined alongside them.
+fn make_procedure(parameters: &Exp, body: &[Exp], env_id: EnvId) -> Result<Exp, VowError> {
+    let parameters = ParameterList::parse(parameters)?;
+    let body = sequence(&hoist_internal_defines(body)?);
+    Ok(Exp::Procedure(Rc::new(Procedure::new(parameters, body, env_id))))
+}
+
+/// Rewrites the `define` forms at the start of a procedure body as a
+/// `letrec*` form enclosing the rest of the body.
+///
+/// This gives internal definitions the semantics of R7RS section 5.3.2:
+/// every defined name is bound, though uninitialized, while the
+/// initializers are evaluated, so no initializer can see a binding of the
+/// same name from an outer scope.
+fn hoist_internal_defines(body: &[Exp]) -> Result<Vec<Exp>, VowError> {
+    let count = body
+        .iter()
+        .take_while(
+            |exp| matches!(exp.unspanned(), Exp::List(list) if !list.is_empty() && list[0].is_symbol("define")),
+        )
+        .count();
+    if count == 0 {
+        return Ok(body.to_vec());
+    }
+    let mut bindings = vec![];
+    for define in &body[..count] {
+        let list = define.as_exp_list()?;
+        check_form(&list, 2, usize::MAX)?;
+        bindings.push(if matches!(list[1].unspanned(), Exp::List(..) | Exp::Pair(..)) {
+            let lambda = iter::once(Exp::symbol("lambda"))
+                .chain(iter::once(list[1].cdr()?))
+                .chain(list[2..].iter().cloned());
+            Exp::List(vec![list[1].car()?, Exp::List(lambda.collect())])
+        } else {
+            check_form(&list, 2, 2)?;
+            Exp::List(list[1..].to_vec())
+        });
+    }
+    let letrec = [Exp::symbol("letrec*"), Exp::List(bindings)];
+    Ok(vec![Exp::List(letrec.into_iter().chain(body[count..].iter().cloned()).collect())])
+}
+
+/// Wraps `body` in a `begin` form if it contains more than one expression.
+fn sequence(body: &[Exp]) -> Exp {
+    match body {
+        [exp] => exp.clone(),
+        _ => Exp::List(
+            iter::once(Exp::Atom(Atom::Symbol("begin".to_string())))
+                .chain(body.iter().cloned())
+                .collect(),
+        ),
+    }
+}
+
+fn read_from_tokens(tokens: &mut Vec<String>) -> Result<Exp, VowError> {
+    if tokens.is_empty() {
+        return Err(VowError::ParseError("Unexpected EOF!".to_string(), None));
+    }
+    let token = tokens.remove(0);
+    if token == "(" {
+        let mut list = vec![];
+        while tokens.first().map(String::as_str) != Some(")") {
+            list.push(read_from_tokens(tokens)?);
+        }
+        tokens.remove(0); // Pop off ')'
+        Ok(Exp::List(list))
+    } else if token == ")" {
+        Err(VowError::ParseError("Unexpected ')'!".to_string(), None))
+    } else {
+        Ok(Exp::Atom(atom(token)?))
+    }
+}
+
+fn builtin_env() -> Env {
+    let mut result = Env::default();
+    result.insert_fn("+", |_, list| {
+        fold_numbers(Exp::int(0), &list, Integer::checked_add, |a, b| a + b, Some(|a, b| a + b))
+    });
+    result.insert_fn("-", |_, list| {
+        check_min_arity("-", &list, 1)?;
+        if list.len() == 1 {
+            return fold_numbers(
+                Exp::int(0),
+                &list,
+                Integer::checked_sub,
+                |a, b| a - b,
+                Some(|a, b| a - b),
+            );
+        }
+        fold_numbers(
+            list[0].clone(),
+            &list[1..],
+            Integer::checked_sub,
+            |a, b| a - b,
+            Some(|a, b| a - b),
+        )
+    });
+    result.insert_fn("*", |_, list| {
+        fold_numbers(Exp::int(1), &list, Integer::checked_mul, |a, b| a * b, Some(|a, b| a * b))
+    });
+    result.insert_fn("/", |_, list| {
+        check_min_arity("/", &list, 1)?;
+        // Without rationals, only evenly divisible integers stay exact.
+        fn divide(a: Integer, b: Integer) -> Option<Integer> {
+            a.checked_rem(b).filter(|r| *r == 0).and_then(|_| a.checked_div(b))
+        }
+        if list.len() == 1 {
+            return fold_numbers(Exp::int(1), &list, divide, |a, b| a / b, Some(|a, b| a / b));
+        }
+        fold_numbers(list[0].clone(), &list[1..], divide, |a, b| a / b, Some(|a, b| a / b))
+    });
+    result.insert_fn("<=", |_, list| compare_numbers("<=", &list, Ordering::is_le));
+    result.insert_fn(">=", |_, list| compare_numbers(">=", &list, Ordering::is_ge));
+    result.insert_fn("<", |_, list| compare_numbers("<", &list, Ordering::is_lt));
+    result.insert_fn(">", |_, list| compare_numbers(">", &list, Ordering::is_gt));
+    result.insert_fn("abs", |_, list| {
+        check_arity("abs", &list, 1)?;
+        match &list[0] {
+            Exp::Atom(Atom::Integer(n)) => {
+                Ok(n.checked_abs().map(Exp::int).unwrap_or_else(|| Exp::num((*n as Number).abs())))
+            }
+            exp => Ok(Exp::num(exp.as_number()?.abs())),
+        }
+    });
+    result.insert_fn("append", |_, mut list| {
+        let Some(last) = list.pop() else {
+            return Ok(Exp::List(vec![]));
+        };
+        let mut result = last;
+        for exp in list.iter().rev() {
+            for element in exp.as_exp_list()?.into_iter().rev() {
+                result = Exp::cons(element, result);
+            }
+        }
+        Ok(result)
+    });
+    result.insert("apply", Exp::Apply);
+    result.insert_fn("assoc", |_, list| find_association("assoc", &list, Exp::eq));
+    result.insert_fn("assq", |_, list| find_association("assq", &list, is_eqv));
+    result.insert_fn("assv", |_, list| find_association("assv", &list, is_eqv));
+    result.insert_fn("call-with-current-continuation", call_cc);
+    result.insert_fn("call/cc", call_cc);
+    result.insert_fn("call-with-values", |env_tree, list| {
+        check_arity("call-with-values", &list, 2)?;
+        let values = list[0].invoke(env_tree, vec![])?.into_values();
+        list[1].invoke(env_tree, values)
+    });
+    result.insert_fn("car", |_, list| {
+        check_arity("car", &list, 1)?;
+        list[0].car()
+    });
+    result.insert_fn("cdr", |_, list| {
+        check_arity("cdr", &list, 1)?;
+        list[0].cdr()
+    });
+    result.insert_fn("cons", |_, list| {
+        check_arity("cons", &list, 2)?;
+        let [car, cdr] = <[Exp; 2]>::try_from(list).unwrap();
+        Ok(Exp::cons(car, cdr))
+    });
+    result.insert_fn("ceiling", |_, list| round_number("ceiling", &list, Number::ceil));
+    result.insert_fn("exact?", |_, list| {
+        check_arity("exact?", &list, 1)?;
+        list[0].as_number()?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Integer(..)))))
+    });
+    result.insert_fn("exact->inexact", |_, list| {
+        check_arity("exact->inexact", &list, 1)?;
+        Ok(Exp::num(list[0].as_number()?))
+    });
+    result.insert_fn("expt", |_, list| {
+        check_arity("expt", &list, 2)?;
+        if let (Exp::Atom(Atom::Integer(base)), Exp::Atom(Atom::Integer(power))) =
+            (&list[0], &list[1])
+        {
+            if let Some(n) = u32::try_from(*power).ok().and_then(|p| base.checked_pow(p)) {
+                return Ok(Exp::int(n));
+            }
+        }
+        let negative_base = list[0].is_number() && list[0].as_number()? < 0.0;
+        let fractional_power = list[1].is_number() && list[1].as_number()?.fract() != 0.0;
+        if list[0].is_complex() || list[1].is_complex() || (negative_base && fractional_power) {
+            return Ok(Exp::complex(list[0].as_complex()?.powc(list[1].as_complex()?)));
+        }
+        Ok(Exp::num(list[0].as_number()?.powf(list[1].as_number()?)))
+    });
+    result.insert_fn("=", |_, list| compare_numbers("=", &list, Ordering::is_eq));
+    result.insert_fn("dynamic-wind", dynamic_wind);
+    result.insert_fn("eq?", |_, list| {
+        check_arity("eq?", &list, 2)?;
+        Ok(Exp::bool(is_eqv(&list[0], &list[1])))
+    });
+    result.insert_fn("eqv?", |_, list| {
+        check_arity("eqv?", &list, 2)?;
+        Ok(Exp::bool(is_eqv(&list[0], &list[1])))
+    });
+    result.insert_fn("equal?", |_, list| {
+        check_arity("equal?", &list, 2)?;
+        Ok(Exp::bool(list[0] == list[1]))
+    });
+    result.insert_fn("floor", |_, list| round_number("floor", &list, Number::floor));
+    result.insert_fn("floor/", |_, list| {
+        let (quotient, remainder) = divide_integers("floor/", &list, Rounding::Floor)?;
+        Ok(Exp::values(vec![quotient, remainder]))
+    });
+    result.insert_fn("floor-quotient", |_, list| {
+        Ok(divide_integers("floor-quotient", &list, Rounding::Floor)?.0)
+    });
+    result.insert_fn("floor-remainder", |_, list| {
+        Ok(divide_integers("floor-remainder", &list, Rounding::Floor)?.1)
+    });
+    result.insert_fn("inexact?", |_, list| {
+        check_arity("inexact?", &list, 1)?;
+        list[0].as_number()?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Number(..)))))
+    });
+    result.insert_fn("inexact->exact", |_, list| {
+        check_arity("inexact->exact", &list, 1)?;
+        to_exact("inexact->exact", &list[0])
+    });
+    result.insert_fn("exact", |_, list| {
+        check_arity("exact", &list, 1)?;
+        to_exact("exact", &list[0])
+    });
+    result.insert_fn("inexact", |_, list| {
+        check_arity("inexact", &list, 1)?;
+        Ok(Exp::num(list[0].as_number()?))
+    });
+    result.insert_fn("exact-integer?", |_, list| {
+        check_arity("exact-integer?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Integer(..)))))
+    });
+    result.insert_fn("integer?", |_, list| {
+        check_arity("integer?", &list, 1)?;
+        Ok(Exp::bool(match &list[0] {
+            Exp::Atom(Atom::Integer(..)) => true,
+            Exp::Atom(Atom::Number(n)) => n.is_finite() && n.fract() == 0.0,
+            _ => false,
+        }))
+    });
+    result.insert_fn("length", |_, list| {
+        check_arity("length", &list, 1)?;
+        Ok(Exp::int(list[0].as_exp_list()?.len() as Integer))
+    });
+    result.insert_fn("list", |_, list| Ok(Exp::List(list)));
+    result.insert_fn("list-tail", |_, list| {
+        check_arity("list-tail", &list, 2)?;
+        list_tail(&list[0], &list[1])
+    });
+    result.insert_fn("list-head", |_, list| {
+        check_arity("list-head", &list, 2)?;
+        list_head(&list[0], &list[1])
+    });
+    result.insert_fn("list-copy", |_, mut list| {
+        check_arity("list-copy", &list, 1)?;
+        // Lists are values, so the argument is already a copy.
+        Ok(list.pop().unwrap())
+    });
+    result.insert_fn("list-ref", |_, list| {
+        check_arity("list-ref", &list, 2)?;
+        match list_tail(&list[0], &list[1])? {
+            Exp::List(rest) if rest.is_empty() => {
+                Err(VowError::Custom(format!("index {} out of range", WriteFormat(&list[1]))))
+            }
+            tail => tail.car(),
+        }
+    });
+    result.insert_fn("last-pair", |_, list| {
+        check_arity("last-pair", &list, 1)?;
+        let (mut elements, tail) = split_tail(&list[0]);
+        match elements.pop() {
+            Some(last) => Ok(Exp::cons(last, tail)),
+            None => Err(list[0].type_error("pair")),
+        }
+    });
+    result.insert_fn("iota", |_, list| {
+        check_arity_range("iota", &list, 1, 3)?;
+        let count = usize::try_from(list[0].as_integer()?)
+            .map_err(|_| list[0].type_error("non-negative integer"))?;
+        let start = list.get(1).cloned().unwrap_or(Exp::int(0));
+        let step = list.get(2).cloned().unwrap_or(Exp::int(1));
+        Ok(Exp::List(match (&start, &step) {
+            (Exp::Atom(Atom::Integer(start)), Exp::Atom(Atom::Integer(step))) => (0..count
+                as Integer)
+                .map(|i| match i.checked_mul(*step).and_then(|d| start.checked_add(d)) {
+                    Some(n) => Exp::int(n),
+                    None => Exp::num(*start as Number + i as Number * *step as Number),
+                })
+                .collect(),
+            _ => {
+                let (start, step) = (start.as_number()?, step.as_number()?);
+                (0..count).map(|i| Exp::num(start + i as Number * step)).collect()
+            }
+        }))
+    });
+    result.insert_fn("list?", |_, list| {
+        check_arity("list?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::List(..))))
+    });
+    result.insert_fn("map", |env_tree, list| {
+        check_min_arity("map", &list, 2)?;
+        Ok(Exp::List(
+            zip_lists(&list[1..])?
+                .into_iter()
+                .map(|args| list[0].invoke(env_tree, args))
+                .collect::<Result<_, _>>()?,
+        ))
+    });
+    result.insert_fn("force", |env_tree, list| {
+        check_arity("force", &list, 1)?;
+        match &list[0] {
+            Exp::Promise(promise) => force(env_tree, Rc::clone(promise)),
+            // Forcing a value which is not a promise returns the value.
+            exp => Ok(exp.clone()),
+        }
+    });
+    result.insert_fn("filter", |env_tree, list| {
+        check_arity("filter", &list, 2)?;
+        let mut result = vec![];
+        for exp in list[1].as_exp_list()? {
+            if list[0].invoke(env_tree, vec![exp.clone()])?.is_true() {
+                result.push(exp);
+            }
+        }
+        Ok(Exp::List(result))
+    });
+    result.insert_fn("fold-left", |env_tree, list| {
+        check_min_arity("fold-left", &list, 3)?;
+        let mut result = list[1].clone();
+        for args in zip_lists(&list[2..])? {
+            result = list[0].invoke(env_tree, iter::once(result).chain(args).collect())?;
+        }
+        Ok(result)
+    });
+    result.insert_fn("fold-right", |env_tree, list| {
+        check_min_arity("fold-right", &list, 3)?;
+        let mut result = list[1].clone();
+        for mut args in zip_lists(&list[2..])?.into_iter().rev() {
+            args.push(result);
+            result = list[0].invoke(env_tree, args)?;
+        }
+        Ok(result)
+    });
+    result.insert_fn("for-each", |env_tree, list| {
+        check_min_arity("for-each", &list, 2)?;
+        for args in zip_lists(&list[1..])? {
+            list[0].invoke(env_tree, args)?;
+        }
+        Ok(Exp::List(vec![]))
+    });
+    result.insert_fn("member", |_, list| find_member("member", &list, Exp::eq));
+    result.insert_fn("memq", |_, list| find_member("memq", &list, is_eqv));
+    result.insert_fn("memv", |_, list| find_member("memv", &list, is_eqv));
+    result.insert_fn("make-promise", |_, mut list| {
+        check_arity("make-promise", &list, 1)?;
+        match list.pop().unwrap() {
+            promise @ Exp::Promise(..) => Ok(promise),
+            value => Ok(Exp::Promise(Rc::new(RefCell::new(PromiseState::Forced(value))))),
+        }
+    });
+    result.insert_fn("make-parameter", |env_tree, list| {
+        check_arity_range("make-parameter", &list, 1, 2)?;
+        let converter = list.get(1).cloned();
+        let value = match &converter {
+            Some(converter) => converter.invoke(env_tree, vec![list[0].clone()])?,
+            None => list[0].clone(),
+        };
+        Ok(Exp::Parameter(Rc::new(Parameter { value: RefCell::new(value), converter })))
+    });
+    result.insert_fn("gcd", |_, list| fold_integers(&list, 0, gcd, gcd_inexact));
+    result.insert_fn("lcm", |_, list| {
+        fold_integers(&list, 1, lcm, |a, b| {
+            if a == 0.0 || b == 0.0 {
+                0.0
+            } else {
+                (a * b).abs() / gcd_inexact(a, b)
+            }
+        })
+    });
+    result.insert_fn("max", |_, list| {
+        check_min_arity("max", &list, 1)?;
+        fold_numbers(list[0].clone(), &list, |a, b| Some(a.max(b)), Number::max, None)
+    });
+    result.insert_fn("min", |_, list| {
+        check_min_arity("min", &list, 1)?;
+        fold_numbers(list[0].clone(), &list, |a, b| Some(a.min(b)), Number::min, None)
+    });
+    result.insert_fn("boolean?", |_, list| {
+        check_arity("boolean?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Bool(..)))))
+    });
+    result.insert_fn("boolean=?", |_, list| {
+        check_min_arity("boolean=?", &list, 2)?;
+        let first = list[0].as_bool()?;
+        for exp in &list[1..] {
+            if exp.as_bool()? != first {
+                return Ok(Exp::bool(false));
+            }
+        }
+        Ok(Exp::bool(true))
+    });
+    result.insert_fn("boolean->string", |_, list| {
+        check_arity("boolean->string", &list, 1)?;
+        Ok(Exp::string(WriteFormat(&Exp::bool(list[0].as_bool()?)).to_string()))
+    });
+    result.insert_fn("modulo", |_, list| Ok(divide_integers("modulo", &list, Rounding::Floor)?.1));
+    result.insert_fn("not", |_, list| {
+        check_arity("not", &list, 1)?;
+        Ok(Exp::bool(!list[0].is_true()))
+    });
+    result.insert_fn("null?", |_, list| {
+        check_arity("null?", &list, 1)?;
+        Ok(Exp::bool(matches!(&list[0], Exp::List(l) if l.is_empty())))
+    });
+    result.insert_fn("number?", |_, list| {
+        check_arity("number?", &list, 1)?;
+        Ok(Exp::bool(list[0].is_number() || matches!(list[0], Exp::Atom(Atom::Complex(..)))))
+    });
+    result.insert_fn("zero?", |_, list| {
+        check_arity("zero?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_complex()? == Complex64::new(0.0, 0.0)))
+    });
+    result.insert_fn("positive?", |_, list| {
+        check_arity("positive?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_real()? > 0.0))
+    });
+    result.insert_fn("negative?", |_, list| {
+        check_arity("negative?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_real()? < 0.0))
+    });
+    result.insert_fn("odd?", |_, list| Ok(Exp::bool(is_odd("odd?", &list)?)));
+    result.insert_fn("even?", |_, list| Ok(Exp::bool(!is_odd("even?", &list)?)));
+    result.insert_fn("pair?", |_, list| {
+        check_arity("pair?", &list, 1)?;
+        Ok(Exp::bool(match &list[0] {
+            Exp::List(l) => !l.is_empty(),
+            Exp::Pair(..) => true,
+            _ => false,
+        }))
+    });
+    result.insert_fn("promise?", |_, list| {
+        check_arity("promise?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Promise(..))))
+    });
+    result.insert_fn("print", |_, list| {
+        println!("{:?}", list);
+        Ok(Exp::List(vec![]))
+    });
+    result.insert_fn("procedure?", |_, list| {
+        check_arity("procedure?", &list, 1)?;
+        Ok(Exp::bool(list[0].is_procedure()))
+    });
+    result.insert_fn("quotient", |_, list| {
+        Ok(divide_integers("quotient", &list, Rounding::Truncate)?.0)
+    });
+    result.insert_fn("reduce", |env_tree, list| {
+        check_arity("reduce", &list, 3)?;
+        let mut elements = list[2].as_exp_list()?.into_iter();
+        let Some(mut result) = elements.next() else {
+            return Ok(list[1].clone());
+        };
+        for exp in elements {
+            result = list[0].invoke(env_tree, vec![exp, result])?;
+        }
+        Ok(result)
+    });
+    result.insert_fn("remainder", |_, list| {
+        Ok(divide_integers("remainder", &list, Rounding::Truncate)?.1)
+    });
+    result.insert_fn("round", |_, list| round_number("round", &list, Number::round_ties_even));
+    result.insert_fn("sort", |env_tree, list| {
+        check_arity("sort", &list, 2)?;
+        match &list[0] {
+            Exp::Vector(vector) => {
+                Ok(Exp::vector(merge_sort(env_tree, vector.borrow().clone(), &list[1])?))
+            }
+            exp => Ok(Exp::List(merge_sort(env_tree, exp.as_exp_list()?, &list[1])?)),
+        }
+    });
+    result.insert_fn("sort!", |env_tree, list| {
+        check_arity("sort!", &list, 2)?;
+        let vector = list[0].as_vector()?;
+        let sorted = merge_sort(env_tree, vector.borrow().clone(), &list[1])?;
+        *vector.borrow_mut() = sorted;
+        Ok(list[0].clone())
+    });
+    result.insert_fn("list-sort", |env_tree, list| {
+        check_arity("list-sort", &list, 2)?;
+        Ok(Exp::List(merge_sort(env_tree, list[1].as_exp_list()?, &list[0])?))
+    });
+    result.insert_fn("values", |_, list| Ok(Exp::values(list)));
+    result.insert_fn("symbol?", |_, list| {
+        check_arity("symbol?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Symbol(..)))))
+    });
+    result.insert_fn("symbol->string", |_, list| {
+        check_arity("symbol->string", &list, 1)?;
+        Ok(Exp::string(list[0].as_symbol()?))
+    });
+    result.insert_fn("string->symbol", |_, list| {
+        check_arity("string->symbol", &list, 1)?;
+        Ok(Exp::symbol(list[0].as_string()?))
+    });
+    result.insert_fn("gensym", |_, list| {
+        check_arity_range("gensym", &list, 0, 1)?;
+        let prefix = match list.first() {
+            Some(Exp::Atom(Atom::Symbol(s))) => s.clone(),
+            Some(exp) => exp.as_string()?,
+            None => "g".to_string(),
+        };
+        let id = NEXT_GENSYM_ID.fetch_add(1, AtomicOrdering::Relaxed);
+        Ok(Exp::symbol(format!("{GENSYM_PREFIX}{prefix}{id}")))
+    });
+    result.insert_fn("truncate", |_, list| round_number("truncate", &list, Number::trunc));
+    result.insert_fn("truncate/", |_, list| {
+        let (quotient, remainder) = divide_integers("truncate/", &list, Rounding::Truncate)?;
+        Ok(Exp::values(vec![quotient, remainder]))
+    });
+    result.insert_fn("truncate-quotient", |_, list| {
+        Ok(divide_integers("truncate-quotient", &list, Rounding::Truncate)?.0)
+    });
+    result.insert_fn("truncate-remainder", |_, list| {
+        Ok(divide_integers("truncate-remainder", &list, Rounding::Truncate)?.1)
+    });
+    result.insert_fn("floor->exact", |_, list| {
+        to_exact("floor->exact", &round_number("floor->exact", &list, Number::floor)?)
+    });
+    result.insert_fn("ceiling->exact", |_, list| {
+        to_exact("ceiling->exact", &round_number("ceiling->exact", &list, Number::ceil)?)
+    });
+    result.insert_fn("round->exact", |_, list| {
+        to_exact("round->exact", &round_number("round->exact", &list, Number::round_ties_even)?)
+    });
+    result.insert_fn("truncate->exact", |_, list| {
+        to_exact("truncate->exact", &round_number("truncate->exact", &list, Number::trunc)?)
+    });
+    result.insert_fn("exact-integer-sqrt", |_, list| {
+        check_arity("exact-integer-sqrt", &list, 1)?;
+        let n = match list[0] {
+            Exp::Atom(Atom::Integer(n)) if n >= 0 => n,
+            _ => return Err(list[0].type_error("non-negative exact integer")),
+        };
+        let root = n.isqrt();
+        Ok(Exp::values(vec![Exp::int(root), Exp::int(n - root * root)]))
+    });
+    result.insert("pi", Exp::Atom(Atom::Number(consts::PI)));
+    result.insert("e", Exp::Atom(Atom::Number(consts::E)));
+    add_math_functions(&mut result);
+    add_complex_functions(&mut result);
+    add_char_functions(&mut result);
+    add_vector_functions(&mut result);
+    add_bytevector_functions(&mut result);
+    add_hash_table_functions(&mut result);
+    add_string_functions(&mut result);
+    add_port_functions(&mut result);
+    add_exception_functions(&mut result);
+    result.insert_fn("load", |env_tree, list| {
+        check_arity("load", &list, 1)?;
+        load_inner(env_tree, &list[0].as_string()?)
+    });
+    result.insert_fn("eval", |env_tree, list| {
+        check_arity_range("eval", &list, 1, 2)?;
+        let env_id = match list.get(1) {
+            Some(exp) => exp.as_environment(env_tree)?,
+            None => interaction_environment("eval")?,
+        };
+        eval_inner(list[0].clone(), env_tree, env_id)
+    });
+    result.insert_fn("interaction-environment", |_, list| {
+        check_arity("interaction-environment", &list, 0)?;
+        interaction_environment("interaction-environment").map(Exp::Environment)
+    });
+    result.insert_fn("scheme-report-environment", |env_tree, list| {
+        check_arity("scheme-report-environment", &list, 1)?;
+        match list[0].as_integer()? {
+            5 | 7 => Ok(Exp::Environment(new_report_env(env_tree)?)),
+            _ => Err(list[0].type_error("report version 5 or 7")),
+        }
+    });
+    result.insert_fn("environment?", |_, list| {
+        check_arity("environment?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Environment(..))))
+    });
+    result.insert_fn("env-ref", |env_tree, list| {
+        check_arity("env-ref", &list, 2)?;
+        resolve(env_tree, list[0].as_environment(env_tree)?, &list[1].as_symbol()?)
+    });
+    result.insert_fn("env-define!", |env_tree, list| {
+        check_arity("env-define!", &list, 3)?;
+        let env_id = list[0].as_environment(env_tree)?;
+        env_tree[env_id].insert(list[1].as_symbol()?, list[2].clone());
+        Ok(Exp::List(vec![]))
+    });
+    result.insert_fn("env-set!", |env_tree, list| {
+        check_arity("env-set!", &list, 3)?;
+        let env_id = list[0].as_environment(env_tree)?;
+        let (found, symbol) = lookup(env_tree, env_id, &list[1].as_symbol()?)?;
+        env_tree[found].insert(symbol, list[2].clone());
+        Ok(Exp::List(vec![]))
+    });
+    result.insert_fn("env-symbols", |env_tree, list| {
+        check_arity("env-symbols", &list, 1)?;
+        let env_id = list[0].as_environment(env_tree)?;
+        Ok(Exp::List(defined_symbols(env_tree, env_id).into_iter().map(Exp::symbol).collect()))
+    });
+    result.insert_fn("command-line", |_, list| {
+        check_arity("command-line", &list, 0)?;
+        Ok(Exp::List(COMMAND_LINE.with(|args| args.borrow().iter().map(Exp::string).collect())))
+    });
+    result.insert_fn("exit", |_, list| {
+        check_arity_range("exit", &list, 0, 1)?;
+        let code = match list.first() {
+            None | Some(Exp::Atom(Atom::Bool(true))) => 0,
+            Some(Exp::Atom(Atom::Bool(false))) => 1,
+            Some(code) => i32::try_from(code.as_integer()?)
+                .map_err(|_| VowError::Custom(format!("exit: invalid status {code}")))?,
+        };
+        Err(VowError::Exit(code))
+    });
+    result.insert_fn("set-recursion-limit!", |_, list| {
+        check_arity("set-recursion-limit!", &list, 1)?;
+        let limit = usize::try_from(list[0].as_integer()?)
+            .ok()
+            .filter(|&limit| limit > 0)
+            .ok_or_else(|| list[0].type_error("positive integer"))?;
+        set_recursion_limit(limit);
+        Ok(Exp::List(vec![]))
+    });
+    result.insert_fn("runtime", |_, list| {
+        check_arity("runtime", &list, 0)?;
+        Ok(Exp::int(START.elapsed().as_millis() as Integer))
+    });
+    result.insert_fn("cpu-time", |_, list| {
+        check_arity("cpu-time", &list, 0)?;
+        // The standard library has no portable way to read the processor time
+        // used by the process, so this is the wall clock time.
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Ok(Exp::int(now.as_millis() as Integer))
+    });
+    result
+}
+
+/// Calls the procedure `list[0]` with an escape continuation which, when
+/// invoked with a value, immediately returns that value from `call/cc`.
+///
+/// Continuations cannot be re-entered: invoking one after its `call/cc` has
+/// returned is an error.
+fn call_cc(env_tree: &mut EnvTree, list: List) -> Result<Exp, VowError> {
+    thread_local! {
+        static NEXT_CONTINUATION_ID: Cell<u64> = const { Cell::new(0) };
+    }
+    check_arity("call/cc", &list, 1)?;
+    let id = NEXT_CONTINUATION_ID.with(|next| next.replace(next.get() + 1));
+    match list[0].invoke(env_tree, vec![Exp::Continuation(id)]) {
+        Err(VowError::Escape(escape)) if escape.id == id => Ok(*escape.value),
+        result => result,
+    }
+}
+
+/// Calls the thunks `before`, `thunk` and `after` in order, returning the value
+/// of `thunk`.
+///
+/// Escape continuations unwind the Rust stack as errors, so `after` runs
+/// whenever control leaves `thunk`, whether it returns normally, raises an
+/// error or escapes to an outer continuation. Since continuations cannot be
+/// re-entered, control never needs to re-enter `thunk`, and the Rust call stack
+/// serves as the stack of active winders.
+fn dynamic_wind(env_tree: &mut EnvTree, list: List) -> Result<Exp, VowError> {
+    check_arity("dynamic-wind", &list, 3)?;
+    list[0].invoke(env_tree, vec![])?;
+    let result = list[1].invoke(env_tree, vec![]);
+    // An error or escape from `after` replaces the outcome of `thunk`.
+    list[2].invoke(env_tree, vec![])?;
+    result
+}
+
+/// An entry in the stack of exception handlers.
+#[derive(Clone)]
+enum ExceptionHandler {
+    /// A handler procedure installed by `with-exception-handler`.
+    Procedure(Exp),
+    /// A `guard` form, which handles exceptions after unwinding to it.
+    Guard,
+}
+
+thread_local! {
+    /// The exception handlers of the current dynamic environment, innermost
+    /// last.
+    static EXCEPTION_HANDLERS: RefCell<Vec<ExceptionHandler>> = const { RefCell::new(vec![]) };
+}
+
+/// Calls `f` with `handlers` as the current exception handlers, restoring the
+/// previous handlers afterwards.
+fn with_exception_handlers<R>(handlers: Vec<ExceptionHandler>, f: impl FnOnce() -> R) -> R {
+    let saved = EXCEPTION_HANDLERS.with(|current| current.replace(handlers));
+    let result = f();
+    EXCEPTION_HANDLERS.with(|current| current.replace(saved));
+    result
+}
+
+/// Raises `obj` as an exception.
+///
+/// The current handler is called with `obj` in the dynamic environment of the
+/// raise, except that the handlers in effect are those which were current
+/// when it was installed. If the handler returns, its value is returned for
+/// `raise-continuable`, and otherwise the next outer handler is called in
+/// turn. Once there are no more handlers to call, or a `guard` is reached,
+/// the exception unwinds the stack as a [VowError::Raised] error.
+fn raise(env_tree: &mut EnvTree, obj: Exp, continuable: bool) -> Result<Exp, VowError> {
+    let handlers = EXCEPTION_HANDLERS.with(|current| current.borrow().clone());
+    for depth in (0..handlers.len()).rev() {
+        let ExceptionHandler::Procedure(handler) = &handlers[depth] else {
+            break;
+        };
+        let value = with_exception_handlers(handlers[..depth].to_vec(), || {
+            handler.invoke(env_tree, vec![obj.clone()])
+        })?;
+        if continuable {
+            return Ok(value);
+        }
+    }
+    Err(VowError::Raised(Raised { value: Box::new(obj) }))
+}
+
+/// Returns the object describing `error` which is passed to exception
+/// handlers, or `None` if `error` is an escape to a continuation.
+fn condition(error: &VowError) -> Option<Exp> {
+    match error.unlocated() {
+        VowError::Escape(_) | VowError::Exit(_) | VowError::TimeLimitExceeded(_) => None,
+        VowError::Raised(raised) => Some(raised.value.as_ref().clone()),
+        error => Some(Exp::ErrorObject { message: error.to_string(), irritants: vec![] }),
+    }
+}
+
+/// Calls the thunk `list[1]` with `list[0]` installed as the current exception
+/// handler.
+///
+/// Errors detected by the interpreter, rather than raised with `raise`, are
+/// passed to the handler as they unwind through this call, after which they
+/// continue to unwind.
+fn with_exception_handler(env_tree: &mut EnvTree, list: List) -> Result<Exp, VowError> {
+    check_arity("with-exception-handler", &list, 2)?;
+    let outer = EXCEPTION_HANDLERS.with(|current| current.borrow().clone());
+    let mut handlers = outer.clone();
+    handlers.push(ExceptionHandler::Procedure(list[0].clone()));
+    let result = with_exception_handlers(handlers, || list[1].invoke(env_tree, vec![]));
+    match result {
+        Err(error)
+            if !matches!(
+                error.unlocated(),
+                VowError::Escape(..)
+                    | VowError::Raised(..)
+                    | VowError::Exit(..)
+                    | VowError::TimeLimitExceeded(..)
+            ) =>
+        {
+            let obj = condition(&error).unwrap();
+            with_exception_handlers(outer, || list[0].invoke(env_tree, vec![obj]))?;
+            Err(error)
+        }
+        result => result,
+    }
+}
+
+/// Evaluates `(guard (var clause ...) body ...)`.
+///
+/// If the body raises an exception, it is bound to `var` and the clauses are
+/// evaluated as in `cond`. If no clause matches, the exception is raised
+/// again in the dynamic environment of the `guard`.
+fn eval_guard(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let spec = list[1].as_exp_list()?;
+    let Some((var, clauses)) = spec.split_first() else {
+        return Err(list[1].type_error("guard clauses"));
+    };
+    let var = var.as_symbol()?;
+    let mut handlers = EXCEPTION_HANDLERS.with(|current| current.borrow().clone());
+    handlers.push(ExceptionHandler::Guard);
+    let error = match with_exception_handlers(handlers, || {
+        eval_inner(sequence(&list[2..]), env_tree, env_id)
+    }) {
+        Ok(value) => return Ok(EvalResult::Value(value)),
+        Err(error) => error,
+    };
+    let Some(obj) = condition(&error) else {
+        return Err(error);
+    };
+    let guard_env = Env::insert_into(env_tree, vec![var], vec![obj.clone()], Some(env_id));
+    match eval_cond_clauses(clauses, env_tree, guard_env)? {
+        Some(result) => Ok(result),
+        None if matches!(error.unlocated(), VowError::Raised(..)) => {
+            raise(env_tree, obj, false).map(EvalResult::Value)
+        }
+        None => Err(error),
+    }
+}
+
+fn add_math_functions(env: &mut Env) {
+    env.insert_fn("sin", |_, list| apply_float("sin", &list, Number::sin));
+    env.insert_fn("cos", |_, list| apply_float("cos", &list, Number::cos));
+    env.insert_fn("tan", |_, list| apply_float("tan", &list, Number::tan));
+    env.insert_fn("asin", |_, list| apply_float("asin", &list, Number::asin));
+    env.insert_fn("acos", |_, list| apply_float("acos", &list, Number::acos));
+    env.insert_fn("atan", |_, list| {
+        check_arity_range("atan", &list, 1, 2)?;
+        match list.get(1) {
+            Some(x) => Ok(Exp::num(list[0].as_number()?.atan2(x.as_number()?))),
+            None => Ok(Exp::num(list[0].as_number()?.atan())),
+        }
+    });
+    env.insert_fn("exp", |_, list| apply_float("exp", &list, Number::exp));
+    env.insert_fn("log", |_, list| {
+        check_arity_range("log", &list, 1, 2)?;
+        match list.get(1) {
+            Some(base) => Ok(Exp::num(list[0].as_number()?.log(base.as_number()?))),
+            None => Ok(Exp::num(list[0].as_number()?.ln())),
+        }
+    });
+    env.insert_fn("sqrt", |_, list| {
+        check_arity("sqrt", &list, 1)?;
+        if let Exp::Atom(Atom::Integer(n)) = list[0] {
+            let root = n.unsigned_abs().isqrt();
+            if root * root == n.unsigned_abs() {
+                let root = root as Integer;
+                return Ok(if n < 0 {
+                    Exp::Atom(Atom::Complex(Complex64::new(0.0, root as Number)))
+                } else {
+                    Exp::int(root)
+                });
+            }
+        }
+        if let Exp::Atom(Atom::Complex(n)) = list[0] {
+            return Ok(Exp::complex(n.sqrt()));
+        }
+        let n = list[0].as_number()?;
+        if n < 0.0 {
+            Ok(Exp::Atom(Atom::Complex(Complex64::new(0.0, (-n).sqrt()))))
+        } else {
+            Ok(Exp::num(n.sqrt()))
+        }
+    });
+}
+
+fn add_complex_functions(env: &mut Env) {
+    env.insert_fn("complex?", |_, list| {
+        check_arity("complex?", &list, 1)?;
+        Ok(Exp::bool(list[0].is_number() || list[0].is_complex()))
+    });
+    env.insert_fn("real?", |_, list| {
+        check_arity("real?", &list, 1)?;
+        Ok(Exp::bool(list[0].is_number()))
+    });
+    env.insert_fn("make-rectangular", |_, list| {
+        check_arity("make-rectangular", &list, 2)?;
+        Ok(Exp::complex(Complex64::new(list[0].as_number()?, list[1].as_number()?)))
+    });
+    env.insert_fn("make-polar", |_, list| {
+        check_arity("make-polar", &list, 2)?;
+        Ok(Exp::complex(Complex64::from_polar(list[0].as_number()?, list[1].as_number()?)))
+    });
+    env.insert_fn("real-part", |_, list| {
+        check_arity("real-part", &list, 1)?;
+        match &list[0] {
+            Exp::Atom(Atom::Complex(n)) => Ok(Exp::num(n.re)),
+            exp => {
+                exp.as_number()?;
+                Ok(exp.clone())
+            }
+        }
+    });
+    env.insert_fn("imag-part", |_, list| {
+        check_arity("imag-part", &list, 1)?;
+        match &list[0] {
+            Exp::Atom(Atom::Complex(n)) => Ok(Exp::num(n.im)),
+            exp => {
+                exp.as_number()?;
+                Ok(Exp::int(0))
+            }
+        }
+    });
+    env.insert_fn("magnitude", |_, list| {
+        check_arity("magnitude", &list, 1)?;
+        match &list[0] {
+            Exp::Atom(Atom::Integer(n)) if *n != Integer::MIN => Ok(Exp::int(n.abs())),
+            exp => Ok(Exp::num(exp.as_complex()?.norm())),
+        }
+    });
+    env.insert_fn("angle", |_, list| {
+        check_arity("angle", &list, 1)?;
+        Ok(Exp::num(list[0].as_complex()?.arg()))
+    });
+}
+
+/// Applies a one-argument floating point function to the number in `list`.
+fn apply_float(name: &str, list: &[Exp], float_op: fn(Number) -> Number) -> Result<Exp, VowError> {
+    check_arity(name, list, 1)?;
+    Ok(Exp::num(float_op(list[0].as_number()?)))
+}
+
+fn add_exception_functions(env: &mut Env) {
+    env.insert_fn("raise", |env_tree, mut list| {
+        check_arity("raise", &list, 1)?;
+        raise(env_tree, list.pop().unwrap(), false)
+    });
+    env.insert_fn("raise-continuable", |env_tree, mut list| {
+        check_arity("raise-continuable", &list, 1)?;
+        raise(env_tree, list.pop().unwrap(), true)
+    });
+    env.insert_fn("error", |env_tree, list| {
+        check_min_arity("error", &list, 1)?;
+        let message = match &list[0] {
+            Exp::Atom(Atom::String(s)) => s.borrow().clone(),
+            exp => exp.to_string(),
+        };
+        raise(env_tree, Exp::ErrorObject { message, irritants: list[1..].to_vec() }, false)
+    });
+    env.insert_fn("with-exception-handler", with_exception_handler);
+    env.insert_fn("error-object?", |_, list| {
+        check_arity("error-object?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::ErrorObject { .. })))
+    });
+    env.insert_fn("error-object-message", |_, list| {
+        check_arity("error-object-message", &list, 1)?;
+        match &list[0] {
+            Exp::ErrorObject { message, .. } => Ok(Exp::string(message.clone())),
+            exp => Err(exp.type_error("error object")),
+        }
+    });
+    env.insert_fn("error-object-irritants", |_, list| {
+        check_arity("error-object-irritants", &list, 1)?;
+        match &list[0] {
+            Exp::ErrorObject { irritants, .. } => Ok(Exp::List(irritants.clone())),
+            exp => Err(exp.type_error("error object")),
+        }
+    });
+}
+
+/// Returns true if `relation` holds for every consecutive pair of characters
+/// in `list`.
+fn compare_chars(
+    name: &str,
+    list: &[Exp],
+    relation: fn(char, char) -> bool,
+) -> Result<Exp, VowError> {
+    check_min_arity(name, list, 2)?;
+    let chars = list.iter().map(Exp::as_char).collect::<Result<Vec<_>, _>>()?;
+    Ok(Exp::bool(chars.windows(2).all(|pair| relation(pair[0], pair[1]))))
+}
+
+/// Reads the optional `start` and `end` arguments found at `list[index]` and
+/// `list[index + 1]`, which default to the whole of a sequence of length `len`.
+fn optional_range(list: &[Exp], index: usize, len: usize) -> Result<(usize, usize), VowError> {
+    let start = match list.get(index) {
+        Some(exp) => exp.as_index(len, true)?,
+        None => 0,
+    };
+    let end = match list.get(index + 1) {
+        Some(exp) => exp.as_index(len, true)?,
+        None => len,
+    };
+    if start > end {
+        return Err(VowError::Custom(format!("invalid range {start} to {end}")));
+    }
+    Ok((start, end))
+}
+
+/// Returns an error unless between `min` and `max` arguments were provided to
+/// the function `name`.
+fn check_arity_range(name: &str, list: &[Exp], min: usize, max: usize) -> Result<(), VowError> {
+    check_min_arity(name, list, min)?;
+    if list.len() > max {
+        return Err(VowError::ArityMismatch {
+            name: name.to_string(),
+            expected: max,
+            got: list.len(),
+        });
+    }
+    Ok(())
+}
+
+thread_local! {
+    /// The number of traced procedure calls which have not yet returned.
+    static TRACE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Replaces the procedure bound to `symbol` with one which prints each call to
+/// it and the value it returns.
+pub fn trace(env_tree: &mut EnvTree, env_id: EnvId, symbol: &str) -> Result<(), VowError> {
+    let (found, symbol) = lookup(env_tree, env_id, symbol)?;
+    let env = env_tree.get_mut(found).unwrap();
+    match env.get(&symbol) {
+        Some(Exp::Traced(..)) => Ok(()),
+        Some(procedure) if procedure.is_procedure() => {
+            env.insert(symbol.clone(), Exp::Traced(symbol, Box::new(procedure)));
+            Ok(())
+        }
+        Some(value) => Err(value.type_error("procedure")),
+        None => Err(VowError::UndefinedSymbol(symbol)),
+    }
+}
+
+/// Restores the procedure bound to `symbol` which was replaced by [trace].
+pub fn untrace(env_tree: &mut EnvTree, env_id: EnvId, symbol: &str) -> Result<(), VowError> {
+    let (found, symbol) = lookup(env_tree, env_id, symbol)?;
+    let env = env_tree.get_mut(found).unwrap();
+    match env.get(&symbol) {
+        Some(Exp::Traced(_, procedure)) => {
+            env.insert(symbol, *procedure);
+            Ok(())
+        }
+        _ => Err(VowError::Custom(format!("{symbol} is not traced"))),
+    }
+}
+
+/// Calls a traced procedure, printing the call and its result to standard
+/// error indented by the number of enclosing traced calls.
+fn call_traced(
+    env_tree: &mut EnvTree,
+    name: &str,
+    procedure: &Exp,
+    args: List,
+) -> Result<Exp, VowError> {
+    let depth = TRACE_DEPTH.with(Cell::get);
+    let indent = "  ".repeat(depth);
+    let arguments: String = args.iter().map(|arg| format!(" {}", WriteFormat(arg))).collect();
+    eprintln!("{indent}[CALL {name}{arguments}]");
+    TRACE_DEPTH.with(|d| d.set(depth + 1));
+    let result = procedure.invoke(env_tree, args);
+    TRACE_DEPTH.with(|d| d.set(depth));
+    let value = result?;
+    eprintln!("{indent}[RETURN {name} {}]", WriteFormat(&value));
+    Ok(value)
+}
+
+fn add_vector_functions(env: &mut Env) {
+    env.insert_fn("make-vector", |_, list| {
+        check_arity_range("make-vector", &list, 1, 2)?;
+        let len = usize::try_from(list[0].as_integer()?)
+            .map_err(|_| list[0].type_error("non-negative integer"))?;
+        let fill = list.get(1).cloned().unwrap_or(Exp::int(0));
+        Ok(Exp::vector(vec![fill; len]))
+    });
+    env.insert_fn("vector", |_, list| Ok(Exp::vector(list)));
+    env.insert_fn("vector-map", |env_tree, list| {
+        check_min_arity("vector-map", &list, 2)?;
+        Ok(Exp::vector(
+            zip_with(&list[1..], vector_elements)?
+                .into_iter()
+                .map(|args| list[0].invoke(env_tree, args))
+                .collect::<Result<_, _>>()?,
+        ))
+    });
+    env.insert_fn("vector-map!", |env_tree, list| {
+        vector_map_in_place("vector-map!", env_tree, &list)
+    });
+    env.insert_fn("vector-for-each!", |env_tree, list| {
+        vector_map_in_place("vector-for-each!", env_tree, &list)
+    });
+    env.insert_fn("vector-for-each", |env_tree, list| {
+        check_min_arity("vector-for-each", &list, 2)?;
+        for args in zip_with(&list[1..], vector_elements)? {
+            list[0].invoke(env_tree, args)?;
+        }
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("vector?", |_, list| {
+        check_arity("vector?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Vector(..))))
+    });
+    env.insert_fn("vector-length", |_, list| {
+        check_arity("vector-length", &list, 1)?;
+        Ok(Exp::int(list[0].as_vector()?.borrow().len() as Integer))
+    });
+    env.insert_fn("vector-ref", |_, list| {
+        check_arity("vector-ref", &list, 2)?;
+        let vector = list[0].as_vector()?;
+        let vector = vector.borrow();
+        Ok(vector[list[1].as_index(vector.len(), false)?].clone())
+    });
+    env.insert_fn("vector-set!", |_, mut list| {
+        check_arity("vector-set!", &list, 3)?;
+        let vector = list[0].as_vector()?;
+        let index = list[1].as_index(vector.borrow().len(), false)?;
+        vector.borrow_mut()[index] = list.pop().unwrap();
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("vector->list", |_, list| {
+        check_arity_range("vector->list", &list, 1, 3)?;
+        let vector = list[0].as_vector()?;
+        let vector = vector.borrow();
+        let (start, end) = optional_range(&list, 1, vector.len())?;
+        Ok(Exp::List(vector[start..end].to_vec()))
+    });
+    env.insert_fn("list->vector", |_, list| {
+        check_arity("list->vector", &list, 1)?;
+        Ok(Exp::vector(list[0].as_exp_list()?))
+    });
+    env.insert_fn("vector->string", |_, list| {
+        check_arity_range("vector->string", &list, 1, 3)?;
+        let vector = list[0].as_vector()?;
+        let vector = vector.borrow();
+        let (start, end) = optional_range(&list, 1, vector.len())?;
+        Ok(Exp::string(vector[start..end].iter().map(Exp::as_char).collect::<Result<String, _>>()?))
+    });
+    env.insert_fn("string->vector", |_, list| {
+        check_arity_range("string->vector", &list, 1, 3)?;
+        let chars = string_chars(&list[0])?;
+        let (start, end) = optional_range(&list, 1, chars.len())?;
+        Ok(Exp::vector(chars[start..end].to_vec()))
+    });
+    env.insert_fn("vector-fill!", |_, list| {
+        check_arity_range("vector-fill!", &list, 2, 4)?;
+        let vector = list[0].as_vector()?;
+        let mut vector = vector.borrow_mut();
+        let (start, end) = optional_range(&list, 2, vector.len())?;
+        vector[start..end].fill(list[1].clone());
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("vector-copy", |_, list| {
+        check_arity_range("vector-copy", &list, 1, 3)?;
+        let vector = list[0].as_vector()?;
+        let vector = vector.borrow();
+        let (start, end) = optional_range(&list, 1, vector.len())?;
+        Ok(Exp::vector(vector[start..end].to_vec()))
+    });
+    env.insert_fn("vector-copy!", |_, list| {
+        check_arity_range("vector-copy!", &list, 3, 5)?;
+        let to = list[0].as_vector()?;
+        let at = list[1].as_index(to.borrow().len(), true)?;
+        // Copy out of the source first, since it may be the same vector.
+        let elements = {
+            let from = list[2].as_vector()?;
+            let from = from.borrow();
+            let (start, end) = optional_range(&list, 3, from.len())?;
+            from[start..end].to_vec()
+        };
+        let mut to = to.borrow_mut();
+        if at + elements.len() > to.len() {
+            return Err(VowError::Custom("vector-copy!: destination is too small".to_string()));
+        }
+        to[at..at + elements.len()].clone_from_slice(&elements);
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("vector-append", |_, list| {
+        let mut result = vec![];
+        for exp in &list {
+            result.extend(exp.as_vector()?.borrow().iter().cloned());
+        }
+        Ok(Exp::vector(result))
+    });
+}
+
+/// Implements `(vector-map! proc v1 v2 ...)`, which stores the result of
+/// applying `proc` element-wise across the vectors into `v1`, and its alias
+/// `vector-for-each!`, reporting errors under `name`.
+fn vector_map_in_place(name: &str, env_tree: &mut EnvTree, list: &[Exp]) -> Result<Exp, VowError> {
+    check_min_arity(name, list, 2)?;
+    let vector = list[1].as_vector()?;
+    for (i, args) in zip_with(&list[1..], vector_elements)?.into_iter().enumerate() {
+        let value = list[0].invoke(env_tree, args)?;
+        vector.borrow_mut()[i] = value;
+    }
+    Ok(Exp::List(vec![]))
+}
+
+/// A hash table key, derived from a value according to the equivalence used by
+/// its table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HashKey {
+    Symbol(Symbol),
+    Integer(Integer),
+    /// The bits of an inexact number.
+    Number(u64),
+    Complex(u64, u64),
+    Bool(Bool),
+    Char(char),
+    Eof,
+    /// The contents of a string, in a table compared with `equal?`.
+    String(String),
+    /// The elements of a list. Lists are always compared by their contents,
+    /// since they are copied rather than shared.
+    List(Vec<HashKey>),
+    /// The elements and final cdr of an improper list.
+    Pair(Vec<HashKey>, Box<HashKey>),
+    /// The contents of a vector, in a table compared with `equal?`.
+    Vector(Vec<HashKey>),
+    /// The contents of a bytevector, in a table compared with `equal?`.
+    Bytevector(Vec<u8>),
+    /// An object compared by identity, identified by its address.
+    Identity(usize),
+}
+
+impl HashKey {
+    /// Returns the key for `exp`, comparing strings and vectors by their
+    /// contents if `equal` is true and by identity otherwise.
+    fn new(exp: &Exp, equal: bool) -> Result<Self, VowError> {
+        let keys = |elements: &[Exp]| {
+            elements.iter().map(|e| HashKey::new(e, equal)).collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match exp {
+            Exp::Atom(Atom::Symbol(s)) => HashKey::Symbol(s.clone()),
+            Exp::Atom(Atom::Integer(n)) => HashKey::Integer(*n),
+            Exp::Atom(Atom::Number(n)) => HashKey::Number(n.to_bits()),
+            Exp::Atom(Atom::Complex(n)) => HashKey::Complex(n.re.to_bits(), n.im.to_bits()),
+            Exp::Atom(Atom::Bool(b)) => HashKey::Bool(*b),
+            Exp::Atom(Atom::Char(c)) => HashKey::Char(*c),
+            Exp::Atom(Atom::Eof) => HashKey::Eof,
+            Exp::Atom(Atom::String(s)) if equal => HashKey::String(s.borrow().clone()),
+            Exp::Atom(Atom::String(s)) => HashKey::Identity(Rc::as_ptr(s) as usize),
+            Exp::List(list) => HashKey::List(keys(list)?),
+            Exp::Pair(..) => {
+                let (elements, tail) = split_tail(exp);
+                HashKey::Pair(keys(&elements)?, Box::new(HashKey::new(&tail, equal)?))
+            }
+            Exp::Vector(v) if equal => HashKey::Vector(keys(&v.borrow())?),
+            Exp::Vector(v) => HashKey::Identity(Rc::as_ptr(v) as usize),
+            Exp::Bytevector(v) if equal => HashKey::Bytevector(v.borrow().clone()),
+            Exp::Bytevector(v) => HashKey::Identity(Rc::as_ptr(v) as usize),
+            Exp::Record { fields, .. } => HashKey::Identity(Rc::as_ptr(fields) as usize),
+            Exp::RecordType(t) => HashKey::Identity(Rc::as_ptr(t) as usize),
+            Exp::Promise(p) => HashKey::Identity(Rc::as_ptr(p) as usize),
+            Exp::Parameter(p) => HashKey::Identity(Rc::as_ptr(p) as usize),
+            Exp::HashTable(t) => HashKey::Identity(Rc::as_ptr(t) as usize),
+            Exp::InputPort(port) => HashKey::Identity(Rc::as_ptr(&port.0) as usize),
+            Exp::OutputPort(port) => HashKey::Identity(Rc::as_ptr(&port.0) as usize),
+            _ => return Err(exp.type_error("hashable key")),
+        })
+    }
+}
+
+/// The contents of a hash table.
+#[derive(Clone, Debug)]
+pub struct HashTable {
+    /// Whether keys are compared with `equal?` rather than `eqv?`.
+    pub equal: bool,
+    /// Maps the key of each entry to its original key and its value.
+    pub entries: HashMap<HashKey, (Exp, Exp)>,
+}
+
+impl HashTable {
+    pub fn new(equal: bool) -> Self {
+        Self { equal, entries: HashMap::new() }
+    }
+
+    pub fn get(&self, key: &Exp) -> Result<Option<Exp>, VowError> {
+        Ok(self.entries.get(&HashKey::new(key, self.equal)?).map(|(_, value)| value.clone()))
+    }
+
+    pub fn insert(&mut self, key: Exp, value: Exp) -> Result<(), VowError> {
+        self.entries.insert(HashKey::new(&key, self.equal)?, (key, value));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &Exp) -> Result<(), VowError> {
+        self.entries.remove(&HashKey::new(key, self.equal)?);
+        Ok(())
+    }
+}
+
+/// Returns the value of `key` in `table`, or the result of calling the thunk
+/// `failure` if it is not present. It is an error for `key` to be missing if
+/// no `failure` thunk was provided.
+fn hash_table_ref(
+    env_tree: &mut EnvTree,
+    name: &str,
+    table: &Exp,
+    key: &Exp,
+    failure: Option<&Exp>,
+) -> Result<Exp, VowError> {
+    // The table is not borrowed while calling `failure`, which may modify it.
+    let value = table.as_hash_table()?.borrow().get(key)?;
+    match (value, failure) {
+        (Some(value), _) => Ok(value),
+        (None, Some(failure)) => failure.invoke(env_tree, vec![]),
+        (None, None) => {
+            Err(VowError::Custom(format!("{name}: key {} not found", WriteFormat(key))))
+        }
+    }
+}
+
+fn add_bytevector_functions(env: &mut Env) {
+    env.insert_fn("bytevector?", |_, list| {
+        check_arity("bytevector?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Bytevector(..))))
+    });
+    env.insert_fn("make-bytevector", |_, list| {
+        check_arity_range("make-bytevector", &list, 1, 2)?;
+        let len = usize::try_from(list[0].as_integer()?)
+            .map_err(|_| list[0].type_error("non-negative integer"))?;
+        let fill = list.get(1).map(Exp::as_byte).transpose()?.unwrap_or(0);
+        Ok(Exp::bytevector(vec![fill; len]))
+    });
+    env.insert_fn("bytevector", |_, list| {
+        Ok(Exp::bytevector(list.iter().map(Exp::as_byte).collect::<Result<_, _>>()?))
+    });
+    env.insert_fn("bytevector-length", |_, list| {
+        check_arity("bytevector-length", &list, 1)?;
+        Ok(Exp::int(list[0].as_bytevector()?.borrow().len() as Integer))
+    });
+    env.insert_fn("bytevector-u8-ref", |_, list| {
+        check_arity("bytevector-u8-ref", &list, 2)?;
+        let bytes = list[0].as_bytevector()?;
+        let bytes = bytes.borrow();
+        Ok(Exp::int(bytes[list[1].as_index(bytes.len(), false)?] as Integer))
+    });
+    env.insert_fn("bytevector-u8-set!", |_, list| {
+        check_arity("bytevector-u8-set!", &list, 3)?;
+        let bytes = list[0].as_bytevector()?;
+        let mut bytes = bytes.borrow_mut();
+        let index = list[1].as_index(bytes.len(), false)?;
+        bytes[index] = list[2].as_byte()?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("bytevector-copy", |_, list| {
+        check_arity_range("bytevector-copy", &list, 1, 3)?;
+        let bytes = list[0].as_bytevector()?;
+        let bytes = bytes.borrow();
+        let (start, end) = optional_range(&list, 1, bytes.len())?;
+        Ok(Exp::bytevector(bytes[start..end].to_vec()))
+    });
+    env.insert_fn("bytevector-copy!", |_, list| {
+        check_arity_range("bytevector-copy!", &list, 3, 5)?;
+        let to = list[0].as_bytevector()?;
+        let at = list[1].as_index(to.borrow().len(), true)?;
+        // Copy out of the source first, since it may be the same bytevector.
+        let bytes = {
+            let from = list[2].as_bytevector()?;
+            let from = from.borrow();
+            let (start, end) = optional_range(&list, 3, from.len())?;
+            from[start..end].to_vec()
+        };
+        let mut to = to.borrow_mut();
+        if at + bytes.len() > to.len() {
+            return Err(VowError::Custom("bytevector-copy!: destination is too small".to_string()));
+        }
+        to[at..at + bytes.len()].copy_from_slice(&bytes);
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("bytevector-append", |_, list| {
+        let mut result = vec![];
+        for exp in &list {
+            result.extend_from_slice(&exp.as_bytevector()?.borrow());
+        }
+        Ok(Exp::bytevector(result))
+    });
+    env.insert_fn("utf8->string", |_, list| {
+        check_arity_range("utf8->string", &list, 1, 3)?;
+        let bytes = list[0].as_bytevector()?;
+        let bytes = bytes.borrow();
+        let (start, end) = optional_range(&list, 1, bytes.len())?;
+        match std::str::from_utf8(&bytes[start..end]) {
+            Ok(s) => Ok(Exp::string(s)),
+            Err(e) => Err(VowError::Custom(format!("utf8->string: invalid UTF-8: {e}"))),
+        }
+    });
+    env.insert_fn("string->utf8", |_, list| {
+        check_arity_range("string->utf8", &list, 1, 3)?;
+        let chars = list[0].as_string()?.chars().collect::<Vec<_>>();
+        let (start, end) = optional_range(&list, 1, chars.len())?;
+        Ok(Exp::bytevector(chars[start..end].iter().collect::<String>().into_bytes()))
+    });
+}
+
+fn add_hash_table_functions(env: &mut Env) {
+    env.insert_fn("make-hash-table", |_, list| {
+        check_arity("make-hash-table", &list, 0)?;
+        Ok(Exp::HashTable(Rc::new(RefCell::new(HashTable::new(false)))))
+    });
+    env.insert_fn("make-equal-hash-table", |_, list| {
+        check_arity("make-equal-hash-table", &list, 0)?;
+        Ok(Exp::HashTable(Rc::new(RefCell::new(HashTable::new(true)))))
+    });
+    env.insert_fn("hash-table?", |_, list| {
+        check_arity("hash-table?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::HashTable(..))))
+    });
+    env.insert_fn("hash-table-ref", |env_tree, list| {
+        check_arity_range("hash-table-ref", &list, 2, 3)?;
+        hash_table_ref(env_tree, "hash-table-ref", &list[0], &list[1], list.get(2))
+    });
+    env.insert_fn("hash-table-ref/default", |_, list| {
+        check_arity("hash-table-ref/default", &list, 3)?;
+        Ok(list[0].as_hash_table()?.borrow().get(&list[1])?.unwrap_or_else(|| list[2].clone()))
+    });
+    env.insert_fn("hash-table-set!", |_, list| {
+        check_arity("hash-table-set!", &list, 3)?;
+        list[0].as_hash_table()?.borrow_mut().insert(list[1].clone(), list[2].clone())?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("hash-table-delete!", |_, list| {
+        check_arity("hash-table-delete!", &list, 2)?;
+        list[0].as_hash_table()?.borrow_mut().remove(&list[1])?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("hash-table-size", |_, list| {
+        check_arity("hash-table-size", &list, 1)?;
+        Ok(Exp::int(list[0].as_hash_table()?.borrow().entries.len() as Integer))
+    });
+    env.insert_fn("hash-table-keys", |_, list| {
+        check_arity("hash-table-keys", &list, 1)?;
+        let table = list[0].as_hash_table()?;
+        let table = table.borrow();
+        Ok(Exp::List(table.entries.values().map(|(key, _)| key.clone()).collect()))
+    });
+    env.insert_fn("hash-table-values", |_, list| {
+        check_arity("hash-table-values", &list, 1)?;
+        let table = list[0].as_hash_table()?;
+        let table = table.borrow();
+        Ok(Exp::List(table.entries.values().map(|(_, value)| value.clone()).collect()))
+    });
+    env.insert_fn("hash-table->alist", |_, list| {
+        check_arity("hash-table->alist", &list, 1)?;
+        let table = list[0].as_hash_table()?;
+        let table = table.borrow();
+        Ok(Exp::List(
+            table
+                .entries
+                .values()
+                .map(|(key, value)| Exp::cons(key.clone(), value.clone()))
+                .collect(),
+        ))
+    });
+    env.insert_fn("hash-table-walk", |env_tree, list| {
+        check_arity("hash-table-walk", &list, 2)?;
+        // Walk a snapshot of the entries, since the procedure may modify the
+        // table.
+        let entries =
+            list[0].as_hash_table()?.borrow().entries.values().cloned().collect::<Vec<_>>();
+        for (key, value) in entries {
+            list[1].invoke(env_tree, vec![key, value])?;
+        }
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("hash-table-update!", |env_tree, list| {
+        check_arity_range("hash-table-update!", &list, 3, 4)?;
+        let value =
+            hash_table_ref(env_tree, "hash-table-update!", &list[0], &list[1], list.get(3))?;
+        let value = list[2].invoke(env_tree, vec![value])?;
+        list[0].as_hash_table()?.borrow_mut().insert(list[1].clone(), value)?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("hash-table-copy", |_, list| {
+        check_arity("hash-table-copy", &list, 1)?;
+        let table = list[0].as_hash_table()?.borrow().clone();
+        Ok(Exp::HashTable(Rc::new(RefCell::new(table))))
+    });
+    env.insert_fn("hash-table-merge!", |_, list| {
+        check_arity("hash-table-merge!", &list, 2)?;
+        let entries =
+            list[1].as_hash_table()?.borrow().entries.values().cloned().collect::<Vec<_>>();
+        let table = list[0].as_hash_table()?;
+        for (key, value) in entries {
+            table.borrow_mut().insert(key, value)?;
+        }
+        Ok(list[0].clone())
+    });
+}
+
+/// Returns true if `relation` holds for every consecutive pair of strings in
+/// `list`.
+fn compare_strings(
+    name: &str,
+    list: &[Exp],
+    relation: fn(&str, &str) -> bool,
+) -> Result<Exp, VowError> {
+    check_min_arity(name, list, 2)?;
+    let strings = list.iter().map(Exp::as_string).collect::<Result<Vec<_>, _>>()?;
+    Ok(Exp::bool(strings.windows(2).all(|pair| relation(&pair[0], &pair[1]))))
+}
+
+/// Returns the characters of the string `exp` between the optional `start` and
+/// `end` arguments found at `list[index]` and `list[index + 1]`.
+fn string_range(exp: &Exp, list: &[Exp], index: usize) -> Result<Vec<char>, VowError> {
+    let chars = exp.as_string()?.chars().collect::<Vec<_>>();
+    let (start, end) = optional_range(list, index, chars.len())?;
+    Ok(chars[start..end].to_vec())
+}
+
+fn add_string_functions(env: &mut Env) {
+    env.insert_fn("string?", |_, list| {
+        check_arity("string?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::String(..)))))
+    });
+    env.insert_fn("string-map", |env_tree, list| {
+        check_min_arity("string-map", &list, 2)?;
+        let mut result = String::new();
+        for args in zip_with(&list[1..], string_chars)? {
+            result.push(list[0].invoke(env_tree, args)?.as_char()?);
+        }
+        Ok(Exp::string(result))
+    });
+    env.insert_fn("string-for-each", |env_tree, list| {
+        check_min_arity("string-for-each", &list, 2)?;
+        for args in zip_with(&list[1..], string_chars)? {
+            list[0].invoke(env_tree, args)?;
+        }
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("make-string", |_, list| {
+        check_arity_range("make-string", &list, 1, 2)?;
+        let len = usize::try_from(list[0].as_integer()?)
+            .map_err(|_| list[0].type_error("non-negative integer"))?;
+        let fill = match list.get(1) {
+            Some(exp) => exp.as_char()?,
+            None => ' ',
+        };
+        Ok(Exp::string(iter::repeat_n(fill, len).collect::<String>()))
+    });
+    env.insert_fn("string-length", |_, list| {
+        check_arity("string-length", &list, 1)?;
+        Ok(Exp::int(list[0].as_string()?.chars().count() as Integer))
+    });
+    env.insert_fn("string-ref", |_, list| {
+        check_arity("string-ref", &list, 2)?;
+        let chars = list[0].as_string()?.chars().collect::<Vec<_>>();
+        Ok(Exp::Atom(Atom::Char(chars[list[1].as_index(chars.len(), false)?])))
+    });
+    env.insert_fn("string-set!", |_, list| {
+        check_arity("string-set!", &list, 3)?;
+        let cell = list[0].as_string_cell()?;
+        let mut chars = cell.borrow().chars().collect::<Vec<_>>();
+        let index = list[1].as_index(chars.len(), false)?;
+        chars[index] = list[2].as_char()?;
+        *cell.borrow_mut() = chars.into_iter().collect();
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("string-append", |_, list| {
+        list.iter().map(Exp::as_string).collect::<Result<String, _>>().map(Exp::string)
+    });
+    env.insert_fn("substring", |_, list| {
+        check_arity("substring", &list, 3)?;
+        Ok(Exp::string(string_range(&list[0], &list, 1)?.into_iter().collect::<String>()))
+    });
+    env.insert_fn("string-copy", |_, list| {
+        check_arity_range("string-copy", &list, 1, 3)?;
+        Ok(Exp::string(string_range(&list[0], &list, 1)?.into_iter().collect::<String>()))
+    });
+    env.insert_fn("string->list", |_, list| {
+        check_arity_range("string->list", &list, 1, 3)?;
+        let chars = string_range(&list[0], &list, 1)?;
+        Ok(Exp::List(chars.into_iter().map(|c| Exp::Atom(Atom::Char(c))).collect()))
+    });
+    env.insert_fn("list->string", |_, list| {
+        check_arity("list->string", &list, 1)?;
+        list[0]
+            .as_exp_list()?
+            .iter()
+            .map(Exp::as_char)
+            .collect::<Result<String, _>>()
+            .map(Exp::string)
+    });
+    env.insert_fn("string->number", |_, list| {
+        check_arity_range("string->number", &list, 1, 2)?;
+        let radix = optional_radix("string->number", &list)?;
+        Ok(parse_number(&list[0].as_string()?, radix).map(Exp::Atom).unwrap_or(Exp::bool(false)))
+    });
+    env.insert_fn("number->string", |_, list| {
+        check_arity_range("number->string", &list, 1, 3)?;
+        let digits = match list.get(2) {
+            Some(exp) => Some(
+                usize::try_from(exp.as_integer()?)
+                    .map_err(|_| exp.type_error("non-negative integer"))?,
+            ),
+            None => None,
+        };
+        match (&list[0], optional_radix("number->string", &list)?, digits) {
+            (Exp::Atom(Atom::Integer(n)), radix, _) => {
+                Ok(Exp::string(integer_to_string(*n, radix)))
+            }
+            (Exp::Atom(Atom::Number(n)), 10, Some(digits)) if n.is_finite() => {
+                Ok(Exp::string(format!("{n:.digits$}")))
+            }
+            (Exp::Atom(Atom::Number(..) | Atom::Complex(..)), 10, _) => {
+                Ok(Exp::string(WriteFormat(&list[0]).to_string()))
+            }
+            (Exp::Atom(Atom::Number(..) | Atom::Complex(..)), radix, _) => {
+                Err(VowError::Custom(format!(
+                    "number->string: cannot write inexact number {} in radix {radix}",
+                    WriteFormat(&list[0])
+                )))
+            }
+            (exp, _, _) => Err(exp.type_error("number")),
+        }
+    });
+    env.insert_fn("string-upcase", |_, list| {
+        check_arity("string-upcase", &list, 1)?;
+        Ok(Exp::string(list[0].as_string()?.to_uppercase()))
+    });
+    env.insert_fn("string-downcase", |_, list| {
+        check_arity("string-downcase", &list, 1)?;
+        Ok(Exp::string(list[0].as_string()?.to_lowercase()))
+    });
+    env.insert_fn("string-foldcase", |_, list| {
+        check_arity("string-foldcase", &list, 1)?;
+        Ok(Exp::string(string_foldcase(&list[0].as_string()?)))
+    });
+    env.insert_fn("string=?", |_, list| compare_strings("string=?", &list, |a, b| a == b));
+    env.insert_fn("string<?", |_, list| compare_strings("string<?", &list, |a, b| a < b));
+    env.insert_fn("string>?", |_, list| compare_strings("string>?", &list, |a, b| a > b));
+    env.insert_fn("string<=?", |_, list| compare_strings("string<=?", &list, |a, b| a <= b));
+    env.insert_fn("string>=?", |_, list| compare_strings("string>=?", &list, |a, b| a >= b));
+    env.insert_fn("string-ci=?", |_, list| {
+        compare_strings("string-ci=?", &list, |a, b| string_foldcase(a) == string_foldcase(b))
+    });
+    env.insert_fn("string-ci<?", |_, list| {
+        compare_strings("string-ci<?", &list, |a, b| string_foldcase(a) < string_foldcase(b))
+    });
+    env.insert_fn("string-ci>?", |_, list| {
+        compare_strings("string-ci>?", &list, |a, b| string_foldcase(a) > string_foldcase(b))
+    });
+    env.insert_fn("string-ci<=?", |_, list| {
+        compare_strings("string-ci<=?", &list, |a, b| string_foldcase(a) <= string_foldcase(b))
+    });
+    env.insert_fn("string-ci>=?", |_, list| {
+        compare_strings("string-ci>=?", &list, |a, b| string_foldcase(a) >= string_foldcase(b))
+    });
+    env.insert_fn("string-contains", |_, list| {
+        check_arity("string-contains", &list, 2)?;
+        let haystack = list[0].as_string()?;
+        Ok(match haystack.find(&list[1].as_string()?) {
+            Some(byte_index) => Exp::int(haystack[..byte_index].chars().count() as Integer),
+            None => Exp::bool(false),
+        })
+    });
+    env.insert_fn("string-split", |_, list| {
+        check_arity("string-split", &list, 2)?;
+        let delimiter = as_delimiter(&list[1])?;
+        if delimiter.is_empty() {
+            return Err(VowError::Custom("string-split: empty delimiter".to_string()));
+        }
+        Ok(Exp::List(list[0].as_string()?.split(&delimiter).map(Exp::string).collect()))
+    });
+    env.insert_fn("string-join", |_, list| {
+        check_arity_range("string-join", &list, 1, 2)?;
+        let delimiter = match list.get(1) {
+            Some(exp) => as_delimiter(exp)?,
+            None => " ".to_string(),
+        };
+        let strings =
+            list[0].as_exp_list()?.iter().map(Exp::as_string).collect::<Result<Vec<_>, _>>()?;
+        Ok(Exp::string(strings.join(&delimiter)))
+    });
+    env.insert_fn("string-prefix?", |_, list| {
+        check_arity("string-prefix?", &list, 2)?;
+        Ok(Exp::bool(list[1].as_string()?.starts_with(&list[0].as_string()?)))
+    });
+    env.insert_fn("string-suffix?", |_, list| {
+        check_arity("string-suffix?", &list, 2)?;
+        Ok(Exp::bool(list[1].as_string()?.ends_with(&list[0].as_string()?)))
+    });
+    env.insert_fn("string-trim", |_, list| {
+        check_arity("string-trim", &list, 1)?;
+        Ok(Exp::string(list[0].as_string()?.trim_start()))
+    });
+    env.insert_fn("string-trim-right", |_, list| {
+        check_arity("string-trim-right", &list, 1)?;
+        Ok(Exp::string(list[0].as_string()?.trim_end()))
+    });
+    env.insert_fn("string-trim-both", |_, list| {
+        check_arity("string-trim-both", &list, 1)?;
+        Ok(Exp::string(list[0].as_string()?.trim()))
+    });
+}
+
+/// Returns the delimiter given to `string-split` or `string-join`, which may
+/// be a character or a string.
+fn as_delimiter(exp: &Exp) -> Result<String, VowError> {
+    match exp {
+        Exp::Atom(Atom::Char(c)) => Ok(c.to_string()),
+        _ => exp.as_string().map_err(|_| exp.type_error("character or string")),
+    }
+}
+
+thread_local! {
+    static CURRENT_INPUT_PORT: Rc<Parameter> = Rc::new(Parameter {
+        value: RefCell::new(Exp::InputPort(Port::new(InPort::new(Box::new(io::stdin()))))),
+        converter: Some(Exp::Function(|_, list| {
+            check_arity("current-input-port", &list, 1)?;
+            list[0].as_input_port().map(Exp::InputPort)
+        })),
+    });
+    static CURRENT_OUTPUT_PORT: Rc<Parameter> = Rc::new(Parameter {
+        value: RefCell::new(Exp::OutputPort(Port::new(Output::Stream(Box::new(io::stdout()))))),
+        converter: Some(Exp::Function(|_, list| {
+            check_arity("current-output-port", &list, 1)?;
+            list[0].as_output_port().map(Exp::OutputPort)
+        })),
+    });
+    static CURRENT_ERROR_PORT: Rc<Parameter> = Rc::new(Parameter {
+        value: RefCell::new(Exp::OutputPort(Port::new(Output::Stream(Box::new(io::stderr()))))),
+        converter: Some(Exp::Function(|_, list| {
+            check_arity("current-error-port", &list, 1)?;
+            list[0].as_output_port().map(Exp::OutputPort)
+        })),
+    });
+}
+
+/// Returns the input port passed as `list[index]`, or the current input port
+/// if the argument was omitted.
+fn input_port_arg(list: &[Exp], index: usize) -> Result<InputPort, VowError> {
+    match list.get(index) {
+        Some(exp) => exp.as_input_port(),
+        None => CURRENT_INPUT_PORT.with(|port| port.value.borrow().as_input_port()),
+    }
+}
+
+/// Returns the output port passed as `list[index]`, or the current output
+/// port if the argument was omitted.
+fn output_port_arg(list: &[Exp], index: usize) -> Result<OutputPort, VowError> {
+    match list.get(index) {
+        Some(exp) => exp.as_output_port(),
+        None => CURRENT_OUTPUT_PORT.with(|port| port.value.borrow().as_output_port()),
+    }
+}
+
+/// Writes `text` to `port`.
+fn write_to_port(port: &OutputPort, text: &str) -> Result<Exp, VowError> {
+    write_bytes_to_port(port, text.as_bytes())
+}
+
+/// Writes `bytes` to `port`.
+fn write_bytes_to_port(port: &OutputPort, bytes: &[u8]) -> Result<Exp, VowError> {
+    port.with(|writer| {
+        writer.write_all(bytes).map_err(|e| VowError::Custom(format!("Error writing to port: {e}")))
+    })?;
+    Ok(Exp::List(vec![]))
+}
+
+/// Returns the text written so far to the string output port `port`.
+fn output_string(port: &Exp) -> Result<Exp, VowError> {
+    port.as_output_port()?.with(|output| match output {
+        Output::String(bytes) => Ok(Exp::string(String::from_utf8_lossy(bytes))),
+        Output::Stream(_) => Err(port.type_error("string output port")),
+    })
+}
+
+/// Expands the directives in a `format` string, taking their values from
+/// `arguments` in order.
+fn format_directives(template: &str, arguments: &[Exp]) -> Result<String, VowError> {
+    let error = |message: String| VowError::Custom(format!("format: {message}"));
+    let mut arguments = arguments.iter();
+    let mut next_argument = |directive: char| {
+        arguments.next().ok_or_else(|| error(format!("missing argument for ~{directive}")))
+    };
+    let mut result = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '~' {
+            result.push(c);
+            continue;
+        }
+        let mut precision = String::new();
+        let directive = loop {
+            match chars.next() {
+                Some(digit) if digit.is_ascii_digit() => precision.push(digit),
+                Some(directive) => break directive,
+                None => return Err(error("incomplete directive at end of string".to_string())),
+            }
+        };
+        if !precision.is_empty() && directive != 'f' {
+            return Err(error(format!("~{directive} does not take a precision")));
+        }
+        match directive.to_ascii_lowercase() {
+            'a' => result.push_str(&next_argument(directive)?.to_string()),
+            's' => result.push_str(&WriteFormat(next_argument(directive)?).to_string()),
+            '%' | 'n' => result.push('\n'),
+            '~' => result.push('~'),
+            'd' => result.push_str(&next_argument(directive)?.as_integer()?.to_string()),
+            'b' => result.push_str(&integer_to_string(next_argument(directive)?.as_integer()?, 2)),
+            'o' => result.push_str(&integer_to_string(next_argument(directive)?.as_integer()?, 8)),
+            'x' => result.push_str(&integer_to_string(next_argument(directive)?.as_integer()?, 16)),
+            'f' => {
+                let n = next_argument(directive)?.as_number()?;
+                result.push_str(&match precision.parse::<usize>() {
+                    Ok(precision) if n.is_finite() => format!("{n:.precision$}"),
+                    _ if n.is_finite() && n.fract() == 0.0 => format!("{n}.0"),
+                    _ if n.is_finite() => format!("{n}"),
+                    _ => number_to_string(n),
+                });
+            }
+            _ => return Err(error(format!("unknown directive ~{directive}"))),
+        }
+    }
+    match arguments.len() {
+        0 => Ok(result),
+        extra => Err(error(format!("{extra} unused argument(s)"))),
+    }
+}
+
+fn open_input_file(path: &Exp) -> Result<Exp, VowError> {
+    let path = path.as_string()?;
+    let file = File::open(&path).map_err(|e| VowError::IoError(path, e))?;
+    Ok(Exp::InputPort(Port::new(InPort::new(Box::new(file)))))
+}
+
+fn open_output_file(path: &Exp) -> Result<Exp, VowError> {
+    let path = path.as_string()?;
+    let file = File::create(&path).map_err(|e| VowError::IoError(path, e))?;
+    Ok(Exp::OutputPort(Port::new(Output::Stream(Box::new(BufWriter::new(file))))))
+}
+
+/// Flushes and closes `port`. Closing an already closed port has no effect.
+fn close_output_port(port: &OutputPort) -> Result<(), VowError> {
+    if port.0.borrow().is_some() {
+        port.with(|writer| {
+            writer.flush().map_err(|e| VowError::Custom(format!("Error flushing port: {e}")))
+        })?;
+    }
+    port.close();
+    Ok(())
+}
+
+fn close_port(port: &Exp) -> Result<(), VowError> {
+    match port {
+        Exp::InputPort(port) => {
+            port.close();
+            Ok(())
+        }
+        Exp::OutputPort(port) => close_output_port(port),
+        _ => Err(port.type_error("port")),
+    }
+}
+
+/// Calls `procedure` with `port`, closing the port however control leaves the
+/// procedure. As with `dynamic-wind`, escapes unwind as errors, so the port is
+/// also closed when the procedure raises an error or invokes an outer
+/// continuation.
+fn call_with_port(env_tree: &mut EnvTree, port: Exp, procedure: &Exp) -> Result<Exp, VowError> {
+    if !matches!(port, Exp::InputPort(..) | Exp::OutputPort(..)) {
+        return Err(port.type_error("port"));
+    }
+    let result = procedure.invoke(env_tree, vec![port.clone()]);
+    // An error while flushing replaces the outcome of the procedure.
+    close_port(&port)?;
+    result
+}
+
+/// Calls `thunk` with `port` as the value of the current port `parameter`,
+/// restoring its previous value however control leaves the thunk.
+fn with_current_port(
+    env_tree: &mut EnvTree,
+    parameter: &Parameter,
+    port: Exp,
+    thunk: &Exp,
+) -> Result<Exp, VowError> {
+    let saved = parameter.value.replace(port);
+    let result = thunk.invoke(env_tree, vec![]);
+    parameter.value.replace(saved);
+    result
+}
+
+fn add_port_functions(env: &mut Env) {
+    env.insert("current-input-port", Exp::Parameter(CURRENT_INPUT_PORT.with(Rc::clone)));
+    env.insert("current-output-port", Exp::Parameter(CURRENT_OUTPUT_PORT.with(Rc::clone)));
+    env.insert("current-error-port", Exp::Parameter(CURRENT_ERROR_PORT.with(Rc::clone)));
+    env.insert_fn("open-input-file", |_, list| {
+        check_arity("open-input-file", &list, 1)?;
+        open_input_file(&list[0])
+    });
+    env.insert_fn("open-output-file", |_, list| {
+        check_arity("open-output-file", &list, 1)?;
+        open_output_file(&list[0])
+    });
+    env.insert_fn("call-with-port", |env_tree, list| {
+        check_arity("call-with-port", &list, 2)?;
+        call_with_port(env_tree, list[0].clone(), &list[1])
+    });
+    env.insert_fn("file-exists?", |_, list| {
+        check_arity("file-exists?", &list, 1)?;
+        Ok(Exp::bool(Path::new(&list[0].as_string()?).exists()))
+    });
+    env.insert_fn("delete-file", |_, list| {
+        check_arity("delete-file", &list, 1)?;
+        let path = list[0].as_string()?;
+        fs::remove_file(&path).map_err(|e| VowError::IoError(path, e))?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("rename-file", |_, list| {
+        check_arity("rename-file", &list, 2)?;
+        let (from, to) = (list[0].as_string()?, list[1].as_string()?);
+        fs::rename(&from, to).map_err(|e| VowError::IoError(from, e))?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("create-directory", |_, list| {
+        check_arity("create-directory", &list, 1)?;
+        let path = list[0].as_string()?;
+        fs::create_dir(&path).map_err(|e| VowError::IoError(path, e))?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("open-binary-input-file", |_, list| {
+        check_arity("open-binary-input-file", &list, 1)?;
+        open_input_file(&list[0])
+    });
+    env.insert_fn("open-binary-output-file", |_, list| {
+        check_arity("open-binary-output-file", &list, 1)?;
+        open_output_file(&list[0])
+    });
+    env.insert_fn("call-with-input-file", |env_tree, list| {
+        check_arity("call-with-input-file", &list, 2)?;
+        call_with_port(env_tree, open_input_file(&list[0])?, &list[1])
+    });
+    env.insert_fn("call-with-output-file", |env_tree, list| {
+        check_arity("call-with-output-file", &list, 2)?;
+        call_with_port(env_tree, open_output_file(&list[0])?, &list[1])
+    });
+    env.insert_fn("open-input-string", |_, list| {
+        check_arity("open-input-string", &list, 1)?;
+        let bytes = list[0].as_string()?.into_bytes();
+        Ok(Exp::InputPort(Port::new(InPort::new(Box::new(io::Cursor::new(bytes))))))
+    });
+    env.insert_fn("open-input-bytevector", |_, list| {
+        check_arity("open-input-bytevector", &list, 1)?;
+        let bytes = list[0].as_bytevector()?.borrow().clone();
+        Ok(Exp::InputPort(Port::new(InPort::new(Box::new(io::Cursor::new(bytes))))))
+    });
+    env.insert_fn("open-output-bytevector", |_, list| {
+        check_arity("open-output-bytevector", &list, 0)?;
+        Ok(Exp::OutputPort(Port::new(Output::String(vec![]))))
+    });
+    env.insert_fn("get-output-bytevector", |_, list| {
+        check_arity("get-output-bytevector", &list, 1)?;
+        list[0].as_output_port()?.with(|output| match output {
+            Output::String(bytes) => Ok(Exp::bytevector(bytes.clone())),
+            Output::Stream(_) => Err(list[0].type_error("bytevector output port")),
+        })
+    });
+    env.insert_fn("open-output-string", |_, list| {
+        check_arity("open-output-string", &list, 0)?;
+        Ok(Exp::OutputPort(Port::new(Output::String(vec![]))))
+    });
+    env.insert_fn("get-output-string", |_, list| {
+        check_arity("get-output-string", &list, 1)?;
+        output_string(&list[0])
+    });
+    env.insert_fn("call-with-string-output-port", |env_tree, list| {
+        check_arity("call-with-string-output-port", &list, 1)?;
+        let port = Exp::OutputPort(Port::new(Output::String(vec![])));
+        list[0].invoke(env_tree, vec![port.clone()])?;
+        output_string(&port)
+    });
+    env.insert_fn("with-output-to-string", |env_tree, list| {
+        check_arity("with-output-to-string", &list, 1)?;
+        let port = Exp::OutputPort(Port::new(Output::String(vec![])));
+        let parameter = CURRENT_OUTPUT_PORT.with(Rc::clone);
+        with_current_port(env_tree, &parameter, port.clone(), &list[0])?;
+        output_string(&port)
+    });
+    env.insert_fn("with-input-from-file", |env_tree, list| {
+        check_arity("with-input-from-file", &list, 2)?;
+        let port = open_input_file(&list[0])?;
+        let parameter = CURRENT_INPUT_PORT.with(Rc::clone);
+        let result = with_current_port(env_tree, &parameter, port.clone(), &list[1]);
+        close_port(&port)?;
+        result
+    });
+    env.insert_fn("with-output-to-file", |env_tree, list| {
+        check_arity("with-output-to-file", &list, 2)?;
+        let port = open_output_file(&list[0])?;
+        let parameter = CURRENT_OUTPUT_PORT.with(Rc::clone);
+        let result = with_current_port(env_tree, &parameter, port.clone(), &list[1]);
+        // An error while flushing replaces the outcome of the thunk.
+        close_port(&port)?;
+        result
+    });
+    env.insert_fn("close-input-port", |_, list| {
+        check_arity("close-input-port", &list, 1)?;
+        list[0].as_input_port()?.close();
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("close-output-port", |_, list| {
+        check_arity("close-output-port", &list, 1)?;
+        close_output_port(&list[0].as_output_port()?)?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("close-port", |_, list| {
+        check_arity("close-port", &list, 1)?;
+        close_port(&list[0])?;
+        Ok(Exp::List(vec![]))
+    });
+    env.insert_fn("input-port?", |_, list| {
+        check_arity("input-port?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::InputPort(..))))
+    });
+    env.insert_fn("output-port?", |_, list| {
+        check_arity("output-port?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::OutputPort(..))))
+    });
+    env.insert_fn("read", |_, list| {
+        check_arity_range("read", &list, 0, 1)?;
+        input_port_arg(&list, 0)?.with(|port| Ok(read(port)?.unwrap_or(Exp::Atom(Atom::Eof))))
+    });
+    env.insert_fn("read-char", |_, list| {
+        check_arity_range("read-char", &list, 0, 1)?;
+        let c = input_port_arg(&list, 0)?.with(|port| port.read_char())?;
+        Ok(Exp::Atom(c.map(Atom::Char).unwrap_or(Atom::Eof)))
+    });
+    env.insert_fn("peek-char", |_, list| {
+        check_arity_range("peek-char", &list, 0, 1)?;
+        let c = input_port_arg(&list, 0)?.with(|port| port.peek_char())?;
+        Ok(Exp::Atom(c.map(Atom::Char).unwrap_or(Atom::Eof)))
+    });
+    env.insert_fn("read-line", |_, list| {
+        check_arity_range("read-line", &list, 0, 1)?;
+        let line = input_port_arg(&list, 0)?.with(|port| port.next_line())?;
+        Ok(line.map(Exp::string).unwrap_or(Exp::Atom(Atom::Eof)))
+    });
+    env.insert_fn("read-string", |_, list| {
+        check_arity_range("read-string", &list, 1, 2)?;
+        let count = usize::try_from(list[0].as_integer()?)
+            .map_err(|_| list[0].type_error("non-negative integer"))?;
+        let text = input_port_arg(&list, 1)?.with(|port| {
+            let mut text = String::new();
+            for _ in 0..count {
+                match port.read_char()? {
+                    Some(c) => text.push(c),
+                    None => break,
+                }
+            }
+            Ok(text)
+        })?;
+        if text.is_empty() && count > 0 {
+            Ok(Exp::Atom(Atom::Eof))
+        } else {
+            Ok(Exp::string(text))
+        }
+    });
+    env.insert_fn("read-u8", |_, list| {
+        check_arity_range("read-u8", &list, 0, 1)?;
+        let byte = input_port_arg(&list, 0)?.with(|port| port.read_u8())?;
+        Ok(byte.map(|byte| Exp::int(byte as Integer)).unwrap_or(Exp::Atom(Atom::Eof)))
+    });
+    env.insert_fn("peek-u8", |_, list| {
+        check_arity_range("peek-u8", &list, 0, 1)?;
+        let byte = input_port_arg(&list, 0)?.with(|port| port.peek_u8())?;
+        Ok(byte.map(|byte| Exp::int(byte as Integer)).unwrap_or(Exp::Atom(Atom::Eof)))
+    });
+    env.insert_fn("u8-ready?", |_, list| {
+        check_arity_range("u8-ready?", &list, 0, 1)?;
+        input_port_arg(&list, 0)?.with(|_| Ok(Exp::bool(true)))
+    });
+    env.insert_fn("read-bytevector", |_, list| {
+        check_arity_range("read-bytevector", &list, 1, 2)?;
+        let count = usize::try_from(list[0].as_integer()?)
+            .map_err(|_| list[0].type_error("non-negative integer"))?;
+        let bytes = input_port_arg(&list, 1)?.with(|port| {
+            let mut bytes = vec![];
+            while bytes.len() < count {
+                match port.read_u8()? {
+                    Some(byte) => bytes.push(byte),
+                    None => break,
+                }
+            }
+            Ok(bytes)
+        })?;
+        if bytes.is_empty() && count > 0 {
+            Ok(Exp::Atom(Atom::Eof))
+        } else {
+            Ok(Exp::bytevector(bytes))
+        }
+    });
+    env.insert_fn("char-ready?", |_, list| {
+        check_arity_range("char-ready?", &list, 0, 1)?;
+        // Reads block until a character is available, so one is always
+        // considered to be ready.
+        input_port_arg(&list, 0)?.with(|_| Ok(Exp::bool(true)))
+    });
+    env.insert_fn("eof-object", |_, list| {
+        check_arity("eof-object", &list, 0)?;
+        Ok(Exp::Atom(Atom::Eof))
+    });
+    env.insert_fn("eof-object?", |_, list| {
+        check_arity("eof-object?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Eof))))
+    });
+    env.insert_fn("write", |_, list| {
+        check_arity_range("write", &list, 1, 2)?;
+        write_to_port(&output_port_arg(&list, 1)?, &WriteFormat(&list[0]).to_string())
+    });
+    env.insert_fn("display", |_, list| {
+        check_arity_range("display", &list, 1, 2)?;
+        write_to_port(&output_port_arg(&list, 1)?, &list[0].to_string())
+    });
+    env.insert_fn("pp", |_, list| {
+        check_arity_range("pp", &list, 1, 2)?;
+        let text = pretty_print(&list[0], PRETTY_PRINT_WIDTH) + "\n";
+        write_to_port(&output_port_arg(&list, 1)?, &text)
+    });
+    env.insert_fn("format", |_, list| {
+        check_min_arity("format", &list, 2)?;
+        let text = format_directives(&list[1].as_string()?, &list[2..])?;
+        match &list[0] {
+            Exp::Atom(Atom::Bool(false)) => Ok(Exp::string(text)),
+            Exp::Atom(Atom::Bool(true)) => write_to_port(&output_port_arg(&[], 0)?, &text),
+            destination => write_to_port(&destination.as_output_port()?, &text),
+        }
+    });
+    env.insert_fn("newline", |_, list| {
+        check_arity_range("newline", &list, 0, 1)?;
+        write_to_port(&output_port_arg(&list, 0)?, "\n")
+    });
+    env.insert_fn("write-char", |_, list| {
+        check_arity_range("write-char", &list, 1, 2)?;
+        write_to_port(&output_port_arg(&list, 1)?, &list[0].as_char()?.to_string())
+    });
+    env.insert_fn("write-string", |_, list| {
+        check_arity_range("write-string", &list, 1, 4)?;
+        let chars = list[0].as_string()?.chars().collect::<Vec<_>>();
+        let (start, end) = optional_range(&list, 2, chars.len())?;
+        write_to_port(&output_port_arg(&list, 1)?, &chars[start..end].iter().collect::<String>())
+    });
+    env.insert_fn("write-u8", |_, list| {
+        check_arity_range("write-u8", &list, 1, 2)?;
+        write_bytes_to_port(&output_port_arg(&list, 1)?, &[list[0].as_byte()?])
+    });
+    env.insert_fn("write-bytevector", |_, list| {
+        check_arity_range("write-bytevector", &list, 1, 4)?;
+        let bytes = list[0].as_bytevector()?.borrow().clone();
+        let (start, end) = optional_range(&list, 2, bytes.len())?;
+        write_bytes_to_port(&output_port_arg(&list, 1)?, &bytes[start..end])
+    });
+    env.insert_fn("flush-output-port", |_, list| {
+        check_arity_range("flush-output-port", &list, 0, 1)?;
+        output_port_arg(&list, 0)?.with(|writer| {
+            writer.flush().map_err(|e| VowError::Custom(format!("Error flushing port: {e}")))
+        })?;
+        Ok(Exp::List(vec![]))
+    });
+}
+
+fn add_char_functions(env: &mut Env) {
+    env.insert_fn("char?", |_, list| {
+        check_arity("char?", &list, 1)?;
+        Ok(Exp::bool(matches!(list[0], Exp::Atom(Atom::Char(..)))))
+    });
+    env.insert_fn("char->integer", |_, list| {
+        check_arity("char->integer", &list, 1)?;
+        Ok(Exp::int(list[0].as_char()? as Integer))
+    });
+    env.insert_fn("integer->char", |_, list| {
+        check_arity("integer->char", &list, 1)?;
+        u32::try_from(list[0].as_integer()?)
+            .ok()
+            .and_then(char::from_u32)
+            .map(|c| Exp::Atom(Atom::Char(c)))
+            .ok_or_else(|| list[0].type_error("character code"))
+    });
+    env.insert_fn("char=?", |_, list| compare_chars("char=?", &list, |a, b| a == b));
+    env.insert_fn("char<?", |_, list| compare_chars("char<?", &list, |a, b| a < b));
+    env.insert_fn("char>?", |_, list| compare_chars("char>?", &list, |a, b| a > b));
+    env.insert_fn("char<=?", |_, list| compare_chars("char<=?", &list, |a, b| a <= b));
+    env.insert_fn("char>=?", |_, list| compare_chars("char>=?", &list, |a, b| a >= b));
+    env.insert_fn("char-ci=?", |_, list| {
+        compare_chars("char-ci=?", &list, |a, b| foldcase(a) == foldcase(b))
+    });
+    env.insert_fn("char-ci<?", |_, list| {
+        compare_chars("char-ci<?", &list, |a, b| foldcase(a) < foldcase(b))
+    });
+    env.insert_fn("char-ci>?", |_, list| {
+        compare_chars("char-ci>?", &list, |a, b| foldcase(a) > foldcase(b))
+    });
+    env.insert_fn("char-ci<=?", |_, list| {
+        compare_chars("char-ci<=?", &list, |a, b| foldcase(a) <= foldcase(b))
+    });
+    env.insert_fn("char-ci>=?", |_, list| {
+        compare_chars("char-ci>=?", &list, |a, b| foldcase(a) >= foldcase(b))
+    });
+    env.insert_fn("char-alphabetic?", |_, list| {
+        check_arity("char-alphabetic?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_char()?.is_alphabetic()))
+    });
+    env.insert_fn("char-numeric?", |_, list| {
+        check_arity("char-numeric?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_char()?.is_numeric()))
+    });
+    env.insert_fn("char-whitespace?", |_, list| {
+        check_arity("char-whitespace?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_char()?.is_whitespace()))
+    });
+    env.insert_fn("char-upper-case?", |_, list| {
+        check_arity("char-upper-case?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_char()?.is_uppercase()))
+    });
+    env.insert_fn("char-lower-case?", |_, list| {
+        check_arity("char-lower-case?", &list, 1)?;
+        Ok(Exp::bool(list[0].as_char()?.is_lowercase()))
+    });
+    env.insert_fn("char-upcase", |_, list| {
+        check_arity("char-upcase", &list, 1)?;
+        Ok(Exp::Atom(Atom::Char(upcase(list[0].as_char()?))))
+    });
+    env.insert_fn("char-downcase", |_, list| {
+        check_arity("char-downcase", &list, 1)?;
+        Ok(Exp::Atom(Atom::Char(downcase(list[0].as_char()?))))
+    });
+    env.insert_fn("char-foldcase", |_, list| {
+        check_arity("char-foldcase", &list, 1)?;
+        Ok(Exp::Atom(Atom::Char(foldcase(list[0].as_char()?))))
+    });
+}
+
+/// Returns the single character which `mapping` converts `c` to, or `c` if
+/// it converts it to several characters, as `ß` is converted to `SS`.
+fn simple_case(c: char, mut mapping: impl Iterator<Item = char>) -> char {
+    match (mapping.next(), mapping.next()) {
+        (Some(mapped), None) => mapped,
+        _ => c,
+    }
+}
+
+fn upcase(c: char) -> char {
+    simple_case(c, c.to_uppercase())
+}
+
+fn downcase(c: char) -> char {
+    simple_case(c, c.to_lowercase())
+}
+
+/// Returns the simple case folding of `c`, which is the same for the upper
+/// and lower case forms of a character, including final sigma.
+fn foldcase(c: char) -> char {
+    downcase(upcase(c))
+}
+
+/// Returns the full case folding of `s`, in which characters may fold to
+/// several characters, as `ß` folds to `ss`.
+fn string_foldcase(s: &str) -> String {
+    s.to_uppercase().chars().flat_map(char::to_lowercase).collect()
+}
+
+/// The result of a single evaluation step.
+///
+/// Expressions in tail position are returned as [EvalResult::TailCall]
+/// instead of being evaluated recursively, so that [eval] can run them in a
+/// loop without growing the Rust stack. These are:
+///
+/// - the body of a procedure, and calls made through `apply`
+/// - the branch chosen by `if`, and the body of the clause chosen by `cond` or
+///   `case`, including the call to the receiver of a `cond` clause of the form
+///   `(test => receiver)`
+/// - the last expression of `begin`, `when`, `unless`, `and` and `or`
+/// - the last expression in the body of `let`, `let*`, `letrec`, `let-values`,
+///   `let*-values` (also spelled `let-values*`), `receive`, `let-syntax` and
+///   `letrec-syntax`
+/// - the result expressions of `do`
+/// - the expansion of a macro use
+enum EvalResult {
+    Value(Exp),
+    TailCall(Exp, EnvId),
+}
+
+/// Evaluates `x` in the environment `env_id`.
+///
+/// Returns [VowError::RecursionDepthExceeded] if this call would nest more
+/// deeply than the recursion limit. Tail calls are evaluated in a loop, so
+/// they do not count towards the limit.
+pub fn eval(x: Exp, env_tree: &mut EnvTree, env_id: EnvId) -> Result<Exp, VowError> {
+    eval_inner(x, env_tree, env_id).map_err(VowError::into_unlocated)
+}
+
+/// Evaluates `x` in the environment `env_id` as [eval] does, returning any
+/// error along with where it was raised in the source code `x` was read from
+/// by [parse_spanned].
+pub fn eval_located(x: Exp, env_tree: &mut EnvTree, env_id: EnvId) -> Result<Exp, Located> {
+    eval_inner(x, env_tree, env_id).map_err(|error| match error {
+        VowError::Located(located) => *located,
+        error => Located { error, span: None, trace: vec![] },
+    })
+}
+
+/// Evaluates `x` on behalf of the interpreter itself, such as the operands of
+/// a special form. Errors raised by source code are returned in
+/// [VowError::Located], so that each stack frame they unwind through can add
+/// to their trace.
+fn eval_inner(x: Exp, env_tree: &mut EnvTree, env_id: EnvId) -> Result<Exp, VowError> {
+    let depth = EVAL_DEPTH.with(Cell::get);
+    let limit = RECURSION_LIMIT.with(Cell::get);
+    if depth >= limit {
+        return Err(VowError::RecursionDepthExceeded(limit));
+    }
+    EVAL_DEPTH.with(|d| d.set(depth + 1));
+    let result = eval_tail_calls(x, env_tree, env_id);
+    EVAL_DEPTH.with(|d| d.set(depth));
+    result
+}
+
+/// The recursion limit used unless the `VOW_RECURSION_LIMIT` environment
+/// variable is set.
+pub const DEFAULT_RECURSION_LIMIT: usize = 10_000;
+
+thread_local! {
+    /// The number of calls to [eval_inner] which have not yet returned.
+    static EVAL_DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// The maximum value of `EVAL_DEPTH`.
+    static RECURSION_LIMIT: Cell<usize> = Cell::new(
+        std::env::var("VOW_RECURSION_LIMIT")
+            .ok()
+            .and_then(|limit| limit.parse().ok())
+            .unwrap_or(DEFAULT_RECURSION_LIMIT),
+    );
+}
+
+/// Sets the maximum number of nested calls to [eval] on this thread.
+pub fn set_recursion_limit(limit: usize) {
+    RECURSION_LIMIT.with(|l| l.set(limit));
+}
+
+/// Evaluates `x` and every tail call it makes in the environment `env_id`.
+///
+/// An error raised here is located at the last expression read from source
+/// which this loop began to evaluate, and the first one is its call site.
+fn eval_tail_calls(x: Exp, env_tree: &mut EnvTree, env_id: EnvId) -> Result<Exp, VowError> {
+    let (mut x, mut env_id) = (x, env_id);
+    let (mut call_site, mut span) = (None, None);
+    loop {
+        if let Some((deadline, limit)) = DEADLINE.with(Cell::get) {
+            if Instant::now() >= deadline {
+                return Err(VowError::TimeLimitExceeded(limit));
+            }
+        }
+        while let Exp::Spanned(spanned) = x {
+            x = spanned.value.clone();
+            call_site.get_or_insert_with(|| Rc::clone(&spanned));
+            span = Some(spanned);
+        }
+        if STEPPING.with(Cell::get) && matches!(x, Exp::List(..) | Exp::Atom(Atom::Symbol(..))) {
+            pause_step(&x, env_tree, env_id)?;
+        }
+        match eval_step(x, env_tree, env_id) {
+            Ok(EvalResult::Value(exp)) => return Ok(exp),
+            Ok(EvalResult::TailCall(exp, id)) => (x, env_id) = (exp, id),
+            Err(error) => {
+                return Err(match (span, call_site) {
+                    (Some(span), Some(call_site)) => error.located(&span.span, &call_site.span),
+                    _ => error,
+                })
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// Whether [eval] pauses before each step, set while evaluating the
+    /// operand of `step`.
+    static STEPPING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Prints the expression `x` about to be evaluated in `env_id` along with the
+/// local bindings of that environment, then reads debugger commands from the
+/// current input port until one resumes evaluation:
+///
+/// - `n` or an empty line evaluates `x` and pauses again before the next step
+/// - `c` continues to completion without pausing
+/// - `p expr` prints the value of `expr` in `env_id` and waits for another
+///   command
+/// - `q` aborts evaluation with an error
+fn pause_step(x: &Exp, env_tree: &mut EnvTree, env_id: EnvId) -> Result<(), VowError> {
+    let output = output_port_arg(&[], 0)?;
+    let input = input_port_arg(&[], 0)?;
+    let mut text = format!("[STEP] {}\n", WriteFormat(x));
+    if let Some(env) = env_tree.get(env_id).filter(|env| env.outer.is_some()) {
+        let mut bindings: Vec<_> =
+            env.symbols.iter().filter(|(s, _)| !s.contains(ALIAS_SEPARATOR)).collect();
+        bindings.sort_by(|a, b| a.0.cmp(b.0));
+        for (symbol, value) in bindings {
+            text.push_str(&format!("  {symbol} = {}\n", WriteFormat(value)));
+        }
+    }
+    write_to_port(&output, &text)?;
+    loop {
+        write_to_port(&output, "step> ")?;
+        let line = input.with(|port| port.next_line())?;
+        match line.as_deref().map(str::trim) {
+            Some("" | "n") => return Ok(()),
+            None | Some("c") => {
+                STEPPING.with(|s| s.set(false));
+                return Ok(());
+            }
+            Some("q") => return Err(VowError::Custom("step: aborted".to_string())),
+            Some(command) if command.starts_with("p ") => {
+                STEPPING.with(|s| s.set(false));
+                let result = parse_str(&command[2..]).and_then(|exps| {
+                    exps.into_iter()
+                        .try_fold(Exp::List(vec![]), |_, exp| eval_inner(exp, env_tree, env_id))
+                });
+                STEPPING.with(|s| s.set(true));
+                let reply = match result {
+                    Ok(value) => format!("{}\n", WriteFormat(&value)),
+                    Err(error) => format!("{error}\n"),
+                };
+                write_to_port(&output, &reply)?;
+            }
+            Some(command) => {
+                let reply = format!("Unknown command '{command}': use n, c, p <expr> or q\n");
+                write_to_port(&output, &reply)?;
+            }
+        }
+    }
+}
+
+/// Returns an error unless the special form `list` has between `min` and
+/// `max` operands.
+fn check_form(list: &[Exp], min: usize, max: usize) -> Result<(), VowError> {
+    let got = list.len() - 1;
+    if (min..=max).contains(&got) {
+        Ok(())
+    } else {
+        let name = WriteFormat(&list[0]).to_string();
+        Err(VowError::ArityMismatch { name, expected: if got < min { min } else { max }, got })
+    }
+}
+
+/// Evaluates each expression in `body` in order, returning the last one as a
+/// tail call.
+fn eval_body(body: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    let Some((last, init)) = body.split_last() else {
+        return Ok(EvalResult::Value(Exp::List(vec![])));
+    };
+    for exp in init {
+        eval_inner(exp.clone(), env_tree, env_id)?;
+    }
+    Ok(EvalResult::TailCall(last.clone(), env_id))
+}
+
+/// Parses a binding list of the form `((name init) ...)`.
+fn parse_bindings(bindings: &Exp) -> Result<Vec<(Symbol, Exp)>, VowError> {
+    bindings
+        .as_exp_list()?
+        .iter()
+        .map(|binding| match binding.as_exp_list()?.as_slice() {
+            [name, init] => Ok((name.as_symbol()?, init.clone())),
+            _ => Err(binding.type_error("binding")),
+        })
+        .collect()
+}
+
+/// Evaluates `(let ((name init) ...) body ...)`, binding every name in a new
+/// environment after evaluating all of the initializers in the current one.
+fn eval_let(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    if matches!(list[1].unspanned(), Exp::Atom(Atom::Symbol(..))) {
+        return eval_named_let(list, env_tree, env_id);
+    }
+    let mut names = vec![];
+    let mut values = vec![];
+    for (name, init) in parse_bindings(&list[1])? {
+        names.push(name);
+        values.push(eval_inner(init, env_tree, env_id)?);
+    }
+    let let_env = Env::insert_into(env_tree, names, values, Some(env_id));
+    eval_body(&list[2..], env_tree, let_env)
+}
+
+/// Evaluates `(let loop ((name init) ...) body ...)`, binding `loop` to a
+/// procedure over the names in its own environment so that the body can call
+/// it recursively.
+fn eval_named_let(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 2, usize::MAX)?;
+    let loop_name = list[1].as_symbol()?;
+    let mut names = vec![];
+    let mut values = vec![];
+    for (name, init) in parse_bindings(&list[2])? {
+        names.push(name);
+        values.push(eval_inner(init, env_tree, env_id)?);
+    }
+    let loop_env = Env::insert_into(env_tree, vec![], vec![], Some(env_id));
+    let procedure = Exp::Procedure(Rc::new(Procedure::new(
+        ParameterList::Fixed(names),
+        sequence(&hoist_internal_defines(&list[3..])?),
+        loop_env,
+    )));
+    env_tree.get_mut(loop_env).unwrap().insert(loop_name, procedure.clone());
+    procedure.apply(env_tree, values)
+}
+
+/// Evaluates `(let* ((name init) ...) body ...)`, binding each name in its
+/// own environment so that later initializers can refer to earlier names.
+fn eval_let_star(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let mut current = env_id;
+    for (name, init) in parse_bindings(&list[1])? {
+        let value = eval_inner(init, env_tree, current)?;
+        current = Env::insert_into(env_tree, vec![name], vec![value], Some(current));
+    }
+    eval_body(&list[2..], env_tree, current)
+}
+
+/// Evaluates `(letrec ((name init) ...) body ...)`, evaluating every
+/// initializer in an environment where all of the names are already bound so
+/// that they can refer to each other.
+///
+/// The names are assigned once every initializer has been evaluated, unless
+/// `sequential` is set, as for `letrec*`, in which case each name is
+/// assigned as soon as its initializer has been evaluated so that later
+/// initializers can use its value.
+fn eval_letrec(
+    list: &[Exp],
+    sequential: bool,
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let bindings = parse_bindings(&list[1])?;
+    let names = bindings.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+    let placeholders = vec![Exp::Uninitialized; names.len()];
+    let letrec_env = Env::insert_into(env_tree, names.clone(), placeholders, Some(env_id));
+    let mut values = vec![];
+    for (name, init) in bindings {
+        let value = eval_inner(init, env_tree, letrec_env)?;
+        if sequential {
+            env_tree.get_mut(letrec_env).unwrap().insert(name, value);
+        } else {
+            values.push(value);
+        }
+    }
+    let env = env_tree.get_mut(letrec_env).unwrap();
+    for (name, value) in names.into_iter().zip(values) {
+        env.insert(name, value);
+    }
+    eval_body(&list[2..], env_tree, letrec_env)
+}
+
+/// Evaluates `(let-values ((formals init) ...) body ...)`, binding the values
+/// returned by each initializer to its formals, which may end in a rest
+/// parameter as in a lambda parameter list.
+///
+/// Every initializer is evaluated in the current environment unless
+/// `sequential` is set, as for `let*-values`, in which case each one can
+/// refer to the names bound before it.
+fn eval_let_values(
+    list: &[Exp],
+    sequential: bool,
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let mut current = env_id;
+    let mut names = vec![];
+    let mut values = vec![];
+    for binding in list[1].as_exp_list()? {
+        let [formals, init] = <[Exp; 2]>::try_from(binding.as_exp_list()?)
+            .map_err(|_| binding.type_error("binding"))?;
+        let formals = ParameterList::parse(&formals)?;
+        let (binding_names, binding_values) =
+            formals.bind(eval_inner(init, env_tree, current)?.into_values())?;
+        if sequential {
+            current = Env::insert_into(env_tree, binding_names, binding_values, Some(current));
+        } else {
+            names.extend(binding_names);
+            values.extend(binding_values);
+        }
+    }
+    if !sequential {
+        current = Env::insert_into(env_tree, names, values, Some(env_id));
+    }
+    eval_body(&list[2..], env_tree, current)
+}
+
+/// Evaluates `(receive formals expression body ...)`, binding the values
+/// returned by `expression` to `formals` for the body.
+fn eval_receive(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 2, usize::MAX)?;
+    let formals = ParameterList::parse(&list[1])?;
+    let (names, values) =
+        formals.bind(eval_inner(list[2].clone(), env_tree, env_id)?.into_values())?;
+    let receive_env = Env::insert_into(env_tree, names, values, Some(env_id));
+    eval_body(&list[3..], env_tree, receive_env)
+}
+
+/// Evaluates `(cond (test body ...) ... (else body ...))`.
+///
+/// The body of the first clause whose test is true is evaluated in tail
+/// position. A clause of the form `(test => proc)` instead applies `proc` to
+/// the value of the test.
+fn eval_cond(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    eval_cond_clauses(&list[1..], env_tree, env_id)?
+        .ok_or_else(|| VowError::Custom("No cond clause matched".to_string()))
+}
+
+/// Evaluates the clauses of a `cond` form, returning `None` if no clause
+/// matched.
+fn eval_cond_clauses(
+    clauses: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<Option<EvalResult>, VowError> {
+    for clause in clauses {
+        let clause = clause.as_exp_list()?;
+        let Some((test, body)) = clause.split_first() else {
+            return Err(VowError::ParseError("Empty cond clause".to_string(), None));
+        };
+        if test.is_symbol("else") {
+            return eval_body(body, env_tree, env_id).map(Some);
+        }
+        let value = eval_inner(test.clone(), env_tree, env_id)?;
+        if !value.is_true() {
+            continue;
+        }
+        return match body {
+            [] => Ok(EvalResult::Value(value)),
+            [arrow, receiver] if arrow.is_symbol("=>") => {
+                eval_inner(receiver.clone(), env_tree, env_id)?.apply(env_tree, vec![value])
+            }
+            _ => eval_body(body, env_tree, env_id),
+        }
+        .map(Some);
+    }
+    Ok(None)
+}
+
+/// Evaluates `(and exp ...)`, returning the first false value without
+/// evaluating the remaining expressions. The last expression is evaluated in
+/// tail position.
+fn eval_and(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    let Some((last, init)) = list[1..].split_last() else {
+        return Ok(EvalResult::Value(Exp::bool(true)));
+    };
+    for exp in init {
+        let value = eval_inner(exp.clone(), env_tree, env_id)?;
+        if !value.is_true() {
+            return Ok(EvalResult::Value(value));
+        }
+    }
+    Ok(EvalResult::TailCall(last.clone(), env_id))
+}
+
+/// Evaluates `(or exp ...)`, returning the first true value without
+/// evaluating the remaining expressions. The last expression is evaluated in
+/// tail position.
+fn eval_or(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    let Some((last, init)) = list[1..].split_last() else {
+        return Ok(EvalResult::Value(Exp::bool(false)));
+    };
+    for exp in init {
+        let value = eval_inner(exp.clone(), env_tree, env_id)?;
+        if value.is_true() {
+            return Ok(EvalResult::Value(value));
+        }
+    }
+    Ok(EvalResult::TailCall(last.clone(), env_id))
+}
+
+/// Evaluates `(when test body ...)`, or `(unless test body ...)` when
+/// `expected` is false, evaluating the body only if the test matches
+/// `expected`.
+fn eval_when(
+    list: &[Exp],
+    expected: bool,
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    if eval_inner(list[1].clone(), env_tree, env_id)?.is_true() == expected {
+        eval_body(&list[2..], env_tree, env_id)
+    } else {
+        Ok(EvalResult::Value(Exp::List(vec![])))
+    }
+}
+
+/// Evaluates `(case key ((datum ...) body ...) ... (else body ...))`.
+///
+/// The key is compared against the unevaluated datums of each clause in
+/// turn, and the body of the first matching clause is evaluated in tail
+/// position.
+fn eval_case(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let key = eval_inner(list[1].clone(), env_tree, env_id)?;
+    for clause in &list[2..] {
+        let clause = clause.as_exp_list()?;
+        let Some((datums, body)) = clause.split_first() else {
+            return Err(VowError::ParseError("Empty case clause".to_string(), None));
+        };
+        if datums.is_symbol("else")
+            || datums.as_exp_list()?.iter().any(|d| is_eqv(d.unspanned(), &key))
+        {
+            return eval_body(body, env_tree, env_id);
+        }
+    }
+    Ok(EvalResult::Value(Exp::List(vec![])))
+}
+
+/// Evaluates `(do ((var init step) ...) (test result ...) body ...)`.
+///
+/// Each iteration binds the variables in a fresh environment. The loop runs
+/// here rather than through recursive calls to [eval], and the result
+/// expressions are evaluated in tail position once the test is true.
+fn eval_do(list: &[Exp], env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    check_form(list, 2, usize::MAX)?;
+    let mut names = vec![];
+    let mut values = vec![];
+    let mut steps = vec![];
+    for spec in list[1].as_exp_list()? {
+        match spec.as_exp_list()?.as_slice() {
+            [name, init, rest @ ..] if rest.len() <= 1 => {
+                names.push(name.as_symbol()?);
+                values.push(eval_inner(init.clone(), env_tree, env_id)?);
+                steps.push(rest.first().cloned());
+            }
+            _ => return Err(spec.type_error("do binding")),
+        }
+    }
+    let exit = list[2].as_exp_list()?;
+    let Some((test, result)) = exit.split_first() else {
+        return Err(list[2].type_error("do test"));
+    };
+    loop {
+        let loop_env = Env::insert_into(env_tree, names.clone(), values.clone(), Some(env_id));
+        if eval_inner(test.clone(), env_tree, loop_env)?.is_true() {
+            return eval_body(result, env_tree, loop_env);
+        }
+        for exp in &list[3..] {
+            eval_inner(exp.clone(), env_tree, loop_env)?;
+        }
+        for (value, step) in values.iter_mut().zip(&steps) {
+            if let Some(step) = step {
+                *value = eval_inner(step.clone(), env_tree, loop_env)?;
+            }
+        }
+    }
+}
+
+/// Evaluates `(define name exp)`, or the procedure shorthand
+/// `(define (name parameter ...) body ...)`.
+fn eval_define(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 2, usize::MAX)?;
+    let (symbol, result) = if matches!(list[1].unspanned(), Exp::List(..) | Exp::Pair(..)) {
+        let name = list[1].car()?.as_symbol()?;
+        (name, make_procedure(&list[1].cdr()?, &list[2..], env_id)?)
+    } else {
+        check_form(list, 2, 2)?;
+        (list[1].as_symbol()?, eval_inner(list[2].clone(), env_tree, env_id)?)
+    };
+    env_tree.get_mut(env_id).unwrap().insert(symbol, result.clone());
+    Ok(EvalResult::Value(result))
+}
+
+/// Evaluates `(define-values formals expression)`, binding each of the values
+/// returned by `expression` to the corresponding name in `formals`. Formals
+/// may end in a rest parameter, as in a lambda parameter list.
+fn eval_define_values(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 2, 2)?;
+    let formals = ParameterList::parse(&list[1])?;
+    let values = eval_inner(list[2].clone(), env_tree, env_id)?.into_values();
+    let (names, values) = formals.bind(values)?;
+    let env = env_tree.get_mut(env_id).unwrap();
+    for (name, value) in names.into_iter().zip(values) {
+        env.insert(name, value);
+    }
+    Ok(EvalResult::Value(Exp::List(vec![])))
+}
+
+/// Expands the body of a `quasiquote` form.
+///
+/// `unquote` and `unquote-splicing` forms are evaluated when `depth` is zero.
+/// Each nested `quasiquote` increases the depth and each nested unquote form
+/// decreases it, so inner forms are preserved until their enclosing
+/// quasiquote is itself evaluated.
+fn eval_quasiquote(
+    template: &Exp,
+    depth: usize,
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<Exp, VowError> {
+    let Exp::List(list) = template.unspanned() else {
+        return Ok(template.strip_spans());
+    };
+    match list.as_slice() {
+        [head, exp] if head.is_symbol("unquote") => {
+            if depth == 0 {
+                eval_inner(exp.clone(), env_tree, env_id)
+            } else {
+                let exp = eval_quasiquote(exp, depth - 1, env_tree, env_id)?;
+                Ok(Exp::List(vec![head.strip_spans(), exp]))
+            }
+        }
+        [head, exp] if head.is_symbol("quasiquote") => {
+            let exp = eval_quasiquote(exp, depth + 1, env_tree, env_id)?;
+            Ok(Exp::List(vec![head.strip_spans(), exp]))
+        }
+        _ => {
+            let mut result = vec![];
+            for element in list {
+                match element.unspanned() {
+                    Exp::List(splice)
+                        if splice.len() == 2 && splice[0].is_symbol("unquote-splicing") =>
+                    {
+                        if depth == 0 {
+                            result.extend(
+                                eval_inner(splice[1].clone(), env_tree, env_id)?.as_exp_list()?,
+                            );
+                        } else {
+                            let exp = eval_quasiquote(&splice[1], depth - 1, env_tree, env_id)?;
+                            result.push(Exp::List(vec![splice[0].strip_spans(), exp]));
+                        }
+                    }
+                    _ => result.push(eval_quasiquote(element, depth, env_tree, env_id)?),
+                }
+            }
+            Ok(Exp::List(result))
+        }
+    }
+}
+
+/// Returns true if `head` is the symbol `name` and the program has not bound
+/// `name` visibly from `env_id`. Debugging forms such as `step` and `time` are
+/// not reserved words, so definitions of the same name take precedence.
+fn is_unbound_form(head: &Exp, name: &str, env_tree: &EnvTree, env_id: EnvId) -> bool {
+    head.is_symbol(name) && resolve(env_tree, env_id, name).is_err()
+}
+
+fn eval_step(x: Exp, env_tree: &mut EnvTree, env_id: EnvId) -> Result<EvalResult, VowError> {
+    match x {
+        Exp::Atom(Atom::Symbol(s)) => match resolve(env_tree, env_id, &s)? {
+            Exp::Uninitialized => {
+                Err(VowError::Custom(format!("'{s}' used before it was initialized")))
+            }
+            exp => Ok(EvalResult::Value(exp)),
+        },
+        Exp::Atom(Atom::Integer(..)) => Ok(EvalResult::Value(x)),
+        Exp::Atom(Atom::Number(..)) => Ok(EvalResult::Value(x)),
+        Exp::Atom(Atom::Complex(..)) => Ok(EvalResult::Value(x)),
+        Exp::Atom(Atom::Bool(..)) => Ok(EvalResult::Value(x)),
+        Exp::Atom(Atom::String(..)) => Ok(EvalResult::Value(x)),
+        Exp::Atom(Atom::Char(..)) => Ok(EvalResult::Value(x)),
+        Exp::Function(..) => Ok(EvalResult::Value(x)),
+        Exp::Procedure(..) => Ok(EvalResult::Value(x)),
+        Exp::Continuation(..) => Ok(EvalResult::Value(x)),
+        Exp::Values(..) => Ok(EvalResult::Value(x)),
+        Exp::Promise(..) => Ok(EvalResult::Value(x)),
+        Exp::Transformer(..) => Ok(EvalResult::Value(x)),
+        Exp::RecordType(..) | Exp::Record { .. } | Exp::RecordProcedure(..) => {
+            Ok(EvalResult::Value(x))
+        }
+        Exp::ErrorObject { .. } => Ok(EvalResult::Value(x)),
+        Exp::Parameter(..) => Ok(EvalResult::Value(x)),
+        Exp::HashTable(..) => Ok(EvalResult::Value(x)),
+        Exp::Environment(..) => Ok(EvalResult::Value(x)),
+        Exp::Traced(..) => Ok(EvalResult::Value(x)),
+        Exp::Spanned(spanned) => Ok(EvalResult::TailCall(spanned.value.clone(), env_id)),
+        Exp::Apply => Ok(EvalResult::Value(x)),
+        Exp::Pair(..) => Err(VowError::Custom(format!("Cannot evaluate {}", WriteFormat(&x)))),
+        Exp::Vector(..) | Exp::Bytevector(..) => Ok(EvalResult::Value(x)),
+        Exp::InputPort(..) | Exp::OutputPort(..) => Ok(EvalResult::Value(x)),
+        Exp::Atom(Atom::Eof) => Ok(EvalResult::Value(x)),
+        Exp::Uninitialized => Ok(EvalResult::Value(x)),
+        Exp::List(list) if list.is_empty() => {
+            Err(VowError::Custom("Cannot evaluate empty list".to_string()))
+        }
+        Exp::List(list) if list[0].is_symbol("quote") => {
+            check_form(&list, 1, 1)?;
+            Ok(EvalResult::Value(list[1].strip_spans()))
+        }
+        Exp::List(list) if list[0].is_symbol("quasiquote") => {
+            check_form(&list, 1, 1)?;
+            Ok(EvalResult::Value(eval_quasiquote(&list[1], 0, env_tree, env_id)?))
+        }
+        Exp::List(list) if list[0].is_symbol("if") => {
+            check_form(&list, 3, 3)?;
+            let result = if eval_inner(list[1].clone(), env_tree, env_id)?.is_true() {
+                list[2].clone()
+            } else {
+                list[3].clone()
+            };
+            Ok(EvalResult::TailCall(result, env_id))
+        }
+        Exp::List(list) if list[0].is_symbol("and") => eval_and(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("or") => eval_or(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("when") => eval_when(&list, true, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("unless") => eval_when(&list, false, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("begin") => eval_body(&list[1..], env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("let") => eval_let(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("let*") => eval_let_star(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("letrec") => {
+            eval_letrec(&list, false, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("letrec*") => {
+            eval_letrec(&list, true, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("let-values") => {
+            eval_let_values(&list, false, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("let*-values") || list[0].is_symbol("let-values*") => {
+            eval_let_values(&list, true, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("receive") => eval_receive(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("cond") => eval_cond(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("case") => eval_case(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("do") => eval_do(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("guard") => eval_guard(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("parameterize") => {
+            eval_parameterize(&list, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("define") => eval_define(&list, env_tree, env_id),
+        Exp::List(list) if list[0].is_symbol("delay") => eval_delay(&list, false, env_id),
+        Exp::List(list) if list[0].is_symbol("delay-force") || list[0].is_symbol("lazy") => {
+            eval_delay(&list, true, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("stream-cons") => {
+            eval_stream_cons(&list, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("define-values") => {
+            eval_define_values(&list, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("define-record-type") => {
+            eval_define_record_type(&list, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("define-syntax") => {
+            eval_define_syntax(&list, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("let-syntax") => {
+            eval_let_syntax(&list, false, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("letrec-syntax") => {
+            eval_let_syntax(&list, true, env_tree, env_id)
+        }
+        Exp::List(list) if list[0].is_symbol("syntax-rules") => {
+            let transformer = SyntaxTransformer::parse(&list, env_id)?;
+            Ok(EvalResult::Value(Exp::Transformer(Rc::new(transformer))))
+        }
+        Exp::List(list) if list[0].is_symbol("set!") => {
+            check_form(&list, 2, 2)?;
+            let symbol = list[1].as_symbol()?;
+            let exp = list[2].clone();
+            let evaluated = eval_inner(exp, env_tree, env_id)?;
+            let (target_id, symbol) = lookup(env_tree, env_id, &symbol)?;
+            env_tree.get_mut(target_id).unwrap().insert(symbol, evaluated);
+            Ok(EvalResult::Value(Exp::Atom(Atom::Bool(true))))
+        }
+        Exp::List(list) if is_unbound_form(&list[0], "trace", env_tree, env_id) => {
+            check_form(&list, 1, 1)?;
+            trace(env_tree, env_id, &list[1].as_symbol()?)?;
+            Ok(EvalResult::Value(Exp::List(vec![])))
+        }
+        Exp::List(list) if is_unbound_form(&list[0], "untrace", env_tree, env_id) => {
+            check_form(&list, 1, 1)?;
+            untrace(env_tree, env_id, &list[1].as_symbol()?)?;
+            Ok(EvalResult::Value(Exp::List(vec![])))
+        }
+        Exp::List(list) if is_unbound_form(&list[0], "step", env_tree, env_id) => {
+            check_form(&list, 1, 1)?;
+            let stepping = STEPPING.with(|s| s.replace(true));
+            let result = eval_inner(list[1].clone(), env_tree, env_id);
+            STEPPING.with(|s| s.set(stepping));
+            Ok(EvalResult::Value(result?))
+        }
+        Exp::List(list) if is_unbound_form(&list[0], "time", env_tree, env_id) => {
+            check_form(&list, 1, 1)?;
+            let start = Instant::now();
+            let result = eval_inner(list[1].clone(), env_tree, env_id)?;
+            let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+            eprintln!("(time {}: {elapsed:.3}ms)", WriteFormat(&result));
+            Ok(EvalResult::Value(result))
+        }
+        Exp::List(list) if is_unbound_form(&list[0], "the-environment", env_tree, env_id) => {
+            check_form(&list, 0, 0)?;
+            Ok(EvalResult::Value(Exp::Environment(env_id)))
+        }
+        Exp::List(list) if is_unbound_form(&list[0], "defined?", env_tree, env_id) => {
+            check_form(&list, 1, 1)?;
+            let symbol = eval_inner(list[1].clone(), env_tree, env_id)?.as_symbol()?;
+            Ok(EvalResult::Value(Exp::bool(resolve(env_tree, env_id, &symbol).is_ok())))
+        }
+        Exp::List(list) if list[0].is_symbol("lambda") => {
+            check_form(&list, 2, usize::MAX)?;
+            Ok(EvalResult::Value(make_procedure(&list[1], &list[2..], env_id)?))
+        }
+        Exp::List(list) => {
+            let proc = eval_inner(list[0].clone(), env_tree, env_id)?;
+            if let Exp::Transformer(transformer) = proc {
+                return Ok(EvalResult::TailCall(transformer.expand(&list)?, env_id));
+            }
+            let mut args: List = vec![];
+            for x in list.iter().skip(1) {
+                args.push(eval_inner(x.clone(), env_tree, env_id)?);
+            }
+            proc.apply(env_tree, args)
+        }
+    }
+}
+
+/// Evaluates `(parameterize ((parameter value) ...) body ...)`, giving each
+/// parameter its value for the duration of the body.
+///
+/// The previous values are restored however control leaves the body, whether
+/// it returns normally, raises an error or escapes to an outer continuation.
+fn eval_parameterize(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let mut parameters = vec![];
+    let mut values = vec![];
+    for binding in list[1].as_exp_list()? {
+        let [parameter, value] = <[Exp; 2]>::try_from(binding.as_exp_list()?)
+            .map_err(|_| binding.type_error("binding"))?;
+        let parameter = match eval_inner(parameter, env_tree, env_id)? {
+            Exp::Parameter(parameter) => parameter,
+            exp => return Err(exp.type_error("parameter")),
+        };
+        let value = eval_inner(value, env_tree, env_id)?;
+        values.push(match &parameter.converter {
+            Some(converter) => converter.invoke(env_tree, vec![value])?,
+            None => value,
+        });
+        parameters.push(parameter);
+    }
+    let saved =
+        parameters.iter().zip(values).map(|(p, value)| p.value.replace(value)).collect::<Vec<_>>();
+    let result = eval_inner(sequence(&list[2..]), env_tree, env_id);
+    for (parameter, value) in parameters.iter().zip(saved) {
+        parameter.value.replace(value);
+    }
+    result.map(EvalResult::Value)
+}
+
+/// Evaluates `(delay expression)`, or `(delay-force expression)` if
+/// `delay_force` is true. SRFI 45 calls `delay-force` `lazy`.
+fn eval_delay(list: &[Exp], delay_force: bool, env_id: EnvId) -> Result<EvalResult, VowError> {
+    check_form(list, 1, 1)?;
+    let state = if delay_force {
+        PromiseState::PendingForce(list[1].clone(), env_id)
+    } else {
+        PromiseState::Pending(list[1].clone(), env_id)
+    };
+    Ok(EvalResult::Value(Exp::Promise(Rc::new(RefCell::new(state)))))
+}
+
+/// Evaluates `(stream-cons head tail)`, which evaluates `head` and delays
+/// `tail`.
+fn eval_stream_cons(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 2, 2)?;
+    let head = eval_inner(list[1].clone(), env_tree, env_id)?;
+    let tail = PromiseState::Pending(list[2].clone(), env_id);
+    Ok(EvalResult::Value(Exp::cons(head, Exp::Promise(Rc::new(RefCell::new(tail))))))
+}
+
+/// A record type created by `define-record-type`.
+#[derive(Debug)]
+pub struct RecordType {
+    pub name: Symbol,
+    pub fields: Vec<Symbol>,
+}
+
+impl RecordType {
+    /// Returns the name of this type without the angle brackets
+    /// conventionally written around record type names.
+    pub fn display_name(&self) -> &str {
+        self.name.strip_prefix('<').and_then(|n| n.strip_suffix('>')).unwrap_or(&self.name)
+    }
+}
+
+/// A procedure created by `define-record-type` for records of one type.
+#[derive(Clone, Debug)]
+pub struct RecordProcedure {
+    pub name: Symbol,
+    pub record_type: Rc<RecordType>,
+    pub operation: RecordOperation,
+}
+
+#[derive(Clone, Debug)]
+pub enum RecordOperation {
+    /// Creates a record, initializing the fields at these indices from the
+    /// arguments in order.
+    Constructor(Vec<usize>),
+    Predicate,
+    Accessor(usize),
+    Modifier(usize),
+}
+
+impl RecordProcedure {
+    pub fn call(&self, mut arguments: List) -> Result<Exp, VowError> {
+        match &self.operation {
+            RecordOperation::Constructor(indices) => {
+                check_arity(&self.name, &arguments, indices.len())?;
+                let mut fields = vec![Exp::List(vec![]); self.record_type.fields.len()];
+                for (index, value) in indices.iter().zip(arguments) {
+                    fields[*index] = value;
+                }
+                Ok(Exp::Record {
+                    record_type: Rc::clone(&self.record_type),
+                    fields: Rc::new(RefCell::new(fields)),
+                })
+            }
+            RecordOperation::Predicate => {
+                check_arity(&self.name, &arguments, 1)?;
+                Ok(Exp::bool(matches!(
+                    &arguments[0],
+                    Exp::Record { record_type, .. } if Rc::ptr_eq(record_type, &self.record_type)
+                )))
+            }
+            RecordOperation::Accessor(index) => {
+                check_arity(&self.name, &arguments, 1)?;
+                Ok(self.fields(&arguments[0])?.borrow()[*index].clone())
+            }
+            RecordOperation::Modifier(index) => {
+                check_arity(&self.name, &arguments, 2)?;
+                let fields = self.fields(&arguments[0])?;
+                fields.borrow_mut()[*index] = arguments.pop().unwrap();
+                Ok(Exp::List(vec![]))
+            }
+        }
+    }
+
+    /// Returns the fields of `exp`, which must be a record of this
+    /// procedure's type.
+    fn fields(&self, exp: &Exp) -> Result<Rc<RefCell<Vec<Exp>>>, VowError> {
+        match exp {
+            Exp::Record { record_type, fields } if Rc::ptr_eq(record_type, &self.record_type) => {
+                Ok(Rc::clone(fields))
+            }
+            _ => Err(VowError::Custom(format!(
+                "{}: expected a {} record, got {}",
+                self.name,
+                self.record_type.display_name(),
+                WriteFormat(exp)
+            ))),
+        }
+    }
+}
+
+/// Evaluates `(define-record-type name (constructor field ...) predicate
+/// (field accessor [modifier]) ...)`, binding `name` to a new record type
+/// along with procedures to create records of that type and to access their
+/// fields. The constructor may be `#f` to omit it.
+fn eval_define_record_type(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 3, usize::MAX)?;
+    let name = list[1].as_symbol()?;
+    let mut fields = vec![];
+    let mut field_procedures = vec![];
+    for spec in &list[4..] {
+        match spec.as_exp_list()?.as_slice() {
+            [field, accessor, modifier @ ..] if modifier.len() <= 1 => {
+                let index = fields.len();
+                fields.push(field.as_symbol()?);
+                field_procedures.push((accessor.as_symbol()?, RecordOperation::Accessor(index)));
+                if let Some(modifier) = modifier.first() {
+                    field_procedures
+                        .push((modifier.as_symbol()?, RecordOperation::Modifier(index)));
+                }
+            }
+            _ => return Err(spec.type_error("record field")),
+        }
+    }
+    let mut procedures = vec![(list[3].as_symbol()?, RecordOperation::Predicate)];
+    if !matches!(list[2], Exp::Atom(Atom::Bool(false))) {
+        let constructor = list[2].as_exp_list()?;
+        let Some((constructor_name, arguments)) = constructor.split_first() else {
+            return Err(list[2].type_error("record constructor"));
+        };
+        let indices = arguments
+            .iter()
+            .map(|argument| {
+                let argument = argument.as_symbol()?;
+                fields.iter().position(|field| *field == argument).ok_or_else(|| {
+                    VowError::Custom(format!("define-record-type: unknown field {argument}"))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        procedures.push((constructor_name.as_symbol()?, RecordOperation::Constructor(indices)));
+    }
+    procedures.extend(field_procedures);
+    let record_type = Rc::new(RecordType { name: name.clone(), fields });
+    let env = env_tree.get_mut(env_id).unwrap();
+    env.insert(name, Exp::RecordType(Rc::clone(&record_type)));
+    for (name, operation) in procedures {
+        let record_type = Rc::clone(&record_type);
+        let procedure = RecordProcedure { name: name.clone(), record_type, operation };
+        env.insert(name, Exp::RecordProcedure(Rc::new(procedure)));
+    }
+    Ok(EvalResult::Value(Exp::List(vec![])))
+}
+
+/// Evaluates `(define-syntax keyword transformer)`, binding `keyword` to the
+/// macro transformer produced by evaluating `transformer`.
+fn eval_define_syntax(
+    list: &[Exp],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 2, 2)?;
+    let keyword = list[1].as_symbol()?;
+    let transformer = eval_inner(list[2].clone(), env_tree, env_id)?;
+    if !matches!(transformer, Exp::Transformer(..)) {
+        return Err(transformer.type_error("syntax transformer"));
+    }
+    env_tree.get_mut(env_id).unwrap().insert(keyword, transformer);
+    Ok(EvalResult::Value(Exp::List(vec![])))
+}
+
+/// Evaluates `(let-syntax ((keyword transformer) ...) body ...)`, or
+/// `letrec-syntax` if `recursive` is true, binding each keyword to a macro
+/// transformer in a new environment for the body.
+///
+/// The transformers of `let-syntax` are evaluated in the enclosing
+/// environment, while those of `letrec-syntax` are evaluated in the new one so
+/// that their expansions can use each other.
+fn eval_let_syntax(
+    list: &[Exp],
+    recursive: bool,
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<EvalResult, VowError> {
+    check_form(list, 1, usize::MAX)?;
+    let syntax_env = Env::insert_into(env_tree, vec![], vec![], Some(env_id));
+    let transformer_env = if recursive { syntax_env } else { env_id };
+    for (keyword, init) in parse_bindings(&list[1])? {
+        let transformer = eval_inner(init, env_tree, transformer_env)?;
+        if !matches!(transformer, Exp::Transformer(..)) {
+            return Err(transformer.type_error("syntax transformer"));
+        }
+        env_tree.get_mut(syntax_env).unwrap().insert(keyword, transformer);
+    }
+    eval_body(&list[2..], env_tree, syntax_env)
+}
+
+/// Begins the name of every symbol created by `gensym`. The reader rejects
+/// tokens with this prefix, so generated symbols never appear in source code.
+const GENSYM_PREFIX: &str = "#:";
+
+/// The number of symbols created by `gensym`, which makes each one unique.
+static NEXT_GENSYM_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Separates the name of an identifier introduced by a macro expansion from
+/// the suffix which makes it unique to that expansion.
+const ALIAS_SEPARATOR: char = '·';
+
+thread_local! {
+    static NEXT_ALIAS_ID: Cell<u64> = const { Cell::new(0) };
+    /// The environment of the macro which introduced each alias.
+    static ALIASES: RefCell<HashMap<Symbol, EnvId>> = RefCell::new(HashMap::new());
+}
+
+/// Returns a fresh alias for `symbol`, introduced by a macro defined in
+/// `env_id`.
+fn make_alias(symbol: &str, env_id: EnvId) -> Symbol {
+    let id = NEXT_ALIAS_ID.with(|next| next.replace(next.get() + 1));
+    let alias = format!("{symbol}{ALIAS_SEPARATOR}{id}");
+    ALIASES.with(|aliases| aliases.borrow_mut().insert(alias.clone(), env_id));
+    alias
+}
+
+/// Returns the identifier renamed by `alias` and the environment of the macro
+/// which introduced it, or `None` if `alias` is not an alias.
+fn alias_target(alias: &str) -> Option<(Symbol, EnvId)> {
+    let env_id = ALIASES.with(|aliases| aliases.borrow().get(alias).copied())?;
+    let (renamed, _) = alias.rsplit_once(ALIAS_SEPARATOR)?;
+    Some((renamed.to_string(), env_id))
+}
+
+/// Returns the name of the identifier written in the source from which
+/// `symbol` was introduced, removing any aliases added by macro expansion.
+fn unaliased(symbol: &str) -> &str {
+    symbol.split_once(ALIAS_SEPARATOR).map_or(symbol, |(name, _)| name)
+}
+
+/// A macro transformer created by `syntax-rules`.
+///
+/// Expansion is hygienic: identifiers introduced by a template are renamed to
+/// fresh aliases, so they cannot capture or be captured by identifiers in the
+/// macro call, and aliases which the expansion does not bind refer to the
+/// bindings visible where the macro was defined.
+#[derive(Clone, Debug)]
+pub struct SyntaxTransformer {
+    pub literals: Vec<Symbol>,
+    pub ellipsis: Symbol,
+    /// `(pattern template)` rules, tried in order.
+    pub rules: Vec<(Exp, Exp)>,
+    pub env: EnvId,
+}
+
+/// The part of a macro call matched by a pattern variable. A variable which
+/// is followed by `n` ellipses in its pattern is nested `n` levels deep.
+#[derive(Clone, Debug)]
+enum MatchBinding {
+    One(Exp),
+    Many(Vec<MatchBinding>),
+}
+
+type Bindings = HashMap<Symbol, MatchBinding>;
+
+/// Splits a list or improper list into its elements and its final cdr, which
+/// is the empty list for a proper list.
+fn split_tail(exp: &Exp) -> (Vec<Exp>, Exp) {
+    let mut elements = vec![];
+    let mut tail = exp;
+    while let Exp::Pair(car, cdr) = tail {
+        elements.push(car.as_ref().clone());
+        tail = cdr;
+    }
+    match tail {
+        Exp::List(list) => {
+            elements.extend(list.iter().cloned());
+            (elements, Exp::List(vec![]))
+        }
+        _ => (elements, tail.clone()),
+    }
+}
+
+impl SyntaxTransformer {
+    /// Parses `(syntax-rules (literal ...) (pattern template) ...)`, or
+    /// `(syntax-rules ellipsis (literal ...) (pattern template) ...)` with a
+    /// custom ellipsis identifier.
+    pub fn parse(list: &[Exp], env_id: EnvId) -> Result<Self, VowError> {
+        check_form(list, 1, usize::MAX)?;
+        let list: Vec<_> = list.iter().map(Exp::strip_spans).collect();
+        let (ellipsis, rest) = match &list[1] {
+            Exp::Atom(Atom::Symbol(ellipsis)) => {
+                check_form(&list, 2, usize::MAX)?;
+                (ellipsis.clone(), &list[2..])
+            }
+            _ => ("...".to_string(), &list[1..]),
+        };
+        let literals = rest[0].as_symbol_list()?;
+        let rules = rest[1..]
+            .iter()
+            .map(|rule| match rule.as_exp_list()?.as_slice() {
+                [pattern @ (Exp::List(..) | Exp::Pair(..)), template] => {
+                    Ok((pattern.clone(), template.clone()))
+                }
+                _ => Err(rule.type_error("syntax rule")),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { literals, ellipsis, rules, env: env_id })
+    }
+
+    /// Expands the macro call `form` using the first rule whose pattern
+    /// matches it.
+    pub fn expand(&self, form: &[Exp]) -> Result<Exp, VowError> {
+        let arguments = Exp::List(form[1..].to_vec());
+        for (pattern, template) in &self.rules {
+            let mut bindings = Bindings::new();
+            // The keyword position of a pattern is ignored.
+            if self.match_pattern(&pattern.cdr()?, &arguments, &mut bindings) {
+                return self.instantiate(template, &bindings, &mut HashMap::new(), false);
+            }
+        }
+        Err(VowError::Custom(format!(
+            "{}: no syntax rule matches {}",
+            WriteFormat(&form[0]),
+            WriteFormat(&Exp::List(form.to_vec()))
+        )))
+    }
+
+    fn is_ellipsis(&self, exp: &Exp) -> bool {
+        exp.is_symbol(unaliased(&self.ellipsis))
+    }
+
+    /// Matches `input` against `pattern`, adding the parts matched by each
+    /// pattern variable to `bindings`.
+    fn match_pattern(&self, pattern: &Exp, input: &Exp, bindings: &mut Bindings) -> bool {
+        let input = input.unspanned();
+        match pattern {
+            Exp::Atom(Atom::Symbol(s)) if self.literals.contains(s) => {
+                input.is_symbol(unaliased(s))
+            }
+            Exp::Atom(Atom::Symbol(_)) if pattern.is_symbol("_") => true,
+            Exp::Atom(Atom::Symbol(s)) => {
+                bindings.insert(s.clone(), MatchBinding::One(input.clone()));
+                true
+            }
+            Exp::List(list) if list.is_empty() => matches!(input, Exp::List(l) if l.is_empty()),
+            Exp::List(..) | Exp::Pair(..) => {
+                if !matches!(input, Exp::List(..) | Exp::Pair(..)) {
+                    return false;
+                }
+                let (patterns, tail) = split_tail(pattern);
+                let (inputs, input_tail) = split_tail(input);
+                self.match_sequence(&patterns, &tail, &inputs, &input_tail, bindings)
+            }
+            Exp::Vector(patterns) => {
+                let Exp::Vector(inputs) = input else {
+                    return false;
+                };
+                let (patterns, inputs) = (patterns.borrow(), inputs.borrow());
+                self.match_sequence(
+                    &patterns,
+                    &Exp::List(vec![]),
+                    &inputs,
+                    &Exp::List(vec![]),
+                    bindings,
+                )
+            }
+            _ => pattern == input,
+        }
+    }
+
+    /// Matches the elements and final cdr of a list against the elements and
+    /// tail of a list pattern, which may contain one element followed by an
+    /// ellipsis.
+    fn match_sequence(
+        &self,
+        patterns: &[Exp],
+        tail: &Exp,
+        inputs: &[Exp],
+        input_tail: &Exp,
+        bindings: &mut Bindings,
+    ) -> bool {
+        let proper = matches!(tail, Exp::List(list) if list.is_empty());
+        let Some(ellipsis) = patterns.iter().position(|p| self.is_ellipsis(p)) else {
+            if inputs.len() < patterns.len() || (proper && inputs.len() != patterns.len()) {
+                return false;
+            }
+            let (matched, rest) = inputs.split_at(patterns.len());
+            let rest =
+                rest.iter().rev().cloned().fold(input_tail.clone(), |cdr, car| Exp::cons(car, cdr));
+            return patterns.iter().zip(matched).all(|(p, i)| self.match_pattern(p, i, bindings))
+                && self.match_pattern(tail, &rest, bindings);
+        };
+        if ellipsis == 0 {
+            return false;
+        }
+        let (before, after) = (&patterns[..ellipsis - 1], &patterns[ellipsis + 1..]);
+        let repeated = &patterns[ellipsis - 1];
+        let Some(count) = inputs.len().checked_sub(before.len() + after.len()) else {
+            return false;
+        };
+        let mut matches = vec![];
+        for input in &inputs[before.len()..before.len() + count] {
+            let mut repetition = Bindings::new();
+            if !self.match_pattern(repeated, input, &mut repetition) {
+                return false;
+            }
+            matches.push(repetition);
+        }
+        for variable in self.pattern_variables(repeated) {
+            let values = matches.iter_mut().map(|m| m.remove(&variable).unwrap()).collect();
+            bindings.insert(variable, MatchBinding::Many(values));
+        }
+        before.iter().zip(inputs).all(|(p, i)| self.match_pattern(p, i, bindings))
+            && after
+                .iter()
+                .zip(&inputs[before.len() + count..])
+                .all(|(p, i)| self.match_pattern(p, i, bindings))
+            && self.match_pattern(tail, input_tail, bindings)
+    }
+
+    /// Returns the pattern variables bound by `pattern`.
+    fn pattern_variables(&self, pattern: &Exp) -> Vec<Symbol> {
+        match pattern {
+            Exp::Atom(Atom::Symbol(s)) => {
+                if self.literals.contains(s) || pattern.is_symbol("_") || self.is_ellipsis(pattern)
+                {
+                    vec![]
+                } else {
+                    vec![s.clone()]
+                }
+            }
+            Exp::List(list) if list.is_empty() => vec![],
+            Exp::List(..) | Exp::Pair(..) => {
+                let (elements, tail) = split_tail(pattern);
+                elements
+                    .iter()
+                    .chain(iter::once(&tail))
+                    .flat_map(|p| self.pattern_variables(p))
+                    .collect()
+            }
+            Exp::Vector(v) => v.borrow().iter().flat_map(|p| self.pattern_variables(p)).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Substitutes the pattern variables in `template` with their bindings.
+    ///
+    /// Every other identifier is replaced with an alias which is unique to this
+    /// expansion, recorded in `renames`, unless it appears inside quoted data.
+    fn instantiate(
+        &self,
+        template: &Exp,
+        bindings: &Bindings,
+        renames: &mut HashMap<Symbol, Symbol>,
+        quoted: bool,
+    ) -> Result<Exp, VowError> {
+        match template {
+            Exp::Atom(Atom::Symbol(s)) => match bindings.get(s) {
+                Some(MatchBinding::One(exp)) => Ok(exp.clone()),
+                Some(MatchBinding::Many(_)) => Err(VowError::Custom(format!(
+                    "pattern variable {s} must be followed by an ellipsis"
+                ))),
+                None if quoted => Ok(template.clone()),
+                None => {
+                    let alias = renames.entry(s.clone()).or_insert_with(|| make_alias(s, self.env));
+                    Ok(Exp::Atom(Atom::Symbol(alias.clone())))
+                }
+            },
+            Exp::List(list) => {
+                let quoted = match list.first() {
+                    Some(head) if head.is_symbol("quote") || head.is_symbol("quasiquote") => true,
+                    Some(head)
+                        if head.is_symbol("unquote") || head.is_symbol("unquote-splicing") =>
+                    {
+                        false
+                    }
+                    _ => quoted,
+                };
+                Ok(Exp::List(self.instantiate_elements(list, bindings, renames, quoted)?))
+            }
+            Exp::Pair(..) => {
+                let (elements, tail) = split_tail(template);
+                let tail = self.instantiate(&tail, bindings, renames, quoted)?;
+                Ok(self
+                    .instantiate_elements(&elements, bindings, renames, quoted)?
+                    .into_iter()
+                    .rev()
+                    .fold(tail, |cdr, car| Exp::cons(car, cdr)))
+            }
+            Exp::Vector(v) => Ok(Exp::vector(self.instantiate_elements(
+                &v.borrow(),
+                bindings,
+                renames,
+                quoted,
+            )?)),
+            _ => Ok(template.clone()),
+        }
+    }
+
+    /// Instantiates a sequence of templates, expanding each template followed
+    /// by one or more ellipses once for every match of its pattern variables.
+    fn instantiate_elements(
+        &self,
+        templates: &[Exp],
+        bindings: &Bindings,
+        renames: &mut HashMap<Symbol, Symbol>,
+        quoted: bool,
+    ) -> Result<Vec<Exp>, VowError> {
+        let mut result = vec![];
+        let mut i = 0;
+        while i < templates.len() {
+            let depth = templates[i + 1..].iter().take_while(|t| self.is_ellipsis(t)).count();
+            result.extend(self.instantiate_repeated(
+                &templates[i],
+                depth,
+                bindings,
+                renames,
+                quoted,
+            )?);
+            i += 1 + depth;
+        }
+        Ok(result)
+    }
+
+    /// Instantiates `template` once for every match of the pattern variables
+    /// it contains which are nested `depth` levels deep.
+    fn instantiate_repeated(
+        &self,
+        template: &Exp,
+        depth: usize,
+        bindings: &Bindings,
+        renames: &mut HashMap<Symbol, Symbol>,
+        quoted: bool,
+    ) -> Result<Vec<Exp>, VowError> {
+        if depth == 0 {
+            return Ok(vec![self.instantiate(template, bindings, renames, quoted)?]);
+        }
+        let variables = self
+            .pattern_variables(template)
+            .into_iter()
+            .filter_map(|v| match bindings.get(&v) {
+                Some(MatchBinding::Many(matches)) => Some((v, matches.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let Some((_, first)) = variables.first() else {
+            return Err(VowError::Custom(format!(
+                "no pattern variables to repeat in {}",
+                WriteFormat(template)
+            )));
+        };
+        let count = first.len();
+        if variables.iter().any(|(_, matches)| matches.len() != count) {
+            return Err(VowError::Custom(format!(
+                "pattern variables in {} matched different numbers of forms",
+                WriteFormat(template)
+            )));
+        }
+        let mut result = vec![];
+        for i in 0..count {
+            let mut repetition = bindings.clone();
+            for (variable, matches) in &variables {
+                repetition.insert(variable.clone(), matches[i].clone());
+            }
+            result.extend(self.instantiate_repeated(
+                template,
+                depth - 1,
+                &repetition,
+                renames,
+                quoted,
+            )?);
+        }
+        Ok(result)
+    }
+}
+
+/// A position in source code, used to report where an error occurred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// The line number, starting from 1.
+    pub line: u32,
+    /// The column number in characters, starting from 1.
+    pub col: u32,
+    /// The file the source code was read from, if any.
+    pub source: Option<String>,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, col {}", self.line, self.col)?;
+        if let Some(source) = &self.source {
+            write!(f, " of {source}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A value along with the location of the source code it was read from.
+#[derive(Clone, Debug)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+pub struct InPort<T: Read> {
+    pub file: BufReader<T>,
+    /// The unread remainder of the current line.
+    pub line: String,
+    /// The file being read, reported in the location of parse errors.
+    pub source: Option<String>,
+    /// The number of lines read so far, which is the number of the current
+    /// line.
+    line_number: u32,
+    /// The number of characters of the current line which have been consumed.
+    column: u32,
+    /// The location of the start of the last token returned by
+    /// [InPort::next_token].
+    token_start: (u32, u32),
+}
+
+impl<T: Read> InPort<T> {
+    pub fn new(file: T) -> Self {
+        InPort {
+            file: BufReader::new(file),
+            line: String::new(),
+            source: None,
+            line_number: 0,
+            column: 0,
+            token_start: (1, 1),
+        }
+    }
+
+    /// Returns the location of the start of the last token read.
+    pub fn token_span(&self) -> Span {
+        let (line, col) = self.token_start;
+        Span { line, col, source: self.source.clone() }
+    }
+
+    /// Adds the location of the last token read to a parse error which does
+    /// not have one.
+    fn locate(&self, error: VowError) -> VowError {
+        match error {
+            VowError::ParseError(message, None) => {
+                VowError::ParseError(message, Some(self.token_span()))
+            }
+            error => error,
+        }
+    }
+
+    /// Reads the next line into `self.line`, returning false at the end of
+    /// input.
+    fn read_line(&mut self) -> Result<bool, VowError> {
+        let read = self
+            .file
+            .read_line(&mut self.line)
+            .map_err(|e| VowError::Custom(format!("Error reading line: {e}")))?;
+        if read > 0 {
+            self.line_number += 1;
+            self.column = 0;
+        }
+        Ok(read > 0)
+    }
+
+    /// Returns the next character without consuming it, or `None` at the end
+    /// of input.
+    pub fn peek_char(&mut self) -> Result<Option<char>, VowError> {
+        if self.line.is_empty() {
+            self.read_line()?;
+        }
+        Ok(self.line.chars().next())
+    }
+
+    /// Consumes and returns the next character, or `None` at the end of input.
+    pub fn read_char(&mut self) -> Result<Option<char>, VowError> {
+        let c = self.peek_char()?;
+        if let Some(c) = c {
+            self.line.drain(..c.len_utf8());
+            self.column += 1;
+        }
+        Ok(c)
+    }
+
+    /// Returns the next byte without consuming it, or `None` at the end of
+    /// input.
+    ///
+    /// Text which has already been buffered by reading characters is read
+    /// first, which is only possible while it is ASCII.
+    pub fn peek_u8(&mut self) -> Result<Option<u8>, VowError> {
+        match self.line.bytes().next() {
+            Some(byte) if byte.is_ascii() => Ok(Some(byte)),
+            Some(_) => {
+                Err(VowError::Custom("cannot read bytes of a character read as text".to_string()))
+            }
+            None => {
+                let buffer = self
+                    .file
+                    .fill_buf()
+                    .map_err(|e| VowError::Custom(format!("Error reading port: {e}")))?;
+                Ok(buffer.first().copied())
+            }
+        }
+    }
+
+    /// Consumes and returns the next byte, or `None` at the end of input.
+    pub fn read_u8(&mut self) -> Result<Option<u8>, VowError> {
+        let byte = self.peek_u8()?;
+        if byte.is_some() {
+            if self.line.is_empty() {
+                self.file.consume(1);
+            } else {
+                self.line.drain(..1);
+                self.column += 1;
+            }
+        }
+        Ok(byte)
+    }
+
+    /// Consumes and returns the rest of the current line without its line
+    /// ending, or `None` at the end of input.
+    pub fn next_line(&mut self) -> Result<Option<String>, VowError> {
+        if self.line.is_empty() && !self.read_line()? {
+            return Ok(None);
+        }
+        let line = std::mem::take(&mut self.line);
+        let text = line.trim_end_matches(['\n', '\r']);
+        self.column += text.chars().count() as u32;
+        Ok(Some(text.to_string()))
+    }
+
+    pub fn next_token(&mut self) -> Result<Option<String>, VowError> {
+        static TOKENIZER: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r#"^\s*(,@|#\(|#u8\(|[('`,)]|"(?:[\\].|[^\\"])*"|;.*|#\\.[^\s('"`,;)]*|[^\s('"`,;)]*)"#,
+            )
+            .expect("valid regex")
+        });
+        let columns = |text: &str| text.chars().count() as u32;
+        loop {
+            if self.line.is_empty() && !self.read_line()? {
+                return Ok(None);
+            }
+            let captures = TOKENIZER.captures(&self.line).expect("captures");
+            let token_match = captures.get(1).expect("token capture");
+            self.token_start =
+                (self.line_number, self.column + columns(&self.line[..token_match.start()]) + 1);
+            let token = token_match.as_str().to_string();
+            let rest = &self.line[token_match.end()..];
+            if token.is_empty() && !rest.trim().is_empty() {
+                let rest = rest.trim_end_matches(['\r', '\n']);
+                return Err(VowError::ParseError(format!("Unterminated string {rest}"), None));
+            }
+            self.column += columns(&self.line[..token_match.end()]);
+            self.line.drain(..token_match.end());
+            if !token.is_empty() && !token.starts_with(';') {
+                return Ok(Some(token));
+            }
+        }
+    }
+}
+
+/// Returns the name of the special form abbreviated by a quote token.
+fn quote_symbol(s: &str) -> Option<&'static str> {
+    match s {
+        "'" => Some("quote"),
+        "`" => Some("quasiquote"),
+        "," => Some("unquote"),
+        ",@" => Some("unquote-splicing"),
+        _ => None,
+    }
+}
+
+/// The deepest nesting of lists, vectors and quotations the reader accepts,
+/// which keeps malformed input from overflowing the stack.
+const MAX_READ_DEPTH: usize = 1000;
+
+/// Reads the expression beginning with `token`, which is nested inside `depth`
+/// enclosing expressions.
+///
+/// If `code` is true the expression is source code which will be evaluated,
+/// so its non-empty lists and its symbols are wrapped in [Exp::Spanned] with
+/// the location of their first token. The elements of vectors are always
+/// read as data.
+fn read_ahead<T: Read>(
+    port: &mut InPort<T>,
+    token: String,
+    depth: usize,
+    code: bool,
+) -> Result<Exp, VowError> {
+    if depth > MAX_READ_DEPTH {
+        return Err(VowError::ParseError("Expression is nested too deeply".to_string(), None));
+    }
+    if token == "#u8(" {
+        let mut bytes = vec![];
+        loop {
+            let Some(next) = port.next_token()? else {
+                return Err(VowError::ParseError("End of Input".to_string(), None));
+            };
+            if next == ")" {
+                return Ok(Exp::bytevector(bytes));
+            }
+            match parse_number(&next, 10) {
+                Some(Atom::Integer(n)) if (0..=255).contains(&n) => bytes.push(n as u8),
+                _ => {
+                    return Err(VowError::ParseError(
+                        format!("Invalid byte {next} in bytevector"),
+                        None,
+                    ))
+                }
+            }
+        }
+    } else if token == "#(" {
+        let mut elements = vec![];
+        loop {
+            let Some(next) = port.next_token()? else {
+                return Err(VowError::ParseError("End of Input".to_string(), None));
+            };
+            if next == ")" {
+                return Ok(Exp::vector(elements));
+            }
+            elements.push(read_ahead(port, next, depth + 1, false)?);
+        }
+    } else if token == "(" {
+        let span = code.then(|| port.token_span());
+        let mut list: Vec<Exp> = vec![];
+        let exp = loop {
+            let Some(next) = port.next_token()? else {
+                return Err(VowError::ParseError("End of Input".to_string(), None));
+            };
+            if next == ")" {
+                break Exp::List(list);
+            } else if next == "." && !list.is_empty() {
+                break read_dotted_tail(port, list, depth, code)?;
+            } else {
+                list.push(read_ahead(port, next, depth + 1, code)?);
+            }
+        };
+        Ok(match (exp, span) {
+            (Exp::List(list), _) if list.is_empty() => Exp::List(list),
+            (value, Some(span)) => Exp::Spanned(Rc::new(Spanned { value, span })),
+            (exp, None) => exp,
+        })
+    } else if token == ")" {
+        Err(VowError::ParseError("Unexpected ')'".to_string(), None))
+    } else if let Some(symbol) = quote_symbol(&token) {
+        let Some(result) = read_nested(port, depth + 1, code)? else {
+            return Err(VowError::ParseError("Unexpected EOF".to_string(), None));
+        };
+        Ok(Exp::List(vec![Exp::Atom(Atom::Symbol(symbol.to_string())), result]))
+    } else {
+        Ok(match atom(token)? {
+            symbol @ Atom::Symbol(_) if code => {
+                let span = port.token_span();
+                Exp::Spanned(Rc::new(Spanned { value: Exp::Atom(symbol), span }))
+            }
+            atom => Exp::Atom(atom),
+        })
+    }
+}
+
+/// Reads the final element of a dotted list `(a b . c)` after the `.` token
+/// and combines it with the preceding `elements`.
+fn read_dotted_tail<T: Read>(
+    port: &mut InPort<T>,
+    elements: List,
+    depth: usize,
+    code: bool,
+) -> Result<Exp, VowError> {
+    let Some(tail) = read_nested(port, depth + 1, code)? else {
+        return Err(VowError::ParseError("End of Input".to_string(), None));
+    };
+    if port.next_token()?.as_deref() != Some(")") {
+        return Err(VowError::ParseError("Expected ')' after dotted tail".to_string(), None));
+    }
+    Ok(elements.into_iter().rev().fold(tail, |cdr, car| Exp::cons(car, cdr)))
+}
+
+fn read<T: Read>(port: &mut InPort<T>) -> Result<Option<Exp>, VowError> {
+    read_nested(port, 0, false)
+}
+
+/// Reads the next expression from `port`, which is nested inside `depth`
+/// enclosing expressions, as source code if `code` is true.
+fn read_nested<T: Read>(
+    port: &mut InPort<T>,
+    depth: usize,
+    code: bool,
+) -> Result<Option<Exp>, VowError> {
+    match port.next_token().map_err(|e| port.locate(e))? {
+        Some(token) => read_ahead(port, token, depth, code).map(Some).map_err(|e| port.locate(e)),
+        None => Ok(None),
+    }
+}
+
+/// Reads the next expression from `port` along with the location of its
+/// first token.
+fn read_spanned<T: Read>(port: &mut InPort<T>) -> Result<Option<Spanned<Exp>>, VowError> {
+    match port.next_token().map_err(|e| port.locate(e))? {
+        Some(token) => {
+            let span = port.token_span();
+            let value = read_ahead(port, token, 0, true).map_err(|e| port.locate(e))?;
+            Ok(Some(Spanned { value, span }))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Interprets the escape sequences in the contents of a string literal.
+fn unescape_string(contents: &str) -> Result<String, VowError> {
+    let mut result = String::with_capacity(contents.len());
+    let mut chars = contents.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('a') => '\x07',
+            Some('b') => '\x08',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('x') => {
+                // Hex escapes are terminated by a semicolon, as in "\x41;".
+                let hex: String = chars.by_ref().take_while(|c| *c != ';').collect();
+                u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).ok_or_else(|| {
+                    VowError::ParseError(format!("Invalid hex escape \\x{hex}; in string"), None)
+                })?
+            }
+            Some(other) => {
+                return Err(VowError::ParseError(
+                    format!("Unknown escape \\{other} in string"),
+                    None,
+                ));
+            }
+            None => return Err(VowError::ParseError("Unterminated string".to_string(), None)),
+        };
+        result.push(escaped);
+    }
+    Ok(result)
+}
+
+/// Formats a string as a literal which reads back as the same string.
+fn escape_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Names of characters which can be written as `#\name`, in the order in
+/// which they are preferred when printing.
+const CHAR_NAMES: &[(&str, char)] = &[
+    ("space", ' '),
+    ("newline", '\n'),
+    ("tab", '\t'),
+    ("return", '\r'),
+    ("null", '\0'),
+    ("alarm", '\x07'),
+    ("backspace", '\x08'),
+    ("escape", '\x1b'),
+    ("delete", '\x7f'),
+    ("altmode", '\x1b'),
+    ("rubout", '\x7f'),
+    ("nul", '\0'),
+    ("linefeed", '\n'),
+];
+
+/// Parses the text of a character literal following `#\`.
+fn parse_char(name: &str) -> Result<char, VowError> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c);
+    }
+    if let Some((_, c)) = CHAR_NAMES.iter().find(|(n, _)| *n == name) {
+        return Ok(*c);
+    }
+    name.strip_prefix('x')
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(char::from_u32)
+        .ok_or_else(|| VowError::ParseError(format!("Unknown character #\\{name}"), None))
+}
+
+fn atom(token: String) -> Result<Atom, VowError> {
+    if token.starts_with(GENSYM_PREFIX) {
+        return Err(VowError::ParseError(format!("Cannot read generated symbol {token}"), None));
+    }
+
+    if token == "#t" {
+        return Ok(Atom::Bool(true));
+    }
+
+    if token == "#f" {
+        return Ok(Atom::Bool(false));
+    }
+
+    if let Some(name) = token.strip_prefix("#\\") {
+        return Ok(Atom::Char(parse_char(name)?));
+    }
+
+    if let Some(contents) = token.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Ok(Atom::String(Rc::new(RefCell::new(unescape_string(contents)?))));
+    }
+
+    // Tokens such as `#xff` and `#e1.0` are numbers with an explicit radix or
+    // exactness.
+    let mut chars = token.chars();
+    if chars.next() == Some('#') && chars.next().is_some_and(|c| "bodxeiBODXEI".contains(c)) {
+        return parse_number(&token, 10)
+            .ok_or_else(|| VowError::ParseError(format!("Invalid number literal {token}"), None));
+    }
+
+    Ok(parse_number(&token, 10).unwrap_or(Atom::Symbol(token)))
+}
+
+/// Parses a numeric literal, returning `None` if `token` is not a number.
+///
+/// The literal may begin with at most one radix prefix (`#b`, `#o`, `#d` or
+/// `#x`), which overrides `default_radix`, and at most one exactness prefix
+/// (`#e` or `#i`), in either order.
+fn parse_number(token: &str, default_radix: u32) -> Option<Atom> {
+    let mut radix = None;
+    let mut exact = None;
+    let mut digits = token;
+    while let Some(rest) = digits.strip_prefix('#') {
+        let mut chars = rest.chars();
+        match chars.next()?.to_ascii_lowercase() {
+            'b' if radix.is_none() => radix = Some(2),
+            'o' if radix.is_none() => radix = Some(8),
+            'd' if radix.is_none() => radix = Some(10),
+            'x' if radix.is_none() => radix = Some(16),
+            'e' if exact.is_none() => exact = Some(true),
+            'i' if exact.is_none() => exact = Some(false),
+            _ => return None,
+        }
+        digits = chars.as_str();
+    }
+
+    let number = match radix.unwrap_or(default_radix) {
+        10 => parse_decimal(digits)?,
+        radix => Atom::Integer(Integer::from_str_radix(digits, radix).ok()?),
+    };
+    match (exact, number) {
+        (Some(false), Atom::Integer(n)) => Some(Atom::Number(n as Number)),
+        (Some(true), Atom::Number(n)) => {
+            (n.fract() == 0.0 && n >= Integer::MIN as Number && n < Integer::MAX as Number)
+                .then_some(Atom::Integer(n as Integer))
+        }
+        (Some(true), Atom::Complex(..)) => None,
+        (_, number) => Some(number),
+    }
+}
+
+/// Parses a decimal integer, real or complex number.
+fn parse_decimal(token: &str) -> Option<Atom> {
+    // Rust accepts words like "inf" and "i" as numbers, which must remain
+    // symbols here.
+    if !token.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '+' | '-' | '.')) {
+        return None;
+    }
+
+    if let Ok(n) = token.parse::<Integer>() {
+        Some(Atom::Integer(n))
+    } else if let Some(n) = special_number(token) {
+        Some(Atom::Number(n))
+    } else if let Ok(n) = token.parse::<Number>() {
+        Some(Atom::Number(n))
+    } else if let Ok(n) = Complex64::from_str(token) {
+        Some(Atom::Complex(n))
+    } else {
+        None
+    }
+}
+
+/// Parses the infinities and NaN written by [number_to_string].
+fn special_number(token: &str) -> Option<Number> {
+    match token {
+        "+inf.0" => Some(Number::INFINITY),
+        "-inf.0" => Some(Number::NEG_INFINITY),
+        "+nan.0" | "-nan.0" => Some(Number::NAN),
+        _ => None,
+    }
+}
+
+/// Formats an exact integer in `radix`, using lowercase letters for digits
+/// above 9.
+fn integer_to_string(n: Integer, radix: u32) -> String {
+    let mut magnitude = n.unsigned_abs();
+    let mut digits = vec![];
+    loop {
+        digits.push(char::from_digit((magnitude % radix as u64) as u32, radix).unwrap());
+        magnitude /= radix as u64;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        digits.push('-');
+    }
+    digits.iter().rev().collect()
+}
+
+/// Formats an inexact number so that it is distinguishable from an exact
+/// integer, e.g. `3.0` instead of `3`.
+fn number_to_string(n: Number) -> String {
+    if n.is_nan() {
+        "+nan.0".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "+inf.0" } else { "-inf.0" }.to_string()
+    } else {
+        format!("{n:?}")
+    }
+}
+
+/// Formats a value as it is printed by `display`, which writes strings and
+/// characters without quoting them.
+impl fmt::Display for Exp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_exp(self, true))
+    }
+}
+
+/// Formats a value as it is printed by `write`, so that data read back in
+/// produce an equivalent value.
+pub struct WriteFormat<'a>(pub &'a Exp);
+
+impl fmt::Display for WriteFormat<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_exp(self.0, false))
+    }
+}
+
+impl fmt::Debug for Exp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&WriteFormat(self), f)
+    }
+}
+
+fn format_exp(x: &Exp, display: bool) -> String {
+    let format_all = |elements: &[Exp]| {
+        elements.iter().map(|e| format_exp(e, display)).collect::<Vec<_>>().join(" ")
+    };
+    match x {
+        Exp::Atom(Atom::Bool(true)) => "#t".to_string(),
+        Exp::Atom(Atom::Bool(false)) => "#f".to_string(),
+        Exp::Atom(Atom::Symbol(s)) => s.clone(),
+        Exp::Atom(Atom::Integer(n)) => format!("{n}"),
+        Exp::Atom(Atom::Number(n)) => number_to_string(*n),
+        Exp::Atom(Atom::Complex(n)) => format!("{n}"),
+        Exp::Atom(Atom::String(s)) if display => s.borrow().clone(),
+        Exp::Atom(Atom::String(s)) => escape_string(&s.borrow()),
+        Exp::Atom(Atom::Char(c)) if display => c.to_string(),
+        Exp::Atom(Atom::Char(c)) => match CHAR_NAMES.iter().find(|(_, named)| named == c) {
+            Some((name, _)) => format!("#\\{name}"),
+            None => format!("#\\{c}"),
+        },
+        Exp::Atom(Atom::Eof) => "#<eof>".to_string(),
+        Exp::List(list) => format!("({})", format_all(list)),
+        Exp::Pair(car, cdr) => {
+            let mut elements = vec![format_exp(car, display)];
+            let mut tail = cdr.as_ref();
+            while let Exp::Pair(car, cdr) = tail {
+                elements.push(format_exp(car, display));
+                tail = cdr;
+            }
+            format!("({} . {})", elements.join(" "), format_exp(tail, display))
+        }
+        Exp::Function(_) | Exp::Apply => "<function>".to_string(),
+        Exp::Vector(v) => format!("#({})", format_all(&v.borrow())),
+        Exp::Bytevector(v) => {
+            format!("#u8({})", v.borrow().iter().map(u8::to_string).collect::<Vec<_>>().join(" "))
+        }
+        Exp::InputPort(_) => "#<input-port>".to_string(),
+        Exp::OutputPort(_) => "#<output-port>".to_string(),
+        Exp::Procedure(_) => "<procedure>".to_string(),
+        Exp::Continuation(_) => "<continuation>".to_string(),
+        Exp::Values(values) => format_all(values),
+        Exp::Promise(_) => "<promise>".to_string(),
+        Exp::Transformer(_) => "<syntax-transformer>".to_string(),
+        Exp::RecordType(record_type) => format!("#<record-type {}>", record_type.display_name()),
+        Exp::Record { record_type, fields } => {
+            let fields = fields.borrow();
+            if fields.is_empty() {
+                format!("#<{}>", record_type.display_name())
+            } else {
+                format!("#<{} {}>", record_type.display_name(), format_all(&fields))
+            }
+        }
+        Exp::RecordProcedure(_) => "<procedure>".to_string(),
+        Exp::Parameter(_) => "<parameter>".to_string(),
+        Exp::HashTable(_) => "#<hash-table>".to_string(),
+        Exp::Environment(_) => "#<environment>".to_string(),
+        Exp::Traced(_, procedure) => format_exp(procedure, display),
+        Exp::Spanned(spanned) => format_exp(&spanned.value, display),
+        Exp::ErrorObject { message, irritants } if irritants.is_empty() => {
+            format!("#<error {}>", escape_string(message))
+        }
+        Exp::ErrorObject { message, irritants } => {
+            format!("#<error {} {}>", escape_string(message), format_all(irritants))
+        }
+        Exp::Uninitialized => "<uninitialized>".to_string(),
+    }
+}
+
+/// The line width `pp` and the REPL pretty-print values to.
+pub const PRETTY_PRINT_WIDTH: usize = 80;
+
+/// Formats a value as `write` would, breaking lists and vectors that do not
+/// fit in `width` columns over several lines. A form headed by a symbol keeps
+/// the symbol on its first line and indents the rest by two columns; other
+/// sequences line their elements up under the first one.
+pub fn pretty_print(exp: &Exp, width: usize) -> String {
+    let mut result = String::new();
+    write_pretty(&mut result, exp, 0, width);
+    result
+}
+
+fn write_pretty(out: &mut String, exp: &Exp, column: usize, width: usize) {
+    let flat = format_exp(exp, false);
+    if column + flat.chars().count() <= width {
+        out.push_str(&flat);
+        return;
+    }
+    let (open, elements, tail) = match exp {
+        Exp::List(list) if !list.is_empty() => ("(", list.clone(), None),
+        Exp::Pair(..) => {
+            let (elements, tail) = split_tail(exp);
+            ("(", elements, Some(tail))
+        }
+        Exp::Vector(v) if !v.borrow().is_empty() => ("#(", v.borrow().clone(), None),
+        _ => {
+            out.push_str(&flat);
+            return;
+        }
+    };
+    out.push_str(open);
+    let first_column = column + open.len();
+    write_pretty(out, &elements[0], first_column, width);
+    let indent = if open == "(" && matches!(elements[0], Exp::Atom(Atom::Symbol(_))) {
+        column + 2
+    } else {
+        first_column
+    };
+    for element in &elements[1..] {
+        out.push('\n');
+        out.push_str(&" ".repeat(indent));
+        write_pretty(out, element, indent, width);
+    }
+    if let Some(tail) = tail {
+        out.push('\n');
+        out.push_str(&" ".repeat(indent));
+        out.push_str(". ");
+        write_pretty(out, &tail, indent + 2, width);
+    }
+    out.push(')');
+}
+
+/// Reads the next expression of source code from `input`, recording where
+/// each of its parts was written so that errors raised while evaluating it
+/// can be located.
+fn parse<T: Read>(input: &mut InPort<T>) -> Result<Option<Exp>, VowError> {
+    read_nested(input, 0, true)
+}
+
+/// Evaluates each expression in `input` in a new standard environment,
+/// returning the printed value of the last one.
+pub fn eval_to_string(input: &str) -> Result<String, VowError> {
+    let (mut env_tree, env_id) = standard_env();
+    let result = eval_all(&mut InPort::new(input.as_bytes()), &mut env_tree, env_id)
+        .map_err(VowError::into_unlocated)?;
+    Ok(WriteFormat(&result).to_string())
+}
+
+/// Reads every expression in `input` without evaluating it.
+pub fn parse_str(input: &str) -> Result<Vec<Exp>, VowError> {
+    let mut port = InPort::new(input.as_bytes());
+    let mut result = vec![];
+    while let Some(exp) = read(&mut port)? {
+        result.push(exp);
+    }
+    Ok(result)
+}
+
+/// Reads every expression in `input` without evaluating it, along with the
+/// location of each one. The expressions are read as source code, so each of
+/// their lists and symbols is wrapped in [Exp::Spanned].
+pub fn parse_spanned(input: &str) -> Result<Vec<Spanned<Exp>>, VowError> {
+    let mut port = InPort::new(input.as_bytes());
+    let mut result = vec![];
+    while let Some(exp) = read_spanned(&mut port)? {
+        result.push(exp);
+    }
+    Ok(result)
+}
+
+/// Reads a single expression, which must be the only one in the string.
+impl FromStr for Exp {
+    type Err = VowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut port = InPort::new(s.as_bytes());
+        let Some(exp) = read(&mut port)? else {
+            return Err(VowError::ParseError("Unexpected EOF".to_string(), None));
+        };
+        match port.next_token().map_err(|e| port.locate(e))? {
+            Some(token) => Err(port.locate(VowError::ParseError(
+                format!("Unexpected {token} after expression"),
+                None,
+            ))),
+            None => Ok(exp),
+        }
+    }
+}
+
+/// Evaluates each expression in `input` in order in the environment `env_id`,
+/// returning their values.
+pub fn eval_str(input: &str, env_tree: &mut EnvTree, env_id: EnvId) -> Result<Vec<Exp>, VowError> {
+    let mut port = InPort::new(input.as_bytes());
+    let mut result = vec![];
+    while let Some(exp) = parse(&mut port)? {
+        result.push(eval(exp, env_tree, env_id)?);
+    }
+    Ok(result)
+}
+
+/// Creates a new tree containing the standard environment, returning it along
+/// with the id of the standard environment.
+pub fn standard_env() -> (EnvTree, EnvId) {
+    let mut env_tree = EnvTree::default();
+    let env_id = new_global_env(&mut env_tree);
+    (env_tree, env_id)
+}
+
+/// Library procedures which are written in Scheme, evaluated in every new
+/// global environment.
+const PRELUDE: &[&str] = &[include_str!("streams.scm"), include_str!("stdlib.scm")];
+
+/// Creates the standard environment in `env_tree`, evaluates the prelude in it
+/// and makes it the interaction environment.
+fn new_global_env(env_tree: &mut EnvTree) -> EnvId {
+    LazyLock::force(&START);
+    let env_id = new_report_env(env_tree).expect("valid prelude");
+    set_interaction_environment(env_id);
+    env_id
+}
+
+thread_local! {
+    /// The built-in procedures, which are constructed once per thread and
+    /// copied into each new standard environment. Values refer to shared
+    /// thread-local state such as the current ports, so they cannot be shared
+    /// between threads.
+    static BUILTIN_ENV: Env = builtin_env();
+    /// The expressions of the prelude, which are read once per thread.
+    static PRELUDE_EXPRESSIONS: Vec<Exp> =
+        PRELUDE.iter().flat_map(|source| parse_str(source).expect("valid prelude")).collect();
+}
+
+/// Creates a new standard environment in `env_tree` and evaluates the prelude
+/// in it, without the procedures disallowed by the running [sandboxed_eval].
+///
+/// Evaluating the prelude only fails if it exceeds the recursion or time
+/// limit.
+fn new_report_env(env_tree: &mut EnvTree) -> Result<EnvId, VowError> {
+    let mut env = BUILTIN_ENV.with(Env::clone);
+    SANDBOX.with(|sandbox| {
+        if let Some(config) = &*sandbox.borrow() {
+            config.restrict(&mut env);
+        }
+    });
+    let env_id = env_tree.insert(env);
+    PRELUDE_EXPRESSIONS.with(|expressions| {
+        expressions.iter().try_for_each(|exp| eval_inner(exp.clone(), env_tree, env_id).map(drop))
+    })?;
+    Ok(env_id)
+}
+
+/// Restrictions on code evaluated by [sandboxed_eval]. The default disallows
+/// everything and has no time limit.
+#[derive(Clone, Debug, Default)]
+pub struct SandboxConfig {
+    /// Whether `load` and the procedures which open or manage files are
+    /// available.
+    pub allow_file_io: bool,
+    /// Whether `exit` is available.
+    pub allow_exit: bool,
+    /// The number of milliseconds after which evaluation fails with
+    /// [VowError::TimeLimitExceeded]. The limit is checked before each
+    /// evaluation step, so a single call to a built-in procedure can overrun
+    /// it.
+    pub max_eval_ms: Option<u64>,
+}
+
+/// The procedures which are removed from the environment of a sandbox which
+/// does not allow file I/O.
+const FILE_PROCEDURES: &[&str] = &[
+    "load",
+    "open-input-file",
+    "open-output-file",
+    "open-binary-input-file",
+    "open-binary-output-file",
+    "call-with-input-file",
+    "call-with-output-file",
+    "with-input-from-file",
+    "with-output-to-file",
+    "file-exists?",
+    "delete-file",
+    "rename-file",
+    "create-directory",
+];
+
+impl SandboxConfig {
+    /// Removes the procedures which this configuration disallows from `env`.
+    fn restrict(&self, env: &mut Env) {
+        if !self.allow_file_io {
+            for name in FILE_PROCEDURES {
+                env.symbols.remove(*name);
+            }
+        }
+        if !self.allow_exit {
+            env.symbols.remove("exit");
+        }
+    }
+}
+
+thread_local! {
+    /// The configuration of the running [sandboxed_eval], which also applies to
+    /// every environment created by `scheme-report-environment` while it runs.
+    static SANDBOX: RefCell<Option<SandboxConfig>> = const { RefCell::new(None) };
+    /// The time after which evaluation fails, along with the time limit it
+    /// was computed from.
+    static DEADLINE: Cell<Option<(Instant, u64)>> = const { Cell::new(None) };
+}
+
+/// Evaluates each expression in `input` in a new standard environment
+/// restricted by `config`, returning the value of each one.
+pub fn sandboxed_eval(input: &str, config: &SandboxConfig) -> Result<Vec<Exp>, VowError> {
+    let sandbox = SANDBOX.with(|sandbox| sandbox.replace(Some(config.clone())));
+    let interaction_environment = INTERACTION_ENVIRONMENT.with(Cell::get);
+    let mut env_tree = EnvTree::default();
+    let env_id = new_global_env(&mut env_tree);
+    let deadline = config.max_eval_ms.map(|ms| (Instant::now() + Duration::from_millis(ms), ms));
+    let deadline = DEADLINE.with(|d| d.replace(deadline));
+    let result = eval_str(input, &mut env_tree, env_id);
+    DEADLINE.with(|d| d.set(deadline));
+    SANDBOX.with(|s| *s.borrow_mut() = sandbox);
+    INTERACTION_ENVIRONMENT.with(|env| env.set(interaction_environment));
+    result
+}
+
+/// Evaluates each expression read from `port` in order, returning the value of
+/// the last one.
+fn eval_all<T: Read>(
+    port: &mut InPort<T>,
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<Exp, VowError> {
+    let mut result = Exp::List(vec![]);
+    while let Some(exp) = parse(port)? {
+        result = eval_inner(exp, env_tree, env_id)?;
+    }
+    Ok(result)
+}
+
+thread_local! {
+    /// The top-level environment of the running program, in which `load`
+    /// evaluates files.
+    static INTERACTION_ENVIRONMENT: Cell<Option<EnvId>> = const { Cell::new(None) };
+}
+
+thread_local! {
+    /// The arguments returned by `command-line`.
+    static COMMAND_LINE: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+/// Sets the list returned by `(command-line)`, whose first element is
+/// conventionally the name of the program.
+pub fn set_command_line(args: Vec<String>) {
+    COMMAND_LINE.with(|command_line| *command_line.borrow_mut() = args);
+}
+
+/// The time at which the first global environment was created, from which
+/// `runtime` measures.
+static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+fn set_interaction_environment(env_id: EnvId) {
+    INTERACTION_ENVIRONMENT.with(|env| env.set(Some(env_id)));
+}
+
+fn interaction_environment(name: &str) -> Result<EnvId, VowError> {
+    INTERACTION_ENVIRONMENT
+        .with(Cell::get)
+        .ok_or_else(|| VowError::Custom(format!("{name}: no interaction environment")))
+}
+
+/// Evaluates the Scheme source file at `path` in the interaction environment,
+/// returning the value of its last expression.
+pub fn load(env_tree: &mut EnvTree, path: &str) -> Result<Exp, VowError> {
+    load_inner(env_tree, path).map_err(VowError::into_unlocated)
+}
+
+/// Implements `load`, returning errors raised by the file with their
+/// locations.
+fn load_inner(env_tree: &mut EnvTree, path: &str) -> Result<Exp, VowError> {
+    let env_id = interaction_environment("load")?;
+    let file = File::open(path).map_err(|e| VowError::IoError(path.to_string(), e))?;
+    let mut port = InPort::new(file);
+    port.source = Some(path.to_string());
+    eval_all(&mut port, env_tree, env_id)
+}
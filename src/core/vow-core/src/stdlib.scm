@@ -0,0 +1,95 @@
+;; Copyright © Vow 2024-present
+
+;; Licensed under the Apache License, Version 2.0 (the "License");
+;; you may not use this file except in compliance with the License.
+;; You may obtain a copy of the License at
+
+;;    https://www.apache.org/licenses/LICENSE-2.0
+
+;; Unless required by applicable law or agreed to in writing, software
+;; distributed under the License is distributed on an "AS IS" BASIS,
+;; WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+;; See the License for the specific language governing permissions and
+;; limitations under the License.
+
+;; Higher-order utilities and stateful closures.
+
+;; Returns a procedure which returns 1 the first time it is called and one
+;; more than the previous result each time after that.
+(define (make-counter)
+  (let ((count 0))
+    (lambda ()
+      (set! count (+ count 1))
+      count)))
+
+;; Returns a procedure which adds its argument to a running total starting at
+;; initial and returns the new total.
+(define (make-accumulator initial)
+  (lambda (amount)
+    (set! initial (+ initial amount))
+    initial))
+
+;; Returns a procedure which calls f and counts the number of times it has
+;; been called. Passing the symbol how-many-calls? returns the count instead,
+;; and passing reset-count sets it back to zero.
+(define (make-monitored f)
+  (let ((count 0))
+    (lambda args
+      (cond ((equal? args '(how-many-calls?)) count)
+            ((equal? args '(reset-count)) (set! count 0) count)
+            (else (set! count (+ count 1))
+                  (apply f args))))))
+
+;; Returns a procedure which calls f with the same arguments at most once,
+;; remembering each result in a hash table keyed by the list of arguments.
+(define (memoize f)
+  (let ((results (make-equal-hash-table)))
+    (lambda args
+      ;; Results are stored in a list so that a result of #f can be told apart
+      ;; from a missing one.
+      (let ((entry (hash-table-ref/default results args #f)))
+        (if entry
+            (car entry)
+            (let ((result (apply f args)))
+              (hash-table-set! results args (list result))
+              result))))))
+
+;; Returns a procedure which calls f the first time it is called and returns
+;; that first result on every later call without calling f again.
+(define (once f)
+  (let ((called #f)
+        (result #f))
+    (lambda args
+      (unless called
+        (set! result (apply f args))
+        (set! called #t))
+      result)))
+
+;; Returns the composition of the procedures fs, which calls the last of them
+;; with its arguments and passes each result to the procedure before it. The
+;; composition of no procedures returns its argument.
+(define (compose . fs)
+  (if (null? fs)
+      (lambda (x) x)
+      (let ((f (car fs))
+            (g (apply compose (cdr fs))))
+        (if (null? (cdr fs))
+            f
+            (lambda args (f (apply g args)))))))
+
+;; Returns a predicate which is true exactly when pred is false.
+(define (negate pred)
+  (lambda args (not (apply pred args))))
+
+;; Returns a procedure which calls f with its first two arguments swapped.
+(define (flip f)
+  (lambda (a b . rest) (apply f b a rest)))
+
+;; Converts a procedure of two arguments into a procedure of one argument
+;; which returns a procedure of the other.
+(define (curry f)
+  (lambda (a) (lambda (b) (f a b))))
+
+;; Returns a procedure which calls f with args followed by its own arguments.
+(define (partial f . args)
+  (lambda more (apply f (append args more))))
@@ -0,0 +1,74 @@
+;; Copyright © Vow 2024-present
+
+;; Licensed under the Apache License, Version 2.0 (the "License");
+;; you may not use this file except in compliance with the License.
+;; You may obtain a copy of the License at
+
+;;    https://www.apache.org/licenses/LICENSE-2.0
+
+;; Unless required by applicable law or agreed to in writing, software
+;; distributed under the License is distributed on an "AS IS" BASIS,
+;; WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+;; See the License for the specific language governing permissions and
+;; limitations under the License.
+
+;; Lazy streams built on delay and force.
+;;
+;; A stream is either the empty list or a pair whose cdr is a promise of the
+;; rest of the stream, as constructed by the stream-cons special form. Every
+;; stream procedure also accepts a promise of a stream, so that stream-cdr can
+;; return the tail of a stream without forcing it.
+
+(define the-empty-stream '())
+
+(define (stream-car stream) (car (force stream)))
+
+(define (stream-cdr stream) (cdr (force stream)))
+
+(define (stream-null? stream) (null? (force stream)))
+
+(define (stream-pair? stream) (pair? (force stream)))
+
+(define (stream-ref stream n)
+  (if (= n 0)
+      (stream-car stream)
+      (stream-ref (stream-cdr stream) (- n 1))))
+
+;; Returns a list of the first n elements of stream, or all of its elements
+;; if it has fewer than n.
+(define (stream-take stream n)
+  (if (or (= n 0) (stream-null? stream))
+      '()
+      (cons (stream-car stream) (stream-take (stream-cdr stream) (- n 1)))))
+
+(define (stream-map f stream)
+  (delay
+    (if (stream-null? stream)
+        '()
+        (cons (f (stream-car stream)) (stream-map f (stream-cdr stream))))))
+
+(define (stream-filter pred stream)
+  (delay-force
+    (cond ((stream-null? stream) (delay '()))
+          ((pred (stream-car stream))
+           (delay (cons (stream-car stream) (stream-filter pred (stream-cdr stream)))))
+          (else (stream-filter pred (stream-cdr stream))))))
+
+(define (stream-fold f init stream)
+  (if (stream-null? stream)
+      init
+      (stream-fold f (f init (stream-car stream)) (stream-cdr stream))))
+
+(define (stream-for-each f stream)
+  (if (stream-null? stream)
+      '()
+      (begin (f (stream-car stream))
+             (stream-for-each f (stream-cdr stream)))))
+
+(define (stream-append . streams)
+  (let loop ((streams streams))
+    (delay-force
+      (cond ((null? streams) (delay '()))
+            ((stream-null? (car streams)) (loop (cdr streams)))
+            (else (delay (cons (stream-car (car streams))
+                               (loop (cons (stream-cdr (car streams)) (cdr streams))))))))))
@@ -0,0 +1,64 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Mutex};
+
+use reedline::{Completer, Span, Suggestion};
+
+/// Completes the symbol before the cursor with the names defined in the REPL
+/// environment.
+///
+/// The environment cannot be shared with the line editor, so the REPL updates
+/// the completer's copy of the defined names after evaluating each input.
+#[derive(Clone, Debug, Default)]
+pub struct VowCompleter {
+    symbols: Arc<Mutex<Vec<String>>>,
+}
+
+impl VowCompleter {
+    /// Replaces the names offered as completions.
+    pub fn set_symbols(&self, symbols: Vec<String>) {
+        *self.symbols.lock().expect("completer lock") = symbols;
+    }
+}
+
+/// Returns true if `c` cannot be part of a symbol.
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "()'`,\";".contains(c)
+}
+
+impl Completer for VowCompleter {
+    fn complete(&mut self, line: &str, pos: usize) -> Vec<Suggestion> {
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| is_delimiter(*c))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let prefix = &line[start..pos];
+        let symbols = self.symbols.lock().expect("completer lock");
+        let mut result: Vec<Suggestion> = symbols
+            .iter()
+            .filter(|symbol| symbol.starts_with(prefix))
+            .map(|symbol| Suggestion {
+                value: symbol.clone(),
+                description: None,
+                extra: None,
+                span: Span::new(start, pos),
+                append_whitespace: false,
+            })
+            .collect();
+        result.sort_by(|a, b| a.value.cmp(&b.value));
+        result
+    }
+}
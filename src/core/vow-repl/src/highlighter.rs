@@ -0,0 +1,153 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use nu_ansi_term::{Color, Style};
+use reedline::{Highlighter, StyledText};
+use vow_core::{Atom, Exp};
+
+/// Keywords of special forms, which are highlighted differently from other
+/// symbols.
+const SPECIAL_FORMS: &[&str] = &[
+    "and",
+    "begin",
+    "case",
+    "cond",
+    "define",
+    "define-record-type",
+    "define-syntax",
+    "define-values",
+    "defined?",
+    "delay",
+    "delay-force",
+    "do",
+    "else",
+    "guard",
+    "if",
+    "lambda",
+    "lazy",
+    "let",
+    "let*",
+    "let*-values",
+    "let-syntax",
+    "let-values",
+    "let-values*",
+    "letrec",
+    "letrec*",
+    "letrec-syntax",
+    "or",
+    "parameterize",
+    "quasiquote",
+    "quote",
+    "receive",
+    "set!",
+    "step",
+    "stream-cons",
+    "syntax-rules",
+    "the-environment",
+    "time",
+    "trace",
+    "unless",
+    "unquote",
+    "unquote-splicing",
+    "untrace",
+    "when",
+];
+
+/// Colors of parentheses, chosen in turn by nesting depth.
+const PAREN_COLORS: &[Color] = &[Color::LightBlue, Color::LightMagenta, Color::LightRed];
+
+/// Colors REPL input as it is typed.
+#[derive(Clone, Copy, Debug)]
+pub struct VowHighlighter {
+    /// If false, input is shown without any styling.
+    color: bool,
+}
+
+impl VowHighlighter {
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+}
+
+/// Returns true if `c` ends a symbol or number.
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "()'`,\";".contains(c)
+}
+
+/// Returns the style of a symbol, number or other atom.
+fn atom_style(token: &str) -> Style {
+    if SPECIAL_FORMS.contains(&token) {
+        Color::Yellow.bold()
+    } else if matches!(
+        token.parse::<Exp>(),
+        Ok(Exp::Atom(Atom::Integer(_) | Atom::Number(_) | Atom::Complex(_)))
+    ) {
+        Color::Cyan.normal()
+    } else {
+        Color::White.normal()
+    }
+}
+
+impl Highlighter for VowHighlighter {
+    fn highlight(&self, line: &str, _cursor: usize) -> StyledText {
+        let mut result = StyledText::new();
+        if !self.color {
+            result.push((Style::new(), line.to_string()));
+            return result;
+        }
+
+        let mut depth = 0;
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let (style, len) = match c {
+                '(' => {
+                    depth += 1;
+                    (PAREN_COLORS[(depth - 1) % PAREN_COLORS.len()].normal(), 1)
+                }
+                ')' => {
+                    let style = PAREN_COLORS[depth.saturating_sub(1) % PAREN_COLORS.len()];
+                    depth = depth.saturating_sub(1);
+                    (style.normal(), 1)
+                }
+                ';' => (Color::DarkGray.normal(), rest.find('\n').unwrap_or(rest.len())),
+                '"' => {
+                    let mut escaped = false;
+                    let end = rest
+                        .char_indices()
+                        .skip(1)
+                        .find(|&(_, c)| {
+                            let closes = c == '"' && !escaped;
+                            escaped = c == '\\' && !escaped;
+                            closes
+                        })
+                        .map_or(rest.len(), |(i, _)| i + 1);
+                    (Color::Green.normal(), end)
+                }
+                c if c.is_whitespace() || "'`,".contains(c) => (Style::new(), c.len_utf8()),
+                _ => {
+                    // Character literals such as #\( may begin with a delimiter.
+                    let start = match rest.strip_prefix("#\\") {
+                        Some(name) => name.chars().next().map_or(2, |c| 2 + c.len_utf8()),
+                        None => 0,
+                    };
+                    let end = rest[start..].find(is_delimiter).map_or(rest.len(), |i| start + i);
+                    (atom_style(&rest[..end]), end)
+                }
+            };
+            result.push((style, rest[..len].to_string()));
+            rest = &rest[len..];
+        }
+        result
+    }
+}
@@ -12,12 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Vow: A contractually-correct lisp
-
-use repl::runner;
-
-fn main() {
-    println!("Hello, world");
-
-    runner::run();
-}
+pub mod completer;
+pub mod highlighter;
+pub mod metacommand;
+pub mod repl;
+pub mod validator;
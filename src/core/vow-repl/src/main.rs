@@ -0,0 +1,32 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::process::ExitCode;
+use std::thread;
+
+use vow_repl::repl;
+
+/// The stack size of the thread which runs the interpreter, which leaves room
+/// for well over [vow_core::DEFAULT_RECURSION_LIMIT] nested evaluations in an
+/// unoptimized build.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+fn main() -> ExitCode {
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(repl::run)
+        .expect("failed to start interpreter thread")
+        .join()
+        .unwrap_or(ExitCode::FAILURE)
+}
@@ -0,0 +1,94 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::{EnvId, EnvTree, Exp, WriteFormat};
+
+/// A command to the REPL itself, entered on a line starting with `,`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Metacommand {
+    /// `,load <path>`: evaluates a file in the REPL environment.
+    Load(String),
+    /// `,env`: lists the symbols defined in the REPL environment, or
+    /// `,env <symbol>`: shows the value of one symbol.
+    Env(Option<String>),
+    /// `,quit` or `,exit`: ends the session.
+    Quit,
+    /// `,help`: lists the metacommands.
+    Help,
+    /// `,trace <symbol>`: prints each call to a procedure.
+    Trace(String),
+    /// `,untrace <symbol>`: stops tracing a procedure.
+    Untrace(String),
+}
+
+const HELP: &str = "\
+,load <path>       Load a Scheme file
+,env               List the symbols defined in the environment
+,env <symbol>      Show the value and type of a symbol
+,trace <symbol>    Print each call to a procedure and its result
+,untrace <symbol>  Stop tracing a procedure
+,help              Show this message
+,quit, ,exit       Leave the REPL";
+
+impl Metacommand {
+    /// Parses a line of input if it is a metacommand, returning `None` if it
+    /// should be evaluated as Scheme code instead.
+    pub fn parse(line: &str) -> Option<Result<Self, String>> {
+        let line = line.trim().strip_prefix(',')?;
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let argument = words.next().map(str::to_string);
+        if words.next().is_some() {
+            return Some(Err(format!(",{name}: too many arguments")));
+        }
+        let required = |argument: Option<String>| {
+            argument.ok_or_else(|| format!(",{name} requires an argument"))
+        };
+        let no_argument = |command: Metacommand| match &argument {
+            Some(_) => Err(format!(",{name} takes no arguments")),
+            None => Ok(command),
+        };
+        Some(match name {
+            "load" => required(argument).map(Metacommand::Load),
+            "env" => Ok(Metacommand::Env(argument)),
+            "quit" | "exit" => no_argument(Metacommand::Quit),
+            "help" => no_argument(Metacommand::Help),
+            "trace" => required(argument).map(Metacommand::Trace),
+            "untrace" => required(argument).map(Metacommand::Untrace),
+            _ => Err(format!("Unknown command ',{name}'. Type ,help for a list of commands")),
+        })
+    }
+
+    /// Runs this command in the REPL environment `env_id`, returning the text
+    /// to print.
+    pub fn run(&self, env_tree: &mut EnvTree, env_id: EnvId) -> Result<String, String> {
+        match self {
+            Metacommand::Load(path) => vow_core::load(env_tree, path)
+                .map(|value| WriteFormat(&value).to_string())
+                .map_err(|e| e.to_string()),
+            Metacommand::Env(None) => Ok(vow_core::defined_symbols(env_tree, env_id).join("\n")),
+            Metacommand::Env(Some(symbol)) => vow_core::eval(Exp::symbol(symbol), env_tree, env_id)
+                .map(|value| format!("{symbol} = {} : {}", WriteFormat(&value), value.type_name()))
+                .map_err(|e| e.to_string()),
+            Metacommand::Quit => Ok(String::new()),
+            Metacommand::Help => Ok(HELP.to_string()),
+            Metacommand::Trace(symbol) => vow_core::trace(env_tree, env_id, symbol)
+                .map(|()| format!("Tracing {symbol}"))
+                .map_err(|e| e.to_string()),
+            Metacommand::Untrace(symbol) => vow_core::untrace(env_tree, env_id, symbol)
+                .map(|()| format!("Stopped tracing {symbol}"))
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
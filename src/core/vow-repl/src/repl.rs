@@ -0,0 +1,387 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs};
+
+use reedline::{
+    default_emacs_keybindings, ColumnarMenu, DefaultPrompt, Emacs, FileBackedHistory, History,
+    KeyCode, KeyModifiers, Reedline, ReedlineEvent, ReedlineMenu, Signal,
+};
+use vow_core::{
+    Atom, EnvId, EnvTree, Exp, Located, Span, Spanned, VowError, WriteFormat, PRETTY_PRINT_WIDTH,
+};
+
+use crate::completer::VowCompleter;
+use crate::highlighter::VowHighlighter;
+use crate::metacommand::Metacommand;
+use crate::validator::BalancedParenValidator;
+
+/// The name of the menu listing completions.
+const COMPLETION_MENU: &str = "completion_menu";
+
+/// The number of history entries kept if `VOW_HISTORY_SIZE` is not set.
+const DEFAULT_HISTORY_SIZE: usize = 10_000;
+
+/// Options given on the command line.
+#[derive(Debug, Default)]
+struct Options {
+    /// Files to load before starting the REPL.
+    loads: Vec<String>,
+    /// Overrides the location of the history file.
+    history_file: Option<PathBuf>,
+    /// Keeps history in memory only, for the current session.
+    no_history: bool,
+    /// Disables syntax highlighting of input.
+    no_color: bool,
+    /// A file to run instead of starting the REPL.
+    script: Option<String>,
+    /// Arguments following `--`, passed to the program by `command-line`.
+    arguments: Vec<String>,
+    /// Prints the value of each expression in the script.
+    verbose: bool,
+    /// Expressions to evaluate instead of starting the REPL, and whether to
+    /// print their values even if they are unspecified.
+    evals: Vec<(String, bool)>,
+    /// Overrides the startup file loaded before the first prompt.
+    rc_file: Option<PathBuf>,
+    /// Skips loading startup files.
+    no_rc: bool,
+}
+
+/// Parses the command-line arguments following the program name.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-l" | "--load" => options
+                .loads
+                .push(args.next().ok_or_else(|| format!("{arg} requires a file path"))?),
+            "--history-file" => {
+                options.history_file =
+                    Some(args.next().ok_or_else(|| format!("{arg} requires a file path"))?.into())
+            }
+            "--no-history" => options.no_history = true,
+            "--no-color" => options.no_color = true,
+            "--no-rc" | "--norc" => options.no_rc = true,
+            "--rcfile" => {
+                options.rc_file =
+                    Some(args.next().ok_or_else(|| format!("{arg} requires a file path"))?.into())
+            }
+            "--script" | "--file" => {
+                options.script =
+                    Some(args.next().ok_or_else(|| format!("{arg} requires a file path"))?)
+            }
+            "-v" | "--verbose" => options.verbose = true,
+            "-e" | "--eval" | "-p" | "--print" => {
+                let expression =
+                    args.next().ok_or_else(|| format!("{arg} requires an expression"))?;
+                options.evals.push((expression, matches!(arg.as_str(), "-p" | "--print")));
+            }
+            "--" => {
+                options.arguments = args.collect();
+                break;
+            }
+            _ if !arg.starts_with('-') && options.script.is_none() => options.script = Some(arg),
+            _ => return Err(format!("Unknown argument '{arg}'")),
+        }
+    }
+    Ok(options)
+}
+
+/// Returns the file in which to persist history, which is the first of
+/// `--history-file`, `$VOW_HISTORY_FILE`, `$XDG_DATA_HOME/vow/history` and
+/// `~/.vow_history` which is set.
+fn history_path(options: &Options) -> Option<PathBuf> {
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    options
+        .history_file
+        .clone()
+        .or_else(|| var("VOW_HISTORY_FILE"))
+        .or_else(|| var("XDG_DATA_HOME").map(|data| data.join("vow").join("history")))
+        .or_else(|| var("HOME").map(|home| home.join(".vow_history")))
+}
+
+/// Returns the startup files to load before the first prompt: the file given
+/// by `--rcfile`, or else whichever of `~/.vowrc` and
+/// `$XDG_CONFIG_HOME/vow/init.scm` exist.
+fn rc_files(options: &Options) -> Vec<PathBuf> {
+    if options.no_rc {
+        return vec![];
+    }
+    if let Some(path) = &options.rc_file {
+        return vec![path.clone()];
+    }
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    [
+        var("HOME").map(|home| home.join(".vowrc")),
+        var("XDG_CONFIG_HOME").map(|config| config.join("vow").join("init.scm")),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|path| path.is_file())
+    .collect()
+}
+
+/// Returns the maximum number of history entries, from `$VOW_HISTORY_SIZE`.
+fn history_size() -> usize {
+    match env::var("VOW_HISTORY_SIZE") {
+        Ok(size) => size.parse().unwrap_or_else(|_| {
+            eprintln!("Warning: ignoring invalid VOW_HISTORY_SIZE '{size}'");
+            DEFAULT_HISTORY_SIZE
+        }),
+        Err(_) => DEFAULT_HISTORY_SIZE,
+    }
+}
+
+/// Creates the REPL history, which is kept in memory only if persistence is
+/// disabled or the history file cannot be written.
+fn history(options: &Options) -> Box<dyn History> {
+    let size = history_size();
+    if !options.no_history {
+        if let Some(path) = history_path(options) {
+            match FileBackedHistory::with_file(size, path.clone()) {
+                Ok(history) => return Box::new(history),
+                Err(error) => eprintln!(
+                    "Warning: cannot write history file {}: {error}. History will not be saved.",
+                    path.display()
+                ),
+            }
+        }
+    }
+    Box::new(FileBackedHistory::new(size))
+}
+
+/// Returns the exit status of the program for the status given to `exit`.
+fn exit_code(code: i32) -> ExitCode {
+    ExitCode::from(code as u8)
+}
+
+/// Evaluates each expression in a line of input, printing its value. Returns
+/// the status code if the input called `exit`.
+fn eval_input(input: &str, env_tree: &mut EnvTree, env_id: EnvId) -> Option<i32> {
+    match vow_core::parse_spanned(input) {
+        Ok(expressions) => {
+            for Spanned { value, span } in expressions {
+                match vow_core::eval_located(value, env_tree, env_id) {
+                    Ok(result) => {
+                        println!("{}", vow_core::pretty_print(&result, PRETTY_PRINT_WIDTH))
+                    }
+                    Err(Located { error: VowError::Exit(code), .. }) => return Some(code),
+                    Err(error) => println!("{}", error_report(&error, &span, None)),
+                }
+            }
+        }
+        Err(error) => println!("Error: {error}"),
+    }
+    None
+}
+
+/// Describes `located`, an error raised while evaluating the top-level
+/// expression at `span`, with the location it was raised at followed by each
+/// call site which led there. Locations read from `source`, which are not
+/// named, are given its name.
+fn error_report(located: &Located, span: &Span, source: Option<&str>) -> String {
+    let name = |span: &Span| match (&span.source, source) {
+        (None, Some(source)) => Span { source: Some(source.to_string()), ..span.clone() },
+        _ => span.clone(),
+    };
+    let mut report =
+        format!("Error at {}: {}", name(located.span.as_ref().unwrap_or(span)), located.error);
+    for call_site in &located.trace {
+        report.push_str(&format!("\n  from {}", name(call_site)));
+    }
+    report
+}
+
+/// Returns true if `value`, the result of evaluating `exp`, is unspecified.
+/// Definitions and procedures called only for their effects, which return an
+/// empty list, do not produce a useful value.
+fn is_unspecified(exp: &Exp, value: &Exp) -> bool {
+    let is_definition = match exp {
+        Exp::List(list) => {
+            matches!(list.first(), Some(Exp::Atom(Atom::Symbol(s))) if s.starts_with("define"))
+        }
+        _ => false,
+    };
+    is_definition || matches!(value, Exp::List(list) if list.is_empty())
+}
+
+/// Evaluates the expressions given by `--eval` and `--print` in order, printing
+/// their values. Stops at the first error.
+fn run_evals(
+    evals: &[(String, bool)],
+    env_tree: &mut EnvTree,
+    env_id: EnvId,
+) -> Result<(), ExitCode> {
+    for (input, print) in evals {
+        let expressions = vow_core::parse_str(input).map_err(|error| {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        })?;
+        for exp in expressions {
+            match vow_core::eval(exp.clone(), env_tree, env_id) {
+                Ok(value) if *print || !is_unspecified(&exp, &value) => {
+                    println!("{}", WriteFormat(&value))
+                }
+                Ok(_) => {}
+                Err(VowError::Exit(code)) => return Err(exit_code(code)),
+                Err(error) => {
+                    eprintln!("Error: {error}");
+                    return Err(ExitCode::FAILURE);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates the Scheme file at `path`, stopping at the first error. The
+/// value of each expression is printed if `verbose` is true.
+fn run_script(path: &str, verbose: bool, env_tree: &mut EnvTree, env_id: EnvId) -> ExitCode {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("{path}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // Ignore a `#!` interpreter line, keeping its newline so that line numbers
+    // in errors are unchanged.
+    let source = match source.strip_prefix("#!") {
+        Some(rest) => rest.find('\n').map_or("", |i| &rest[i..]),
+        None => &source,
+    };
+    let expressions = match vow_core::parse_spanned(source) {
+        Ok(expressions) => expressions,
+        Err(error) => {
+            eprintln!("{path}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    for Spanned { value, span } in expressions {
+        match vow_core::eval_located(value, env_tree, env_id) {
+            Ok(result) if verbose => println!("{}", WriteFormat(&result)),
+            Ok(_) => {}
+            Err(Located { error: VowError::Exit(code), .. }) => return exit_code(code),
+            Err(error) => {
+                eprintln!("{}", error_report(&error, &span, Some(path)));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+pub fn run() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}");
+            eprintln!(
+                "Usage: vow [--load FILE]... [--history-file FILE] [--no-history] [--no-color] \
+                 [--no-rc] [--rcfile FILE] \
+                 [-e EXPR]... [-p EXPR]... [-v] [[--script] FILE] [-- ARGUMENTS...]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut env_tree, standard_env_id) = vow_core::standard_env();
+    let program = options.script.clone().unwrap_or_else(|| "vow".to_string());
+    vow_core::set_command_line(
+        std::iter::once(program).chain(options.arguments.iter().cloned()).collect(),
+    );
+
+    for path in &options.loads {
+        match vow_core::load(&mut env_tree, path) {
+            Ok(_) => {}
+            Err(VowError::Exit(code)) => return exit_code(code),
+            Err(error) => eprintln!("Error: {error}"),
+        }
+    }
+
+    if let Err(code) = run_evals(&options.evals, &mut env_tree, standard_env_id) {
+        return code;
+    }
+    if let Some(script) = &options.script {
+        return run_script(script, options.verbose, &mut env_tree, standard_env_id);
+    }
+    if !options.evals.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    println!("Hello, world");
+
+    for path in rc_files(&options) {
+        match vow_core::load(&mut env_tree, &path.to_string_lossy()) {
+            Ok(_) => {}
+            Err(VowError::Exit(code)) => return exit_code(code),
+            Err(error) => eprintln!("Warning: error in {}: {error}", path.display()),
+        }
+    }
+
+    let completer = VowCompleter::default();
+    let mut keybindings = default_emacs_keybindings();
+    keybindings.add_binding(
+        KeyModifiers::NONE,
+        KeyCode::Tab,
+        ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::Menu(COMPLETION_MENU.to_string()),
+            ReedlineEvent::MenuNext,
+        ]),
+    );
+    let mut line_editor = Reedline::create()
+        .with_history(history(&options))
+        .with_completer(Box::new(completer.clone()))
+        .with_menu(ReedlineMenu::EngineCompleter(Box::new(
+            ColumnarMenu::default().with_name(COMPLETION_MENU),
+        )))
+        .with_edit_mode(Box::new(Emacs::new(keybindings)))
+        .with_validator(Box::new(BalancedParenValidator))
+        .with_highlighter(Box::new(VowHighlighter::new(!options.no_color)));
+    let prompt = DefaultPrompt::default();
+
+    loop {
+        completer.set_symbols(vow_core::defined_symbols(&env_tree, standard_env_id));
+        let sig = line_editor.read_line(&prompt);
+        match sig {
+            Ok(Signal::Success(buffer)) => match Metacommand::parse(&buffer) {
+                Some(Ok(Metacommand::Quit)) => break,
+                Some(Ok(command)) => match command.run(&mut env_tree, standard_env_id) {
+                    Ok(output) if output.is_empty() => {}
+                    Ok(output) => println!("{output}"),
+                    Err(error) => println!("Error: {error}"),
+                },
+                Some(Err(error)) => println!("Error: {error}"),
+                None => {
+                    if let Some(code) = eval_input(&buffer, &mut env_tree, standard_env_id) {
+                        return exit_code(code);
+                    }
+                }
+            },
+            Ok(Signal::CtrlD) | Ok(Signal::CtrlC) => {
+                println!("\nAborted!");
+                break;
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,63 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reedline::{ValidationResult, Validator};
+
+/// Treats input as incomplete while it has unclosed parentheses or an
+/// unterminated string, so that pressing Enter continues it on a new line.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BalancedParenValidator;
+
+/// Returns true if `input` has no unclosed parentheses or strings.
+/// Parentheses in strings, comments and character literals such as `#\(` are
+/// ignored. Input with too many closing parentheses counts as balanced, so
+/// that the error is reported when it is parsed.
+pub fn is_balanced(input: &str) -> bool {
+    let mut depth = 0;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ';' => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '#' if chars.as_str().starts_with('\\') => {
+                chars.nth(1);
+            }
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => {
+                        chars.next();
+                    }
+                    Some(_) => {}
+                    None => return false,
+                }
+            },
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+impl Validator for BalancedParenValidator {
+    fn validate(&self, line: &str) -> ValidationResult {
+        if is_balanced(line) {
+            ValidationResult::Complete
+        } else {
+            ValidationResult::Incomplete
+        }
+    }
+}
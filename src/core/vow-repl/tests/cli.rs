@@ -0,0 +1,299 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests which run the `vow` binary.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::{env, fs};
+
+/// Writes `contents` to a temporary script unique to `name`, returning its
+/// path.
+fn write_script(name: &str, contents: &str) -> String {
+    let path = env::temp_dir().join(format!("vow-cli-{}-{name}.scm", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn vow(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vow")).args(args).output().unwrap()
+}
+
+/// Starts the REPL with `home` as the home directory and no input, so that
+/// it exits when it tries to show the first prompt.
+fn vow_at_home(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vow"))
+        .args(["--no-history", "--no-color"])
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+/// Creates an empty temporary directory unique to `name`.
+fn temp_home(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("vow-cli-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn run_script() {
+    let path = write_script("run", "(define x 6)\n(display (* x 7))\n(newline)\n");
+    let output = vow(&[&path]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "42\n");
+}
+
+#[test]
+fn script_flag() {
+    let path = write_script("flag", "(display \"ok\")");
+    assert_eq!(stdout(&vow(&["--script", &path])), "ok");
+    assert_eq!(stdout(&vow(&["--file", &path])), "ok");
+}
+
+#[test]
+fn verbose_prints_values() {
+    let path = write_script("verbose", "(+ 1 2)\n\"text\"\n");
+    assert_eq!(stdout(&vow(&[&path])), "");
+    assert_eq!(stdout(&vow(&["-v", &path])), "3\n\"text\"\n");
+    assert_eq!(stdout(&vow(&["--verbose", &path])), "3\n\"text\"\n");
+}
+
+#[test]
+fn error_stops_script() {
+    let path = write_script("error", "(display 1)\n\n  (frobble)\n(display 2)\n");
+    let output = vow(&[&path]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "1");
+    let error = stderr(&output);
+    assert!(error.starts_with(&format!("Error at line 3, col 4 of {path}: ")), "{error}");
+    assert!(error.contains("undefined symbol 'frobble'\n  from line 3, col 3"), "{error}");
+}
+
+#[test]
+fn error_reports_call_sites() {
+    let path = write_script("trace", "(define (f x)\n  (+ x (car x)))\n(display 1)\n(f 5)\n");
+    let error = stderr(&vow(&[&path]));
+    let lines: Vec<_> = error.lines().collect();
+    assert!(lines[0].starts_with(&format!("Error at line 2, col 8 of {path}: ")), "{error}");
+    assert_eq!(lines[1..], [format!("  from line 4, col 1 of {path}")], "{error}");
+}
+
+#[test]
+fn error_in_loaded_file() {
+    let library = write_script("library", "(display 1)\n(car 5)\n");
+    let path = write_script("loader", &format!("(newline)\n(load {library:?})\n"));
+    let error = stderr(&vow(&[&path]));
+    assert!(error.starts_with(&format!("Error at line 2, col 1 of {library}: ")), "{error}");
+    assert!(error.ends_with(&format!("\n  from line 2, col 1 of {path}\n")), "{error}");
+}
+
+#[test]
+fn parse_error_fails() {
+    let path = write_script("parse_error", "(display 1)\n(display");
+    let output = vow(&[&path]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn missing_script_fails() {
+    let output = vow(&["/nonexistent/vow/script.scm"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn shebang_line_is_ignored() {
+    let path = write_script("shebang", "#!/usr/bin/env vow\n(display \"hi\")\n(car '())\n");
+    let output = vow(&[&path]);
+    assert_eq!(stdout(&output), "hi");
+    assert!(stderr(&output).starts_with("Error at line 3, col 1"), "{}", stderr(&output));
+}
+
+#[test]
+fn exit_codes() {
+    let path = write_script("exit", "(display 1)\n(exit 3)\n(display 2)\n");
+    let output = vow(&[&path]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "1");
+    assert_eq!(vow(&[&write_script("exit_default", "(exit)")]).status.code(), Some(0));
+    assert_eq!(vow(&[&write_script("exit_false", "(exit #f)")]).status.code(), Some(1));
+}
+
+#[test]
+fn command_line_arguments() {
+    let path = write_script("args", "(write (cdr (command-line)))");
+    assert_eq!(stdout(&vow(&[&path, "--", "a", "-v", "b c"])), r#"("a" "-v" "b c")"#);
+    assert_eq!(stdout(&vow(&[&path])), "()");
+}
+
+#[test]
+fn unknown_argument() {
+    let output = vow(&["--frobble"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Unknown argument '--frobble'"));
+}
+
+#[test]
+fn eval_expressions() {
+    let output = vow(&["-e", "(define x 10)", "-e", "(* x x)"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "100\n");
+    assert_eq!(stdout(&vow(&["--eval", "(+ 1 2) (list 'a \"b\")"])), "3\n(a \"b\")\n");
+}
+
+#[test]
+fn eval_skips_unspecified_values() {
+    assert_eq!(stdout(&vow(&["-e", "(display \"hi\")"])), "hi");
+    assert_eq!(stdout(&vow(&["-e", "(define (f) 1)", "-e", "(f)"])), "1\n");
+}
+
+#[test]
+fn print_expressions() {
+    assert_eq!(stdout(&vow(&["-p", "(define x 10)", "--print", "(display \"\")"])), "10\n()\n");
+}
+
+#[test]
+fn eval_error() {
+    let output = vow(&["-e", "(display 1)", "-e", "(car '())", "-e", "(display 2)"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "1");
+    assert!(stderr(&output).starts_with("Error: "));
+    assert_eq!(vow(&["-e", "(+ 1"]).status.code(), Some(1));
+    assert_eq!(vow(&["-e"]).status.code(), Some(1));
+}
+
+#[test]
+fn eval_exit() {
+    assert_eq!(vow(&["-e", "(exit 4)", "-e", "(display 1)"]).status.code(), Some(4));
+}
+
+#[test]
+fn eval_before_script() {
+    let path = write_script("eval_before", "(display x)");
+    assert_eq!(stdout(&vow(&["-e", "(define x 5)", &path])), "5");
+}
+
+#[test]
+fn rc_file_is_loaded() {
+    let home = temp_home("rc");
+    fs::write(home.join(".vowrc"), "(display \"vowrc\")").unwrap();
+    fs::create_dir_all(home.join("config/vow")).unwrap();
+    fs::write(home.join("config/vow/init.scm"), "(display \" init\")").unwrap();
+    assert!(stdout(&vow_at_home(&home, &[])).contains("vowrc init"));
+}
+
+#[test]
+fn no_rc_flag() {
+    let home = temp_home("norc");
+    fs::write(home.join(".vowrc"), "(display \"vowrc\")").unwrap();
+    assert!(!stdout(&vow_at_home(&home, &["--no-rc"])).contains("vowrc"));
+    assert!(!stdout(&vow_at_home(&home, &["--norc"])).contains("vowrc"));
+}
+
+#[test]
+fn rcfile_flag() {
+    let home = temp_home("rcfile");
+    fs::write(home.join(".vowrc"), "(display \"vowrc\")").unwrap();
+    let custom = home.join("custom.scm");
+    fs::write(&custom, "(display \"custom\")").unwrap();
+    let output = stdout(&vow_at_home(&home, &["--rcfile", custom.to_str().unwrap()]));
+    assert!(output.contains("custom"));
+    assert!(!output.contains("vowrc"));
+}
+
+#[test]
+fn rc_file_error_is_a_warning() {
+    let home = temp_home("rc_error");
+    fs::write(home.join(".vowrc"), "(display \"before\") (car '()) (display \"after\")").unwrap();
+    let output = vow_at_home(&home, &[]);
+    assert!(stdout(&output).contains("before"));
+    assert!(stderr(&output).contains("Warning: error in "), "{}", stderr(&output));
+}
+
+#[test]
+fn rc_file_is_not_loaded_for_scripts() {
+    let home = temp_home("rc_script");
+    fs::write(home.join(".vowrc"), "(display \"vowrc\")").unwrap();
+    assert_eq!(stdout(&vow_at_home(&home, &["-e", "(+ 1 2)"])), "3\n");
+}
+
+#[test]
+fn trace_prints_calls_and_returns() {
+    let output = vow(&[
+        "-e",
+        "(define (fact n) (if (= n 0) 1 (* n (fact (- n 1)))))",
+        "-e",
+        "(trace fact)",
+        "-p",
+        "(fact 2)",
+        "-e",
+        "(untrace fact)",
+        "-e",
+        "(fact 3)",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "2\n6\n");
+    assert_eq!(
+        stderr(&output),
+        "[CALL fact 2]\n  [CALL fact 1]\n    [CALL fact 0]\n    [RETURN fact 1]\n  [RETURN fact 1]\n[RETURN fact 2]\n"
+    );
+}
+
+#[test]
+fn time_prints_elapsed_time() {
+    let output = vow(&["-p", "(time (+ 1 2))"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "3\n");
+    let stderr = stderr(&output);
+    assert!(stderr.starts_with("(time 3: ") && stderr.ends_with("ms)\n"), "{stderr}");
+}
+
+#[test]
+fn unbounded_recursion_reports_error() {
+    let output = vow(&["-e", "(define (f n) (+ 1 (f n)))", "-p", "(f 0)"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("maximum recursion depth of 10000 exceeded"));
+}
+
+#[test]
+fn recursion_limit_from_environment() {
+    let output = Command::new(env!("CARGO_BIN_EXE_vow"))
+        .args(["-e", "(define (f n) (+ 1 (f n)))", "-p", "(f 0)"])
+        .env("VOW_RECURSION_LIMIT", "50")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("maximum recursion depth of 50 exceeded"));
+}
+
+#[test]
+fn current_error_port_writes_to_stderr() {
+    let output = vow(&["-e", "(display \"oops\" (current-error-port))", "-p", "1"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1\n");
+    assert_eq!(stderr(&output), "oops");
+}
@@ -0,0 +1,63 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn empty_and_or() {
+    assert_eq!(eval("(and)"), "#t");
+    assert_eq!(eval("(or)"), "#f");
+}
+
+#[test]
+fn and_returns_last_value() {
+    assert_eq!(eval("(and #t (list 1 2))"), "(1 2)");
+}
+
+#[test]
+fn and_returns_first_false_value() {
+    assert_eq!(eval("(and #t #f #t)"), "#f");
+}
+
+#[test]
+fn or_returns_first_true_value() {
+    assert_eq!(eval("(or #f (list 1) (list 2))"), "(1)");
+}
+
+#[test]
+fn or_returns_last_value() {
+    assert_eq!(eval("(or #f #f)"), "#f");
+}
+
+#[test]
+fn and_short_circuits() {
+    assert_eq!(eval("(and #f (undefined-function))"), "#f");
+}
+
+#[test]
+fn or_short_circuits() {
+    assert_eq!(eval("(or #t (undefined-function))"), "#t");
+}
+
+#[test]
+fn and_or_tail_position() {
+    let program = "(define loop
+                     (lambda (n) (or (= n 100000) (and #t (loop (+ n 1))))))
+                   (loop 0)";
+    assert_eq!(eval(program), "#t");
+}
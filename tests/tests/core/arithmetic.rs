@@ -0,0 +1,349 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::{runner, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn addition() {
+    assert_eq!(eval("(+)"), "0");
+    assert_eq!(eval("(+ 3)"), "3");
+    assert_eq!(eval("(+ 1 2 3 4)"), "10");
+}
+
+#[test]
+fn multiplication() {
+    assert_eq!(eval("(*)"), "1");
+    assert_eq!(eval("(* 3)"), "3");
+    assert_eq!(eval("(* 1 2 3 4)"), "24");
+}
+
+#[test]
+fn subtraction() {
+    assert_eq!(eval("(- 5)"), "-5");
+    assert_eq!(eval("(- 5 1)"), "4");
+    assert_eq!(eval("(- 10 1 2 3)"), "4");
+    assert!(runner::eval_to_string("(-)").is_err());
+}
+
+#[test]
+fn division() {
+    assert_eq!(eval("(/ 2)"), "0.5");
+    assert_eq!(eval("(/ 10 4)"), "2.5");
+    assert_eq!(eval("(/ 120 2 3 4)"), "5");
+    assert!(runner::eval_to_string("(/)").is_err());
+}
+
+#[test]
+fn arithmetic_type_error() {
+    assert!(runner::eval_to_string("(+ 1 'a)").is_err());
+}
+
+#[test]
+fn numeric_equality() {
+    assert_eq!(eval("(= 1 1 1)"), "#t");
+    assert_eq!(eval("(= 1 1 2)"), "#f");
+}
+
+#[test]
+fn numeric_ordering() {
+    assert_eq!(eval("(< 1 2 3)"), "#t");
+    assert_eq!(eval("(< 1 3 2)"), "#f");
+    assert_eq!(eval("(> 3 2 1)"), "#t");
+    assert_eq!(eval("(> 3 1 2)"), "#f");
+    assert_eq!(eval("(<= 1 1 2)"), "#t");
+    assert_eq!(eval("(<= 1 2 1)"), "#f");
+    assert_eq!(eval("(>= 2 2 1)"), "#t");
+    assert_eq!(eval("(>= 2 1 2)"), "#f");
+}
+
+#[test]
+fn comparison_requires_two_arguments() {
+    assert!(runner::eval_to_string("(< 1)").is_err());
+    assert!(runner::eval_to_string("(=)").is_err());
+}
+
+#[test]
+fn exact_integers_are_precise() {
+    assert_eq!(eval("(= (expt 2 53) (+ (expt 2 53) 1))"), "#f");
+    assert_eq!(eval("(+ (expt 2 53) 1)"), "9007199254740993");
+}
+
+#[test]
+fn mixed_arithmetic_is_inexact() {
+    assert_eq!(eval("(+ 1 2.0)"), "3.0");
+    assert_eq!(eval("(* 2 1.5)"), "3.0");
+    assert_eq!(eval("(= 1 1.0)"), "#t");
+    assert_eq!(eval("(max 1 2.0 3)"), "3.0");
+}
+
+#[test]
+fn integer_overflow_becomes_inexact() {
+    assert_eq!(eval("(inexact? (* 9223372036854775807 2))"), "#t");
+}
+
+#[test]
+fn exactness_predicates_and_conversions() {
+    assert_eq!(eval("(exact? 1)"), "#t");
+    assert_eq!(eval("(exact? 1.0)"), "#f");
+    assert_eq!(eval("(inexact? 1.5)"), "#t");
+    assert_eq!(eval("(inexact? 1)"), "#f");
+    assert_eq!(eval("(exact->inexact 3)"), "3.0");
+    assert_eq!(eval("(inexact->exact 3.0)"), "3");
+    assert!(runner::eval_to_string("(exact? 'a)").is_err());
+}
+
+#[test]
+fn inexact_to_exact_rounds_to_nearest() {
+    assert_eq!(eval("(inexact->exact 3.7)"), "4");
+    assert_eq!(eval("(inexact->exact -3.7)"), "-4");
+    assert_eq!(eval("(inexact->exact 2.5)"), "2");
+    assert_eq!(eval("(inexact->exact 3.5)"), "4");
+    assert_eq!(eval("(inexact->exact 7)"), "7");
+}
+
+#[test]
+fn inexact_to_exact_rejects_non_finite() {
+    assert!(runner::eval_to_string("(inexact->exact +inf.0)").is_err());
+    assert!(runner::eval_to_string("(inexact->exact -inf.0)").is_err());
+    assert!(runner::eval_to_string("(inexact->exact +nan.0)").is_err());
+    assert!(runner::eval_to_string("(inexact->exact 1e300)").is_err());
+    assert!(runner::eval_to_string("(exact +nan.0)").is_err());
+}
+
+#[test]
+fn exact_and_inexact() {
+    assert_eq!(eval("(exact 2.0)"), "2");
+    assert_eq!(eval("(exact 5)"), "5");
+    assert_eq!(eval("(inexact 5)"), "5.0");
+    assert_eq!(eval("(inexact 1.5)"), "1.5");
+    assert_eq!(eval("(exact? (exact (sqrt 2)))"), "#t");
+    assert_eq!(eval("(exact (sqrt 16))"), "4");
+    assert_eq!(eval("(exact (expt 2.0 10))"), "1024");
+    assert!(runner::eval_to_string("(inexact \"1\")").is_err());
+}
+
+#[test]
+fn integer_predicate() {
+    assert_eq!(eval("(integer? 3)"), "#t");
+    assert_eq!(eval("(integer? 3.0)"), "#t");
+    assert_eq!(eval("(integer? 3.5)"), "#f");
+    assert_eq!(eval("(integer? 'a)"), "#f");
+}
+
+#[test]
+fn rounding() {
+    assert_eq!(eval("(floor -4.3)"), "-5.0");
+    assert_eq!(eval("(ceiling -4.3)"), "-4.0");
+    assert_eq!(eval("(truncate -4.3)"), "-4.0");
+    assert_eq!(eval("(round -4.3)"), "-4.0");
+    assert_eq!(eval("(round 2.5)"), "2.0");
+    assert_eq!(eval("(round 3.5)"), "4.0");
+    assert_eq!(eval("(round 7)"), "7");
+}
+
+#[test]
+fn rounding_exact_integers() {
+    assert_eq!(eval("(floor -4)"), "-4");
+    assert_eq!(eval("(ceiling 4)"), "4");
+    assert_eq!(eval("(truncate -4)"), "-4");
+    assert_eq!(eval("(exact? (floor 5))"), "#t");
+    assert_eq!(eval("(exact? (floor 5.5))"), "#f");
+}
+
+#[test]
+fn floor_division() {
+    assert_eq!(eval("(floor-quotient 7 2)"), "3");
+    assert_eq!(eval("(floor-quotient -7 2)"), "-4");
+    assert_eq!(eval("(floor-remainder -7 2)"), "1");
+    assert_eq!(eval("(floor-remainder 7 -2)"), "-1");
+    assert_eq!(eval("(call-with-values (lambda () (floor/ -7 2)) list)"), "(-4 1)");
+}
+
+#[test]
+fn truncate_division() {
+    assert_eq!(eval("(truncate-quotient -7 2)"), "-3");
+    assert_eq!(eval("(truncate-remainder -7 2)"), "-1");
+    assert_eq!(eval("(truncate-remainder 7 -2)"), "1");
+    assert_eq!(eval("(call-with-values (lambda () (truncate/ -7 2)) list)"), "(-3 -1)");
+}
+
+#[test]
+fn integer_division_of_inexact_integers() {
+    assert_eq!(eval("(floor-quotient -7.0 2)"), "-4.0");
+    assert_eq!(eval("(truncate-remainder -7 2.0)"), "-1.0");
+    assert!(runner::eval_to_string("(floor-quotient 7.5 2)").is_err());
+}
+
+#[test]
+fn integer_division_by_zero() {
+    assert!(runner::eval_to_string("(floor-quotient 1 0)").is_err());
+    assert!(runner::eval_to_string("(truncate-remainder 1.0 0.0)").is_err());
+}
+
+#[test]
+fn number_predicate() {
+    assert_eq!(eval("(number? 1)"), "#t");
+    assert_eq!(eval("(number? 1.5)"), "#t");
+    assert_eq!(eval("(number? 'a)"), "#f");
+}
+
+#[test]
+fn sign_predicates() {
+    assert_eq!(eval("(zero? 0)"), "#t");
+    assert_eq!(eval("(zero? -0.0)"), "#t");
+    assert_eq!(eval("(zero? 0.1)"), "#f");
+    assert_eq!(eval("(positive? 3)"), "#t");
+    assert_eq!(eval("(positive? 0)"), "#f");
+    assert_eq!(eval("(negative? -2.5)"), "#t");
+    assert_eq!(eval("(negative? -0.0)"), "#f");
+}
+
+#[test]
+fn parity_predicates() {
+    assert_eq!(eval("(odd? 3)"), "#t");
+    assert_eq!(eval("(odd? -3)"), "#t");
+    assert_eq!(eval("(odd? 2.0)"), "#f");
+    assert_eq!(eval("(even? 0)"), "#t");
+    assert_eq!(eval("(even? -4)"), "#t");
+    assert_eq!(eval("(even? 3.0)"), "#f");
+}
+
+#[test]
+fn exact_integer_predicate() {
+    assert_eq!(eval("(exact-integer? 5)"), "#t");
+    assert_eq!(eval("(exact-integer? 5.0)"), "#f");
+    assert_eq!(eval("(exact-integer? 'five)"), "#f");
+}
+
+#[test]
+fn numeric_predicates_reject_non_numbers() {
+    for input in ["(zero? 'a)", "(positive? \"1\")", "(negative? '())", "(odd? 1.5)", "(even? 'a)"]
+    {
+        assert!(
+            matches!(runner::eval_to_string(input), Err(VowError::TypeError { .. })),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn quotient_remainder_modulo() {
+    assert_eq!(eval("(quotient 17 5)"), "3");
+    assert_eq!(eval("(quotient -17 5)"), "-3");
+    assert_eq!(eval("(remainder 17 -5)"), "2");
+    assert_eq!(eval("(remainder -17 5)"), "-2");
+    assert_eq!(eval("(modulo 17 -5)"), "-3");
+    assert_eq!(eval("(modulo -17 5)"), "3");
+    assert_eq!(eval("(modulo -7.0 2)"), "1.0");
+}
+
+#[test]
+fn quotient_remainder_modulo_by_zero() {
+    assert!(runner::eval_to_string("(quotient 1 0)").is_err());
+    assert!(runner::eval_to_string("(remainder 1 0)").is_err());
+    assert!(runner::eval_to_string("(modulo 1.0 0)").is_err());
+}
+
+#[test]
+fn gcd_and_lcm() {
+    assert_eq!(eval("(gcd)"), "0");
+    assert_eq!(eval("(lcm)"), "1");
+    assert_eq!(eval("(gcd 12 8)"), "4");
+    assert_eq!(eval("(gcd -12 18 8)"), "2");
+    assert_eq!(eval("(lcm 4 6)"), "12");
+    assert_eq!(eval("(lcm -3 4 0)"), "0");
+    assert_eq!(eval("(gcd 32.0 -36)"), "4.0");
+    assert!(runner::eval_to_string("(gcd 1.5 3)").is_err());
+}
+
+#[test]
+fn trigonometry_identities() {
+    assert_eq!(eval("(= (sin 0) 0)"), "#t");
+    assert_eq!(eval("(= (cos 0) 1)"), "#t");
+    assert_eq!(eval("(= (tan 0) 0)"), "#t");
+    assert_eq!(eval("(< (abs (- (sin (/ pi 2)) 1)) 1e-12)"), "#t");
+    assert_eq!(eval("(= (asin 1) (/ pi 2))"), "#t");
+    assert_eq!(eval("(= (acos 1) 0)"), "#t");
+}
+
+#[test]
+fn arctangent() {
+    assert_eq!(eval("(= (atan 1) (/ pi 4))"), "#t");
+    assert_eq!(eval("(= (atan 1 -1) (* 3 (/ pi 4)))"), "#t");
+    assert_eq!(eval("(= (atan -1 0) (- (/ pi 2)))"), "#t");
+}
+
+#[test]
+fn exponentials_and_logarithms() {
+    assert_eq!(eval("(= (exp 0) 1)"), "#t");
+    assert_eq!(eval("(= (exp 1) e)"), "#t");
+    assert_eq!(eval("(= (log 1) 0)"), "#t");
+    assert_eq!(eval("(= (log e) 1)"), "#t");
+    assert_eq!(eval("(log 8 2)"), "3.0");
+    assert_eq!(eval("(log 100 10)"), "2.0");
+}
+
+#[test]
+fn square_roots() {
+    assert_eq!(eval("(sqrt 16)"), "4");
+    assert_eq!(eval("(sqrt 2.25)"), "1.5");
+    assert_eq!(eval("(sqrt 2)"), "1.4142135623730951");
+    assert_eq!(eval("(exact? (sqrt 2))"), "#f");
+}
+
+#[test]
+fn square_root_of_negative_number_is_complex() {
+    assert_eq!(eval("(sqrt -1)"), "0+1i");
+    assert_eq!(eval("(sqrt -2.25)"), "0+1.5i");
+}
+
+#[test]
+fn special_float_literals() {
+    assert_eq!(eval("(list +inf.0 -inf.0 +nan.0)"), "(+inf.0 -inf.0 +nan.0)");
+    assert_eq!(eval("(> +inf.0 1e308)"), "#t");
+}
+
+#[test]
+fn rounding_to_exact() {
+    assert_eq!(eval("(floor->exact 2.7)"), "2");
+    assert_eq!(eval("(floor->exact -2.2)"), "-3");
+    assert_eq!(eval("(ceiling->exact 2.2)"), "3");
+    assert_eq!(eval("(round->exact 2.5)"), "2");
+    assert_eq!(eval("(round->exact 3.5)"), "4");
+    assert_eq!(eval("(truncate->exact -2.7)"), "-2");
+    assert_eq!(eval("(floor->exact 7)"), "7");
+    assert_eq!(eval("(exact? (round->exact 1.2))"), "#t");
+    assert!(runner::eval_to_string("(floor->exact +inf.0)").is_err());
+    assert!(runner::eval_to_string("(round->exact 'a)").is_err());
+}
+
+#[test]
+fn exact_integer_sqrt() {
+    assert_eq!(eval("(call-with-values (lambda () (exact-integer-sqrt 17)) list)"), "(4 1)");
+    assert_eq!(eval("(call-with-values (lambda () (exact-integer-sqrt 16)) list)"), "(4 0)");
+    assert_eq!(eval("(call-with-values (lambda () (exact-integer-sqrt 0)) list)"), "(0 0)");
+    assert_eq!(
+        eval(
+            "(let-values (((s r) (exact-integer-sqrt 1000000007)))
+               (list s r (= 1000000007 (+ (* s s) r))))"
+        ),
+        "(31622 49123 #t)"
+    );
+    assert!(runner::eval_to_string("(exact-integer-sqrt -1)").is_err());
+    assert!(runner::eval_to_string("(exact-integer-sqrt 4.0)").is_err());
+}
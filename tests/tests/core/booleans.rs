@@ -0,0 +1,64 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn not_accepts_any_value() {
+    assert_eq!(eval("(not #f)"), "#t");
+    assert_eq!(eval("(not #t)"), "#f");
+    assert_eq!(eval("(not 0)"), "#f");
+    assert_eq!(eval("(not \"\")"), "#f");
+    assert_eq!(eval("(not 'a)"), "#f");
+    assert_eq!(eval("(not car)"), "#f");
+    assert_eq!(eval("(not '())"), "#t");
+}
+
+#[test]
+fn non_boolean_values_are_true_in_conditionals() {
+    assert_eq!(eval("(if 0 'yes 'no)"), "yes");
+    assert_eq!(eval("(if \"\" 'yes 'no)"), "yes");
+    assert_eq!(eval("(cond (#\\a 'yes) (else 'no))"), "yes");
+    assert_eq!(eval("(and 1 2 3)"), "3");
+    assert_eq!(eval("(or #f 'a 'b)"), "a");
+    assert_eq!(eval("(when 1 'yes)"), "yes");
+}
+
+#[test]
+fn boolean_predicate() {
+    assert_eq!(eval("(boolean? #f)"), "#t");
+    assert_eq!(eval("(boolean? 0)"), "#f");
+    assert_eq!(eval("(boolean? '())"), "#f");
+}
+
+#[test]
+fn boolean_equal() {
+    assert_eq!(eval("(boolean=? #t #t)"), "#t");
+    assert_eq!(eval("(boolean=? #f #f #f)"), "#t");
+    assert_eq!(eval("(boolean=? #t #f)"), "#f");
+    assert_eq!(eval("(boolean=? #t #t #f)"), "#f");
+    assert!(runner::eval_to_string("(boolean=? #t 1)").is_err());
+    assert!(runner::eval_to_string("(boolean=? #t)").is_err());
+}
+
+#[test]
+fn boolean_to_string() {
+    assert_eq!(eval("(boolean->string #t)"), "\"#t\"");
+    assert_eq!(eval("(boolean->string #f)"), "\"#f\"");
+    assert!(runner::eval_to_string("(boolean->string 1)").is_err());
+}
@@ -0,0 +1,104 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn bytevector_literal() {
+    assert_eq!(eval("#u8(1 2 255)"), "#u8(1 2 255)");
+    assert_eq!(eval("#u8()"), "#u8()");
+    assert_eq!(eval("'#u8(0 #xff)"), "#u8(0 255)");
+}
+
+#[test]
+fn invalid_bytevector_literal() {
+    assert!(runner::eval_to_string("#u8(256)").is_err());
+    assert!(runner::eval_to_string("#u8(-1)").is_err());
+    assert!(runner::eval_to_string("#u8(1.0)").is_err());
+    assert!(runner::eval_to_string("#u8(a)").is_err());
+    assert!(runner::eval_to_string("#u8(1 2").is_err());
+}
+
+#[test]
+fn make_bytevector() {
+    assert_eq!(eval("(make-bytevector 3)"), "#u8(0 0 0)");
+    assert_eq!(eval("(make-bytevector 2 7)"), "#u8(7 7)");
+    assert!(runner::eval_to_string("(make-bytevector 2 256)").is_err());
+}
+
+#[test]
+fn bytevector_constructor_and_predicate() {
+    assert_eq!(eval("(bytevector 1 3 5)"), "#u8(1 3 5)");
+    assert_eq!(eval("(bytevector? (bytevector))"), "#t");
+    assert_eq!(eval("(bytevector? #(1 2))"), "#f");
+    assert!(runner::eval_to_string("(bytevector 1 'a)").is_err());
+}
+
+#[test]
+fn bytevector_access() {
+    assert_eq!(eval("(bytevector-length #u8(1 2 3))"), "3");
+    assert_eq!(eval("(bytevector-u8-ref #u8(5 6 7) 1)"), "6");
+    assert_eq!(
+        eval("(define b (bytevector 1 2 3)) (bytevector-u8-set! b 1 200) b"),
+        "#u8(1 200 3)"
+    );
+    assert!(runner::eval_to_string("(bytevector-u8-ref #u8(1) 1)").is_err());
+}
+
+#[test]
+fn bytevector_copy() {
+    assert_eq!(eval("(bytevector-copy #u8(1 2 3 4) 1 3)"), "#u8(2 3)");
+    assert_eq!(
+        eval("(define a #u8(1 2)) (define b (bytevector-copy a)) (bytevector-u8-set! b 0 9) a"),
+        "#u8(1 2)"
+    );
+    assert_eq!(
+        eval("(define b (bytevector 1 2 3 4 5)) (bytevector-copy! b 1 #u8(8 9)) b"),
+        "#u8(1 8 9 4 5)"
+    );
+    assert_eq!(
+        eval("(define b (bytevector 1 2 3 4 5)) (bytevector-copy! b 0 b 2) b"),
+        "#u8(3 4 5 4 5)"
+    );
+}
+
+#[test]
+fn bytevector_append() {
+    assert_eq!(eval("(bytevector-append #u8(1) #u8() #u8(2 3))"), "#u8(1 2 3)");
+}
+
+#[test]
+fn bytevector_equality() {
+    assert_eq!(eval("(equal? #u8(1 2) (bytevector 1 2))"), "#t");
+    assert_eq!(eval("(eqv? #u8(1 2) (bytevector 1 2))"), "#f");
+    assert_eq!(eval("(define b #u8(1)) (eq? b b)"), "#t");
+}
+
+#[test]
+fn utf8_conversion() {
+    assert_eq!(eval("(string->utf8 \"abc\")"), "#u8(97 98 99)");
+    assert_eq!(eval("(string->utf8 \"λ\")"), "#u8(206 187)");
+    assert_eq!(eval("(utf8->string #u8(104 105))"), "\"hi\"");
+    assert_eq!(eval("(string->utf8 \"hello\" 1 3)"), "#u8(101 108)");
+    assert!(runner::eval_to_string("(utf8->string #u8(255))").is_err());
+}
+
+#[test]
+fn utf8_round_trip() {
+    assert_eq!(eval("(utf8->string (string->utf8 \"héllo, wörld ✓\"))"), "\"héllo, wörld ✓\"");
+}
@@ -0,0 +1,56 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+const CLASSIFY: &str = "(define classify
+                          (lambda (x) (case x ((1 2) 'low) ((3 4) 'mid) (else 'high))))";
+
+#[test]
+fn case_matches_datum_list() {
+    assert_eq!(eval(&format!("{CLASSIFY} (classify 2)")), "low");
+    assert_eq!(eval(&format!("{CLASSIFY} (classify 3)")), "mid");
+}
+
+#[test]
+fn case_else_clause() {
+    assert_eq!(eval(&format!("{CLASSIFY} (classify 10)")), "high");
+}
+
+#[test]
+fn case_datums_are_not_evaluated() {
+    assert_eq!(eval("(case 'banana ((apple banana) 'fruit) (else 'unknown))"), "fruit");
+}
+
+#[test]
+fn case_evaluates_key_once() {
+    assert_eq!(
+        eval("(define n 0) (case (begin (set! n (+ n 1)) n) ((5) 'five) ((1) n) (else 'none))"),
+        "1"
+    );
+}
+
+#[test]
+fn case_without_match_returns_unspecified() {
+    assert_eq!(eval("(case 7 ((1) 'one))"), "()");
+}
+
+#[test]
+fn case_evaluates_all_body_expressions() {
+    assert_eq!(eval("(define x 0) (case 1 ((1) (set! x 10) (+ x 1)))"), "11");
+}
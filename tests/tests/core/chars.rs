@@ -0,0 +1,124 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn char_literals() {
+    assert_eq!(eval("#\\a"), "#\\a");
+    assert_eq!(eval("#\\space"), "#\\space");
+    assert_eq!(eval("#\\newline"), "#\\newline");
+    assert_eq!(eval("#\\altmode"), "#\\escape");
+    assert_eq!(eval("#\\rubout"), "#\\delete");
+    assert_eq!(eval("(quote (#\\( #\\)))"), "(#\\( #\\))");
+}
+
+#[test]
+fn unknown_char_name_is_an_error() {
+    assert!(runner::eval_to_string("#\\bogus").is_err());
+}
+
+#[test]
+fn char_integer_conversion() {
+    assert_eq!(eval("(char->integer #\\A)"), "65");
+    assert_eq!(eval("(char->integer #\\tab)"), "9");
+    assert_eq!(eval("(integer->char 97)"), "#\\a");
+    assert!(runner::eval_to_string("(integer->char 1.5)").is_err());
+}
+
+#[test]
+fn char_integer_conversion_unicode() {
+    assert_eq!(eval("(char->integer #\\λ)"), "955");
+    assert_eq!(eval("(char->integer #\\😀)"), "128512");
+    assert_eq!(eval("(integer->char #x1F600)"), "#\\😀");
+    assert_eq!(eval("(integer->char #x10FFFF)"), "#\\\u{10FFFF}");
+    assert_eq!(eval("(char->integer (integer->char 0))"), "0");
+}
+
+#[test]
+fn integer_to_char_rejects_invalid_code_points() {
+    assert!(runner::eval_to_string("(integer->char #x110000)").is_err());
+    assert!(runner::eval_to_string("(integer->char #xD800)").is_err());
+    assert!(runner::eval_to_string("(integer->char #xDFFF)").is_err());
+    assert!(runner::eval_to_string("(integer->char -1)").is_err());
+}
+
+#[test]
+fn char_comparisons() {
+    assert_eq!(eval("(char=? #\\a #\\a #\\a)"), "#t");
+    assert_eq!(eval("(char<? #\\a #\\b #\\c)"), "#t");
+    assert_eq!(eval("(char<? #\\a #\\c #\\b)"), "#f");
+    assert_eq!(eval("(char>=? #\\b #\\b #\\a)"), "#t");
+    assert!(runner::eval_to_string("(char=? #\\a 1)").is_err());
+}
+
+#[test]
+fn char_classification() {
+    assert_eq!(eval("(char? #\\a)"), "#t");
+    assert_eq!(eval("(char? \"a\")"), "#f");
+    assert_eq!(eval("(char-alphabetic? #\\a)"), "#t");
+    assert_eq!(eval("(char-numeric? #\\7)"), "#t");
+    assert_eq!(eval("(char-whitespace? #\\space)"), "#t");
+    assert_eq!(eval("(char-upper-case? #\\A)"), "#t");
+    assert_eq!(eval("(char-lower-case? #\\A)"), "#f");
+}
+
+#[test]
+fn char_case_conversion() {
+    assert_eq!(eval("(char-upcase #\\a)"), "#\\A");
+    assert_eq!(eval("(char-downcase #\\A)"), "#\\a");
+    assert_eq!(eval("(char-upcase #\\1)"), "#\\1");
+    assert_eq!(eval("(char-upcase #\\λ)"), "#\\Λ");
+    assert_eq!(eval("(char-downcase #\\Ä)"), "#\\ä");
+    assert_eq!(eval("(char-upcase #\\😀)"), "#\\😀");
+}
+
+#[test]
+fn char_case_conversion_to_several_chars_is_unchanged() {
+    assert_eq!(eval("(char-upcase #\\ß)"), "#\\ß");
+    assert_eq!(eval("(char-downcase (integer->char #x130))"), "#\\\u{130}");
+}
+
+#[test]
+fn char_foldcase() {
+    assert_eq!(eval("(char-foldcase #\\A)"), "#\\a");
+    assert_eq!(eval("(char-foldcase #\\a)"), "#\\a");
+    assert_eq!(eval("(char-foldcase #\\Σ)"), "#\\σ");
+    assert_eq!(eval("(char-foldcase #\\ς)"), "#\\σ");
+    assert_eq!(eval("(char-foldcase #\\ß)"), "#\\ß");
+}
+
+#[test]
+fn char_ci_comparisons() {
+    assert_eq!(eval("(char-ci=? #\\a #\\A #\\a)"), "#t");
+    assert_eq!(eval("(char-ci=? #\\a #\\b)"), "#f");
+    assert_eq!(eval("(char-ci<? #\\a #\\B #\\c)"), "#t");
+    assert_eq!(eval("(char<? #\\a #\\B)"), "#f");
+    assert_eq!(eval("(char-ci>? #\\b #\\A)"), "#t");
+    assert_eq!(eval("(char-ci<=? #\\a #\\A)"), "#t");
+    assert_eq!(eval("(char-ci>=? #\\a #\\B)"), "#f");
+    assert!(runner::eval_to_string("(char-ci=? #\\a \"a\")").is_err());
+}
+
+#[test]
+fn char_ci_comparisons_unicode() {
+    assert_eq!(eval("(char-ci=? #\\λ #\\Λ)"), "#t");
+    assert_eq!(eval("(char-ci=? #\\Σ #\\σ #\\ς)"), "#t");
+    assert_eq!(eval("(char-ci=? #\\Ä #\\ä)"), "#t");
+    assert_eq!(eval("(char-ci<? #\\ä #\\Ö)"), "#t");
+}
@@ -0,0 +1,102 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::{runner, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn complex_arithmetic() {
+    assert_eq!(eval("(+ 1+2i 3-1i)"), "4+1i");
+    assert_eq!(eval("(- 1+2i)"), "-1-2i");
+    assert_eq!(eval("(* 1+2i 3+4i)"), "-5+10i");
+    assert_eq!(eval("(/ 1+2i 2)"), "0.5+1i");
+}
+
+#[test]
+fn mixed_real_and_complex_arithmetic() {
+    assert_eq!(eval("(+ 1 (sqrt -4))"), "1+2i");
+    assert_eq!(eval("(* 2 1.5+2i)"), "3+4i");
+    assert_eq!(eval("(- 5 1+1i)"), "4-1i");
+}
+
+#[test]
+fn complex_results_with_no_imaginary_part_are_real() {
+    assert_eq!(eval("(* (sqrt -1) (sqrt -1))"), "-1.0");
+    assert_eq!(eval("(real? (+ 1+2i 1-2i))"), "#t");
+}
+
+#[test]
+fn complex_equality() {
+    assert_eq!(eval("(= 1+2i (make-rectangular 1 2))"), "#t");
+    assert_eq!(eval("(= 1+2i 1-2i)"), "#f");
+    assert_eq!(eval("(= 1+2i 1)"), "#f");
+}
+
+#[test]
+fn complex_numbers_are_not_ordered() {
+    assert!(runner::eval_to_string("(< 1+2i 3)").is_err());
+    assert!(runner::eval_to_string("(max 1+2i 3)").is_err());
+}
+
+#[test]
+fn constructors_and_accessors() {
+    assert_eq!(eval("(make-rectangular 1 2)"), "1+2i");
+    assert_eq!(eval("(make-polar 2 0)"), "2.0");
+    assert_eq!(eval("(real-part 1+2i)"), "1.0");
+    assert_eq!(eval("(imag-part 1+2i)"), "2.0");
+    assert_eq!(eval("(real-part 5)"), "5");
+    assert_eq!(eval("(imag-part 5)"), "0");
+}
+
+#[test]
+fn magnitude_and_angle() {
+    assert_eq!(eval("(magnitude 3+4i)"), "5.0");
+    assert_eq!(eval("(magnitude -7)"), "7");
+    assert_eq!(eval("(angle -1)"), "3.141592653589793");
+    assert_eq!(eval("(= (angle 0+1i) (/ pi 2))"), "#t");
+}
+
+#[test]
+fn complex_and_real_predicates() {
+    assert_eq!(eval("(complex? 1+2i)"), "#t");
+    assert_eq!(eval("(complex? 1)"), "#t");
+    assert_eq!(eval("(complex? 'a)"), "#f");
+    assert_eq!(eval("(real? 1.5)"), "#t");
+    assert_eq!(eval("(real? 1+2i)"), "#f");
+}
+
+#[test]
+fn complex_square_roots_and_powers() {
+    assert_eq!(eval("(sqrt -4)"), "0+2i");
+    assert_eq!(eval("(sqrt -4.0+0i)"), "0+2i");
+    assert_eq!(eval("(< (magnitude (- (expt 0+1i 2) -1)) 1e-12)"), "#t");
+    assert_eq!(eval("(complex? (expt -8 0.5))"), "#t");
+    assert_eq!(eval("(real? (expt -8 0.5))"), "#f");
+}
+
+#[test]
+fn sign_predicates_on_complex_numbers() {
+    assert_eq!(eval("(zero? (sqrt -4))"), "#f");
+    assert_eq!(eval("(zero? (- 1+2i 1+2i))"), "#t");
+    assert_eq!(eval("(zero? (* 0 1+2i))"), "#t");
+    for input in ["(positive? 1+2i)", "(negative? (sqrt -4))"] {
+        match runner::eval_to_string(input) {
+            Err(VowError::TypeError { expected, .. }) => assert_eq!(expected, "real number"),
+            result => panic!("{input}: {result:?}"),
+        }
+    }
+}
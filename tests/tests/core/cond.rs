@@ -0,0 +1,53 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn cond_selects_first_true_clause() {
+    let classify = "(define classify
+                      (lambda (x) (cond ((= x 1) 'one) ((= x 2) 'two) (else 'other))))";
+    assert_eq!(eval(&format!("{classify} (classify 1)")), "one");
+    assert_eq!(eval(&format!("{classify} (classify 2)")), "two");
+    assert_eq!(eval(&format!("{classify} (classify 3)")), "other");
+}
+
+#[test]
+fn cond_evaluates_all_body_expressions() {
+    assert_eq!(eval("(define x 0) (cond (#t (set! x 5) (+ x 1)))"), "6");
+}
+
+#[test]
+fn cond_clause_without_body_returns_test_value() {
+    assert_eq!(eval("(cond ((list 1 2)) (else 'no))"), "(1 2)");
+}
+
+#[test]
+fn cond_arrow_clause() {
+    assert_eq!(eval("(cond ((list 1 2) => length) (else 0))"), "2");
+}
+
+#[test]
+fn cond_does_not_evaluate_later_tests() {
+    assert_eq!(eval("(cond (#t 'first) ((undefined-function) 'second))"), "first");
+}
+
+#[test]
+fn cond_without_match_is_an_error() {
+    assert!(runner::eval_to_string("(cond (#f 1))").is_err());
+}
@@ -0,0 +1,80 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn escape_returns_value() {
+    assert_eq!(eval("(call/cc (lambda (k) (k 42) 99))"), "42");
+    assert_eq!(eval("(call-with-current-continuation (lambda (k) 99))"), "99");
+    assert_eq!(eval("(+ 1 (call/cc (lambda (k) (+ 10 (k 1)))))"), "2");
+}
+
+#[test]
+fn early_exit_from_for_each() {
+    assert_eq!(
+        eval(
+            "(define (find-first pred lst)
+               (call/cc
+                 (lambda (return)
+                   (for-each (lambda (x) (if (pred x) (return x) #f)) lst)
+                   #f)))
+             (list (find-first (lambda (x) (> x 2)) '(1 2 3 4))
+                   (find-first (lambda (x) (> x 9)) '(1 2 3 4)))"
+        ),
+        "(3 #f)"
+    );
+}
+
+#[test]
+fn break_out_of_nested_loops() {
+    assert_eq!(
+        eval(
+            "(define (find-pair target)
+               (call/cc
+                 (lambda (break)
+                   (do ((i 0 (+ i 1))) ((= i 10) #f)
+                     (do ((j 0 (+ j 1))) ((= j 10))
+                       (if (= (* i j) target) (break (list i j)) #f))))))
+             (find-pair 12)"
+        ),
+        "(2 6)"
+    );
+}
+
+#[test]
+fn inner_continuation_does_not_escape_outer() {
+    assert_eq!(
+        eval("(call/cc (lambda (outer) (+ 1 (call/cc (lambda (inner) (inner 10))))))"),
+        "11"
+    );
+    assert_eq!(
+        eval("(call/cc (lambda (outer) (+ 1 (call/cc (lambda (inner) (outer 10))))))"),
+        "10"
+    );
+}
+
+#[test]
+fn continuation_is_a_procedure() {
+    assert_eq!(eval("(call/cc procedure?)"), "#t");
+}
+
+#[test]
+fn invoking_continuation_after_return_is_an_error() {
+    assert!(runner::eval_to_string("(define k (call/cc (lambda (c) c))) (k 1)").is_err());
+}
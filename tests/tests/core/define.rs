@@ -0,0 +1,124 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn define_value() {
+    assert_eq!(eval("(define x 42) x"), "42");
+}
+
+#[test]
+fn define_procedure_shorthand() {
+    assert_eq!(eval("(define (add x y) (+ x y)) (add 1 2)"), "3");
+}
+
+#[test]
+fn define_procedure_without_parameters() {
+    assert_eq!(eval("(define (answer) 42) (answer)"), "42");
+}
+
+#[test]
+fn define_procedure_multiple_body_expressions() {
+    assert_eq!(eval("(define n 0) (define (bump x) (set! n (+ n x)) (* n 2)) (bump 5)"), "10");
+}
+
+#[test]
+fn define_recursive_procedure() {
+    assert_eq!(eval("(define (fact n) (if (= n 0) 1 (* n (fact (- n 1))))) (fact 6)"), "720");
+}
+
+#[test]
+fn define_rest_argument() {
+    assert_eq!(eval("(define (f x . rest) (list x rest)) (f 1 2 3)"), "(1 (2 3))");
+    assert_eq!(eval("(define (f x . rest) (list x rest)) (f 1)"), "(1 ())");
+}
+
+#[test]
+fn define_rest_argument_requires_fixed_arguments() {
+    assert!(runner::eval_to_string("(define (f x y . rest) x) (f 1)").is_err());
+}
+
+#[test]
+fn define_invalid_signature() {
+    assert!(runner::eval_to_string("(define (f x . y z) x)").is_err());
+    assert!(runner::eval_to_string("(define (1 x) x)").is_err());
+}
+
+#[test]
+fn internal_defines_mutually_recursive() {
+    assert_eq!(
+        eval(
+            "(define (parity n)
+               (define (even? n) (if (= n 0) #t (odd? (- n 1))))
+               (define (odd? n) (if (= n 0) #f (even? (- n 1))))
+               (if (even? n) 'even 'odd))
+             (list (parity 10) (parity 7))"
+        ),
+        "(even odd)"
+    );
+}
+
+#[test]
+fn internal_defines_are_local() {
+    assert_eq!(eval("(define x 1) (define (f) (define x 2) x) (list (f) x)"), "(2 1)");
+}
+
+#[test]
+fn internal_define_initializers_see_earlier_definitions() {
+    assert_eq!(eval("(define (f) (define a 1) (define b (+ a 1)) (list a b)) (f)"), "(1 2)");
+}
+
+#[test]
+fn internal_define_shadows_outer_binding_in_initializers() {
+    assert!(runner::eval_to_string("(define x 1) (define (f) (define y x) (define x 2) y) (f)")
+        .is_err());
+}
+
+#[test]
+fn internal_defines_in_named_let() {
+    assert_eq!(
+        eval("(let loop ((i 0)) (define next (+ i 1)) (if (= next 3) next (loop next)))"),
+        "3"
+    );
+}
+
+#[test]
+fn defined_for_bound_and_unbound_symbols() {
+    assert_eq!(
+        eval("(define x 1) (list (defined? 'x) (defined? 'car) (defined? 'nope))"),
+        "(#t #t #f)"
+    );
+    assert_eq!(eval("(let ((local 1)) (defined? 'local))"), "#t");
+    assert_eq!(eval("(let ((local 1)) 'ignored) (defined? 'local)"), "#f");
+    assert_eq!(eval("(defined? (string->symbol \"car\"))"), "#t");
+}
+
+#[test]
+fn defined_sees_later_definitions() {
+    assert_eq!(
+        eval("(define before (defined? 'later)) (define later 1) (list before (defined? 'later))"),
+        "(#f #t)"
+    );
+}
+
+#[test]
+fn defined_requires_a_symbol() {
+    assert!(runner::eval_to_string("(defined? 1)").is_err());
+    assert!(runner::eval_to_string("(defined? 'a 'b)").is_err());
+}
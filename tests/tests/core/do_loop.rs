@@ -0,0 +1,60 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn do_countdown_returns_value() {
+    assert_eq!(eval("(do ((i 10 (- i 1)) (acc 0 (+ acc i))) ((= i 0) acc))"), "55");
+}
+
+#[test]
+fn do_fills_list() {
+    assert_eq!(
+        eval("(do ((i 0 (+ i 1)) (items (list) (cons i items))) ((= i 5) items))"),
+        "(4 3 2 1 0)"
+    );
+}
+
+#[test]
+fn do_evaluates_body_for_side_effects() {
+    assert_eq!(
+        eval("(define total 0) (do ((i 1 (+ i 1))) ((> i 4) total) (set! total (* 2 i)))"),
+        "8"
+    );
+}
+
+#[test]
+fn do_updates_variables_simultaneously() {
+    assert_eq!(eval("(do ((a 1 b) (b 2 a) (n 0 (+ n 1))) ((= n 3) (list a b)))"), "(2 1)");
+}
+
+#[test]
+fn do_variable_without_step_keeps_value() {
+    assert_eq!(eval("(do ((i 0 (+ i 1)) (k 7)) ((= i 3) k))"), "7");
+}
+
+#[test]
+fn do_without_result_expressions() {
+    assert_eq!(eval("(do ((i 0 (+ i 1))) ((= i 3)))"), "()");
+}
+
+#[test]
+fn do_many_iterations() {
+    assert_eq!(eval("(do ((i 0 (+ i 1))) ((= i 100000) i))"), "100000");
+}
@@ -0,0 +1,110 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn dynamic_wind_runs_thunks_in_order() {
+    assert_eq!(
+        eval(
+            "(define trace '())
+             (define (note x) (set! trace (cons x trace)))
+             (define result
+               (dynamic-wind (lambda () (note 'before))
+                             (lambda () (note 'during) 'value)
+                             (lambda () (note 'after))))
+             (list result trace)"
+        ),
+        "(value (after during before))"
+    );
+}
+
+#[test]
+fn after_runs_when_continuation_escapes() {
+    assert_eq!(
+        eval(
+            "(define trace '())
+             (define (note x) (set! trace (cons x trace)))
+             (define result
+               (call/cc
+                 (lambda (k)
+                   (dynamic-wind (lambda () (note 'before))
+                                 (lambda () (k 'escaped) (note 'unreachable))
+                                 (lambda () (note 'after))))))
+             (list result trace)"
+        ),
+        "(escaped (after before))"
+    );
+}
+
+#[test]
+fn nested_winds_unwind_innermost_first() {
+    assert_eq!(
+        eval(
+            "(define trace '())
+             (define (note x) (set! trace (cons x trace)))
+             (call/cc
+               (lambda (k)
+                 (dynamic-wind
+                   (lambda () (note 'outer-before))
+                   (lambda ()
+                     (dynamic-wind (lambda () (note 'inner-before))
+                                   (lambda () (k 0))
+                                   (lambda () (note 'inner-after))))
+                   (lambda () (note 'outer-after)))))
+             trace"
+        ),
+        "(outer-after inner-after inner-before outer-before)"
+    );
+}
+
+#[test]
+fn after_runs_when_thunk_errors() {
+    assert_eq!(
+        eval(
+            "(define cleaned-up #f)
+             (call/cc
+               (lambda (k)
+                 (dynamic-wind (lambda () #f)
+                               (lambda () (car '()))
+                               (lambda () (set! cleaned-up #t) (k 'handled)))))
+             cleaned-up"
+        ),
+        "#t"
+    );
+    assert!(runner::eval_to_string(
+        "(dynamic-wind (lambda () #f) (lambda () (car '())) (lambda () #f))"
+    )
+    .is_err());
+}
+
+#[test]
+fn after_can_escape_to_another_continuation() {
+    assert_eq!(
+        eval(
+            "(call/cc
+               (lambda (outer)
+                 (call/cc
+                   (lambda (inner)
+                     (dynamic-wind (lambda () #f)
+                                   (lambda () (inner 'from-thunk))
+                                   (lambda () (outer 'from-after)))))))"
+        ),
+        "from-after"
+    );
+}
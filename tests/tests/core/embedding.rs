@@ -0,0 +1,137 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::{Exp, VowError, WriteFormat};
+
+#[test]
+fn eval_str_returns_each_value() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let values = vow_core::eval_str("(+ 1 2) \"a\" 'b", &mut env_tree, env_id).unwrap();
+    assert_eq!(values, vec![Exp::int(3), Exp::string("a"), Exp::symbol("b")]);
+}
+
+#[test]
+fn eval_str_keeps_definitions() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    vow_core::eval_str("(define (square x) (* x x))", &mut env_tree, env_id).unwrap();
+    let values = vow_core::eval_str("(square 12)", &mut env_tree, env_id).unwrap();
+    assert_eq!(values, vec![Exp::int(144)]);
+}
+
+#[test]
+fn eval_str_empty_input() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    assert_eq!(vow_core::eval_str("  ; nothing\n", &mut env_tree, env_id).unwrap(), vec![]);
+}
+
+#[test]
+fn eval_str_error() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let result = vow_core::eval_str("(define x 1) (undefined-procedure)", &mut env_tree, env_id);
+    assert!(matches!(result, Err(VowError::UndefinedSymbol(_))));
+    assert_eq!(vow_core::eval_str("x", &mut env_tree, env_id).unwrap(), vec![Exp::int(1)]);
+}
+
+#[test]
+fn separate_environments() {
+    let (mut first, first_id) = vow_core::standard_env();
+    let (mut second, second_id) = vow_core::standard_env();
+    vow_core::eval_str("(define x 1)", &mut first, first_id).unwrap();
+    assert!(vow_core::eval_str("x", &mut second, second_id).is_err());
+}
+
+#[test]
+fn redefining_builtins_does_not_affect_other_environments() {
+    let (mut first, first_id) = vow_core::standard_env();
+    vow_core::eval_str("(set! car cdr) (define (stream-car s) 'mine)", &mut first, first_id)
+        .unwrap();
+    let (mut second, second_id) = vow_core::standard_env();
+    let values = vow_core::eval_str(
+        "(car '(1 2)) (stream-car (stream-cons 'a '()))",
+        &mut second,
+        second_id,
+    )
+    .unwrap();
+    assert_eq!(values, vec![Exp::int(1), Exp::symbol("a")]);
+}
+
+#[test]
+fn parse_str_does_not_evaluate() {
+    let expressions = vow_core::parse_str("(car '(1 2)) 7").unwrap();
+    assert_eq!(expressions.len(), 2);
+    assert_eq!(WriteFormat(&expressions[0]).to_string(), "(car (quote (1 2)))");
+    assert_eq!(expressions[1], Exp::int(7));
+}
+
+#[test]
+fn parse_str_error() {
+    assert!(vow_core::parse_str("(1 2").is_err());
+}
+
+#[test]
+fn eval_parsed_expression() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let exp = vow_core::parse_str("(list 1 2)").unwrap().remove(0);
+    let value = vow_core::eval(exp, &mut env_tree, env_id).unwrap();
+    assert_eq!(value, Exp::List(vec![Exp::int(1), Exp::int(2)]));
+}
+
+#[test]
+fn constructed_values() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let exp = Exp::List(vec![Exp::symbol("vector-length"), Exp::vector(vec![Exp::bool(true)])]);
+    assert_eq!(vow_core::eval(exp, &mut env_tree, env_id).unwrap(), Exp::int(1));
+    assert_eq!(Exp::cons(Exp::num(1.5), Exp::int(2)).to_string(), "(1.5 . 2)");
+}
+
+#[test]
+fn display_format() {
+    let values = vow_core::parse_str(r#""a \"b\"" #\x ("c" #\space) sym 2.5"#).unwrap();
+    let printed: Vec<String> = values.iter().map(|v| format!("{v}")).collect();
+    assert_eq!(printed, vec!["a \"b\"", "x", "(c  )", "sym", "2.5"]);
+}
+
+#[test]
+fn write_format() {
+    let values = vow_core::parse_str(r#""a \"b\"" #\x ("c" #\space) sym 2.5"#).unwrap();
+    let printed: Vec<String> = values.iter().map(|v| format!("{}", WriteFormat(v))).collect();
+    assert_eq!(printed, vec![r#""a \"b\"""#, "#\\x", r#"("c" #\space)"#, "sym", "2.5"]);
+}
+
+#[test]
+fn debug_format() {
+    assert_eq!(format!("{:?}", Exp::List(vec![Exp::string("a"), Exp::int(1)])), "(\"a\" 1)");
+    assert_eq!(format!("{:?}", vec![Exp::bool(false)]), "[#f]");
+}
+
+#[test]
+fn exp_from_str() {
+    let exp: Exp = "(+ 1 2)".parse().unwrap();
+    assert_eq!(exp, Exp::List(vec![Exp::symbol("+"), Exp::int(1), Exp::int(2)]));
+    assert_eq!("  \"text\" ; comment".parse::<Exp>().unwrap(), Exp::string("text"));
+}
+
+#[test]
+fn exp_from_str_evaluates() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let exp = "(string-append \"a\" \"b\")".parse().unwrap();
+    assert_eq!(vow_core::eval(exp, &mut env_tree, env_id).unwrap(), Exp::string("ab"));
+}
+
+#[test]
+fn exp_from_str_errors() {
+    assert!(matches!("".parse::<Exp>(), Err(VowError::ParseError(..))));
+    assert!(matches!("(1 2".parse::<Exp>(), Err(VowError::ParseError(..))));
+    assert!(matches!("1 2".parse::<Exp>(), Err(VowError::ParseError(..))));
+}
@@ -0,0 +1,145 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::{runner, Exp};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn eval_in_interaction_environment() {
+    assert_eq!(eval("(define x 20) (eval '(+ x 1) (interaction-environment))"), "21");
+    assert_eq!(eval("(eval '(define y 5) (interaction-environment)) y"), "5");
+    assert_eq!(eval("(define x 3) (eval '(* x x))"), "9");
+}
+
+#[test]
+fn eval_constructed_code() {
+    assert_eq!(eval("(eval (list '+ 1 2 3) (scheme-report-environment 5))"), "6");
+    assert_eq!(
+        eval("(eval (read (open-input-string \"(map car '((a 1) (b 2)))\")) (interaction-environment))"),
+        "(a b)"
+    );
+}
+
+#[test]
+fn report_environment_is_separate() {
+    assert_eq!(
+        eval(
+            "(define x 1)
+             (define env (scheme-report-environment 7))
+             (eval '(define x 2) env)
+             (list x (eval 'x env) (eval '(car '(a b)) env))"
+        ),
+        "(1 2 a)"
+    );
+    assert!(runner::eval_to_string(
+        "(define only-here 1) (eval 'only-here (scheme-report-environment 5))"
+    )
+    .is_err());
+}
+
+#[test]
+fn environment_values() {
+    assert_eq!(eval("(environment? (interaction-environment))"), "#t");
+    assert_eq!(eval("(environment? 'x)"), "#f");
+    assert_eq!(eval("(interaction-environment)"), "#<environment>");
+    assert_eq!(eval("(eq? (interaction-environment) (interaction-environment))"), "#t");
+}
+
+#[test]
+fn invalid_environments_are_errors() {
+    assert!(runner::eval_to_string("(eval '1 'not-an-environment)").is_err());
+    assert!(runner::eval_to_string("(scheme-report-environment 6)").is_err());
+}
+
+#[test]
+fn the_environment_captures_local_scope() {
+    assert_eq!(eval("(define env (let ((x 1) (y 2)) (the-environment))) (eval '(+ x y) env)"), "3");
+    assert_eq!(eval("(eq? (the-environment) (interaction-environment))"), "#t");
+}
+
+#[test]
+fn env_ref_define_and_set() {
+    assert_eq!(
+        eval(
+            "(define (make-counter) (let ((count 0)) (the-environment)))
+             (define env (make-counter))
+             (env-set! env 'count 5)
+             (env-define! env 'step 2)
+             (list (env-ref env 'count) (env-ref env 'step) (env-ref env 'car) (defined? 'step))"
+        ),
+        "(5 2 <function> #f)"
+    );
+}
+
+#[test]
+fn env_set_requires_existing_binding() {
+    assert!(runner::eval_to_string("(env-set! (the-environment) 'unbound 1)").is_err());
+    assert!(runner::eval_to_string("(env-ref (the-environment) 'unbound)").is_err());
+}
+
+#[test]
+fn env_symbols_lists_visible_bindings() {
+    assert_eq!(
+        eval(
+            "(define env (let ((zzz-local 1)) (the-environment)))
+             (define symbols (env-symbols env))
+             (list (memq 'zzz-local symbols) (and (memq 'car symbols) #t))"
+        ),
+        "((zzz-local) #t)"
+    );
+}
+
+#[test]
+fn environment_from_another_tree_is_an_error() {
+    let (mut first, first_id) = vow_core::standard_env();
+    let (mut second, second_id) = vow_core::standard_env();
+    let source = "(let loop ((i 0)) (if (< i 10) (loop (+ i 1)) (the-environment)))";
+    let env = vow_core::eval_str(source, &mut first, first_id).unwrap().remove(0);
+    let quoted = Exp::List(vec![Exp::symbol("quote"), Exp::symbol("car")]);
+    let env_ref = Exp::List(vec![Exp::symbol("env-ref"), env, quoted]);
+    let error = vow_core::eval(env_ref, &mut second, second_id).unwrap_err();
+    assert_eq!(error.to_string(), "environment does not exist");
+}
+
+#[test]
+fn resolve_through_deeply_nested_environments() {
+    assert_eq!(
+        eval(
+            "(define outermost 'found)
+             (define env (the-environment))
+             (do ((i 0 (+ i 1))) ((= i 10000))
+               (set! env (eval '(let ((inner 0)) (the-environment)) env)))
+             (list (eval 'outermost env) (env-set! env 'outermost 'changed) outermost)"
+        ),
+        "(found () changed)"
+    );
+}
+
+#[test]
+fn resolve_and_find_are_iterative() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let mut innermost = env_id;
+    for _ in 0..100_000 {
+        innermost = vow_core::Env::insert_into(&mut env_tree, vec![], vec![], Some(innermost));
+    }
+    let env = &env_tree[innermost];
+    assert_eq!(
+        env.resolve(&env_tree, "car").map(|car| car.to_string()),
+        Some("<function>".to_string())
+    );
+    assert_eq!(env.find(&env_tree, "car", innermost), Some(env_id));
+    assert!(env.resolve(&env_tree, "unbound").is_none());
+}
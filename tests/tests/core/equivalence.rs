@@ -0,0 +1,106 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn eq_immediates() {
+    assert_eq!(eval("(eq? 'a 'a)"), "#t");
+    assert_eq!(eval("(eq? 'a 'b)"), "#f");
+    assert_eq!(eval("(eq? #t #t)"), "#t");
+    assert_eq!(eval("(eq? 100 100)"), "#t");
+    assert_eq!(eval("(eq? #\\a #\\a)"), "#t");
+    assert_eq!(eval("(eq? '() '())"), "#t");
+}
+
+#[test]
+fn eq_heap_objects_by_identity() {
+    assert_eq!(eval("(eq? \"abc\" (string-copy \"abc\"))"), "#f");
+    assert_eq!(eval("(define s \"abc\") (eq? s s)"), "#t");
+    assert_eq!(eval("(eq? (vector 1) (vector 1))"), "#f");
+    assert_eq!(eval("(define v (vector 1)) (eq? v v)"), "#t");
+    assert_eq!(eval("(eq? car car)"), "#t");
+    assert_eq!(eval("(eq? car cdr)"), "#f");
+    assert_eq!(eval("(define f (lambda () 1)) (eq? f f)"), "#t");
+    assert_eq!(eval("(eq? (lambda () 1) (lambda () 1))"), "#f");
+}
+
+#[test]
+fn eqv_numbers() {
+    assert_eq!(eval("(eqv? 2 2)"), "#t");
+    assert_eq!(eval("(eqv? 2.5 2.5)"), "#t");
+    assert_eq!(eval("(eqv? 2 2.0)"), "#f");
+    assert_eq!(eval("(eqv? 0.0 -0.0)"), "#f");
+    assert_eq!(eval("(eqv? +nan.0 +nan.0)"), "#t");
+    assert_eq!(eval("(= 0.0 -0.0)"), "#t");
+}
+
+#[test]
+fn eqv_strings_by_identity() {
+    assert_eq!(eval("(eqv? (make-string 1 #\\a) (make-string 1 #\\a))"), "#f");
+    assert_eq!(eval("(define s (make-string 1 #\\a)) (eqv? s s)"), "#t");
+}
+
+#[test]
+fn eqv_lists() {
+    assert_eq!(eval("(eqv? '() '())"), "#t");
+    assert_eq!(eval("(eqv? (list 1) (list 1))"), "#f");
+}
+
+#[test]
+fn lists_have_no_identity() {
+    // Unlike R7RS, where a pair is eq? to itself, lists are stored by value.
+    assert_eq!(eval("(define a (list 1 2)) (eq? a a)"), "#f");
+    assert_eq!(eval("(define a (list 1 2)) (equal? a a)"), "#t");
+}
+
+#[test]
+fn procedures_by_identity() {
+    assert_eq!(eval("(define (f) 1) (eqv? f f)"), "#t");
+    assert_eq!(eval("(define (f) 1) (memq f (list car f))"), "(<procedure>)");
+    assert_eq!(eval("(define (f) 1) (trace f) (eq? f f)"), "#t");
+    assert_eq!(
+        eval("(define-record-type point (make-point x) point? (x point-x)) (eq? point-x point-x)"),
+        "#t"
+    );
+    assert_eq!(eval("(define-syntax m (syntax-rules () ((_) 1))) (eq? m m)"), "#t");
+}
+
+#[test]
+fn equal_compares_structure() {
+    assert_eq!(eval("(equal? '(1 (2 #(3))) (list 1 (list 2 (vector 3))))"), "#t");
+    assert_eq!(eval("(equal? \"abc\" (string-copy \"abc\"))"), "#t");
+    assert_eq!(eval("(equal? '(1 . 2) (cons 1 2))"), "#t");
+    assert_eq!(eval("(equal? '(1 2) '(1 2.0))"), "#f");
+}
+
+#[test]
+fn equal_uses_eqv_for_numbers() {
+    assert_eq!(eval("(equal? '(0.0) '(-0.0))"), "#f");
+    assert_eq!(eval("(equal? (list +nan.0) (list +nan.0))"), "#t");
+}
+
+#[test]
+fn memq_and_assq_use_eq() {
+    assert_eq!(eval("(memq \"b\" '(\"a\" \"b\"))"), "#f");
+    assert_eq!(eval("(member \"b\" '(\"a\" \"b\"))"), "(\"b\")");
+    assert_eq!(
+        eval("(define k \"key\") (assq k (list (list \"key\" 1) (list k 2)))"),
+        "(\"key\" 2)"
+    );
+}
@@ -0,0 +1,200 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner::{self, VowError};
+use vow_core::{Located, Span};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn parse_error() {
+    let result = runner::eval_to_string("(+ 1 2");
+    assert!(matches!(result, Err(VowError::ParseError(..))));
+}
+
+#[test]
+fn unexpected_close_paren() {
+    let result = runner::eval_to_string(")");
+    assert!(matches!(result, Err(VowError::ParseError(..))));
+}
+
+#[test]
+fn unterminated_string() {
+    let result = runner::eval_to_string("\"abc");
+    assert!(matches!(result, Err(VowError::ParseError(..))));
+}
+
+#[test]
+fn type_error() {
+    let result = runner::eval_to_string("(+ 1 #t)");
+    assert!(matches!(result, Err(VowError::TypeError { expected: "number", .. })));
+}
+
+#[test]
+fn undefined_symbol() {
+    let result = runner::eval_to_string("(frobble 1)");
+    assert!(matches!(result, Err(VowError::UndefinedSymbol(s)) if s == "frobble"));
+}
+
+#[test]
+fn arity_mismatch() {
+    let result = runner::eval_to_string("(car '(1) '(2))");
+    assert!(matches!(
+        result,
+        Err(VowError::ArityMismatch { name, expected: 1, got: 2 }) if name == "car"
+    ));
+}
+
+#[test]
+fn procedure_arity_mismatch() {
+    let result = runner::eval_to_string("((lambda (x y) x) 1)");
+    assert!(matches!(result, Err(VowError::ArityMismatch { expected: 2, got: 1, .. })));
+}
+
+#[test]
+fn custom_error() {
+    let result = runner::eval_to_string("()");
+    assert!(matches!(result, Err(VowError::Custom(..))));
+}
+
+#[test]
+fn error_display() {
+    let error = runner::eval_to_string("frobble").unwrap_err();
+    assert_eq!(error.to_string(), "undefined symbol 'frobble'");
+}
+
+#[test]
+fn error_does_not_corrupt_later_evaluation() {
+    assert!(runner::eval_to_string("(car '())").is_err());
+    assert_eq!(runner::eval_to_string("(car '(1 2))").unwrap(), "1");
+}
+
+/// Returns the line and column of the parse error produced by reading `input`.
+fn parse_error_location(input: &str) -> (u32, u32) {
+    match vow_core::parse_str(input) {
+        Err(VowError::ParseError(_, Some(Span { line, col, source: None }))) => (line, col),
+        result => panic!("expected a located parse error, got {result:?}"),
+    }
+}
+
+#[test]
+fn parse_error_locations() {
+    assert_eq!(parse_error_location(")"), (1, 1));
+    assert_eq!(parse_error_location("(+ 1\n   2))"), (2, 6));
+    assert_eq!(parse_error_location("(list 1 2)\n  \"abc"), (2, 3));
+    assert_eq!(parse_error_location("'a #\\frobble"), (1, 4));
+    assert_eq!(parse_error_location("(1 . 2 3)"), (1, 8));
+    assert_eq!(parse_error_location("; comment\n(λ \"é\" #\\bad)"), (2, 8));
+}
+
+#[test]
+fn parse_error_at_end_of_input() {
+    assert_eq!(parse_error_location("(define x\n  (list 1"), (2, 9));
+}
+
+#[test]
+fn parse_error_display_includes_location() {
+    let error = runner::eval_to_string("(car\n  #\\frobble)").unwrap_err();
+    assert_eq!(error.to_string(), "parse error at line 2, col 3: Unknown character #\\frobble");
+}
+
+/// Evaluates each expression in `input` in a new standard environment,
+/// returning the error raised by the first one which fails.
+fn eval_located(input: &str) -> Located {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    for expression in vow_core::parse_spanned(input).unwrap() {
+        if let Err(located) = vow_core::eval_located(expression.value, &mut env_tree, env_id) {
+            return located;
+        }
+    }
+    panic!("expected an error from {input:?}");
+}
+
+/// Returns the line and column at which evaluating `input` raised an error,
+/// followed by those of the error's trace.
+fn error_locations(input: &str) -> Vec<(u32, u32)> {
+    let located = eval_located(input);
+    located.span.iter().chain(&located.trace).map(|span| (span.line, span.col)).collect()
+}
+
+#[test]
+fn eval_errors_have_no_location() {
+    let error = runner::eval_to_string("(cond ())").unwrap_err();
+    assert!(matches!(error, VowError::ParseError(_, None)));
+}
+
+#[test]
+fn eval_located_errors() {
+    let located = eval_located("(cond ())");
+    assert_eq!(located.span.map(|span| (span.line, span.col)), Some((1, 1)));
+    assert!(matches!(located.error, VowError::ParseError(_, None)));
+}
+
+#[test]
+fn exit_has_no_location() {
+    let located = eval_located("(display 1)\n(exit 3)");
+    assert!(matches!(located.error, VowError::Exit(3)));
+    assert!(located.span.is_none());
+}
+
+#[test]
+fn undefined_symbol_location() {
+    let input = "(define x 1)\n(display (+ x frobble))";
+    assert_eq!(error_locations(input), vec![(2, 15), (2, 10), (2, 1)]);
+    let result = runner::eval_to_string(input);
+    assert!(matches!(result, Err(VowError::UndefinedSymbol(symbol)) if symbol == "frobble"));
+}
+
+#[test]
+fn error_in_procedure_body_location() {
+    let input = "(define (f lst)\n  (let ((head (car lst)))\n    (+ head 1)))\n(f '())";
+    assert_eq!(error_locations(input), vec![(2, 15), (4, 1)]);
+}
+
+#[test]
+fn error_trace_lists_call_sites() {
+    let input = "(define (g x) (car x))\n(define (f x) (+ 1 (g x)))\n(f 5)";
+    assert_eq!(error_locations(input), vec![(1, 15), (2, 20), (3, 1)]);
+}
+
+#[test]
+fn recursive_call_sites_are_listed_once() {
+    let input = "(define (f n)\n  (if (= n 0) (car '()) (+ 1 (f (- n 1)))))\n(f 3)";
+    assert_eq!(error_locations(input), vec![(2, 15), (2, 30), (3, 1)]);
+}
+
+#[test]
+fn raised_errors_are_located() {
+    assert_eq!(error_locations("(define y 2)\n\n  (raise 'oops)"), vec![(3, 3)]);
+}
+
+#[test]
+fn quoted_data_has_no_location() {
+    assert_eq!(eval("(equal? '(a (b c)) (list 'a (list 'b 'c)))"), "#t");
+    assert_eq!(eval("(eq? (car '(a)) 'a)"), "#t");
+    assert_eq!(eval("(case 'b ((a) 1) ((b c) 2) (else 3))"), "2");
+    assert_eq!(eval("`(x ,(car '(y)) ,@(list 'z))"), "(x y z)");
+}
+
+#[test]
+fn parse_spanned_locations() {
+    let spans: Vec<(u32, u32)> = vow_core::parse_spanned("1 (a\n b)\n\n   'c \"d\"")
+        .unwrap()
+        .into_iter()
+        .map(|spanned| (spanned.span.line, spanned.span.col))
+        .collect();
+    assert_eq!(spans, vec![(1, 1), (1, 3), (4, 4), (4, 7)]);
+}
@@ -0,0 +1,219 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner::{self, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn guard_catches_raise() {
+    assert_eq!(eval("(guard (e (#t (list 'caught e))) (raise 'oops))"), "(caught oops)");
+}
+
+#[test]
+fn guard_returns_body_value() {
+    assert_eq!(eval("(guard (e (#t 'caught)) (+ 1 2))"), "3");
+}
+
+#[test]
+fn guard_clauses() {
+    assert_eq!(
+        eval(
+            "(guard (e ((symbol? e) 'symbol)
+                       ((and (pair? e) e) => car)
+                       (else 'other))
+               (raise (list 4)))"
+        ),
+        "4"
+    );
+    assert_eq!(eval("(guard (e ((symbol? e) 'symbol) (else 'other)) (raise 1))"), "other");
+}
+
+#[test]
+fn guard_reraises_when_no_clause_matches() {
+    assert_eq!(
+        eval(
+            "(guard (outer (#t (list 'outer outer)))
+               (guard (inner ((string? inner) 'inner))
+                 (raise 'oops)))"
+        ),
+        "(outer oops)"
+    );
+    let result = runner::eval_to_string("(guard (e ((string? e) 'inner)) (raise 'oops))");
+    assert!(matches!(result, Err(VowError::Raised(..))));
+}
+
+#[test]
+fn error_objects() {
+    assert_eq!(
+        eval(
+            "(guard (e ((error-object? e)
+                        (list (error-object-message e) (error-object-irritants e))))
+               (error \"bad thing\" 1 'two))"
+        ),
+        "(\"bad thing\" (1 two))"
+    );
+    assert_eq!(eval("(error-object? 'oops)"), "#f");
+}
+
+#[test]
+fn guard_catches_interpreter_errors() {
+    assert_eq!(
+        eval("(guard (e ((error-object? e) (error-object-message e))) (car '()))"),
+        "\"type error: expected pair, got ()\""
+    );
+}
+
+#[test]
+fn guard_rethrows_unmatched_interpreter_errors() {
+    let result = runner::eval_to_string("(guard (e ((symbol? e) 'caught)) (car '()))");
+    assert!(matches!(result, Err(VowError::TypeError { expected: "pair", .. })));
+}
+
+#[test]
+fn raise_continuable() {
+    assert_eq!(
+        eval(
+            "(with-exception-handler
+               (lambda (e) 42)
+               (lambda () (+ (raise-continuable 'oops) 1)))"
+        ),
+        "43"
+    );
+}
+
+#[test]
+fn handler_returning_from_raise_is_an_error() {
+    let result = runner::eval_to_string(
+        "(with-exception-handler
+           (lambda (e) 42)
+           (lambda () (+ (raise 'oops) 1)))",
+    );
+    assert!(matches!(result, Err(VowError::Raised(..))));
+}
+
+#[test]
+fn returning_handler_passes_exception_to_next_handler() {
+    assert_eq!(
+        eval(
+            "(define log '())
+             (guard (e (#t (cons e log)))
+               (with-exception-handler
+                 (lambda (e) (set! log (cons 'outer log)))
+                 (lambda ()
+                   (with-exception-handler
+                     (lambda (e) (set! log (cons 'inner log)))
+                     (lambda () (raise 'boom))))))"
+        ),
+        "(boom outer inner)"
+    );
+}
+
+#[test]
+fn handler_is_removed_when_thunk_returns() {
+    assert_eq!(
+        eval(
+            "(define log '())
+             (guard (e (#t (list e log)))
+               (with-exception-handler
+                 (lambda (e) (set! log (cons 'stale log)))
+                 (lambda () 'done))
+               (raise 'later))"
+        ),
+        "(later ())"
+    );
+}
+
+#[test]
+fn handler_is_removed_after_escape() {
+    assert_eq!(
+        eval(
+            "(define log '())
+             (call/cc
+               (lambda (k)
+                 (with-exception-handler
+                   (lambda (e) (set! log (cons 'stale log)))
+                   (lambda () (k 'escaped)))))
+             (guard (e (#t (list e log))) (raise 'after))"
+        ),
+        "(after ())"
+    );
+}
+
+#[test]
+fn handler_runs_before_unwinding() {
+    assert_eq!(
+        eval(
+            "(define log '())
+             (call/cc
+               (lambda (k)
+                 (with-exception-handler
+                   (lambda (e) (set! log (cons (list 'handled e) log)) (k 'escaped))
+                   (lambda () (raise 'boom)))))
+             log"
+        ),
+        "((handled boom))"
+    );
+}
+
+#[test]
+fn handler_is_called_with_outer_handlers() {
+    assert_eq!(
+        eval(
+            "(with-exception-handler
+               (lambda (e) (list 'outer e))
+               (lambda ()
+                 (with-exception-handler
+                   (lambda (e) (raise-continuable (list 'inner e)))
+                   (lambda () (raise-continuable 'oops)))))"
+        ),
+        "(outer (inner oops))"
+    );
+}
+
+#[test]
+fn guard_inside_handler_takes_priority() {
+    assert_eq!(
+        eval(
+            "(with-exception-handler
+               (lambda (e) 'handler)
+               (lambda () (guard (e (#t 'guard)) (raise-continuable 'oops))))"
+        ),
+        "guard"
+    );
+}
+
+#[test]
+fn handler_sees_interpreter_errors() {
+    assert_eq!(
+        eval(
+            "(define seen #f)
+             (guard (e (#t seen))
+               (with-exception-handler
+                 (lambda (e) (set! seen (error-object? e)))
+                 (lambda () (vector-ref (vector) 0))))"
+        ),
+        "#t"
+    );
+}
+
+#[test]
+fn uncaught_error_message() {
+    let error = runner::eval_to_string("(error \"something failed:\" 'x 42)").unwrap_err();
+    assert_eq!(error.to_string(), "something failed: x 42");
+    let error = runner::eval_to_string("(raise 'oops)").unwrap_err();
+    assert_eq!(error.to_string(), "uncaught exception: oops");
+}
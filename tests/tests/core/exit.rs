@@ -0,0 +1,72 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner::{self, VowError};
+
+fn exit_code(input: &str) -> Option<i32> {
+    match runner::eval_to_string(input) {
+        Err(VowError::Exit(code)) => Some(code),
+        _ => None,
+    }
+}
+
+#[test]
+fn exit_status() {
+    assert_eq!(exit_code("(exit)"), Some(0));
+    assert_eq!(exit_code("(exit 7)"), Some(7));
+    assert_eq!(exit_code("(exit #t)"), Some(0));
+    assert_eq!(exit_code("(exit #f)"), Some(1));
+}
+
+#[test]
+fn exit_stops_evaluation() {
+    assert_eq!(exit_code("(define x 1) (exit 2) (car '())"), Some(2));
+}
+
+#[test]
+fn exit_invalid_status() {
+    assert!(matches!(runner::eval_to_string("(exit \"no\")"), Err(VowError::TypeError { .. })));
+    assert!(exit_code("(exit 1 2)").is_none());
+}
+
+#[test]
+fn exit_cannot_be_caught() {
+    assert_eq!(exit_code("(guard (e (#t 'caught)) (exit 3))"), Some(3));
+    assert_eq!(
+        exit_code("(with-exception-handler (lambda (e) 'handled) (lambda () (exit 4)))"),
+        Some(4)
+    );
+}
+
+#[test]
+fn exit_runs_dynamic_wind_after() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let result = vow_core::eval_str(
+        "(define log '())
+         (dynamic-wind (lambda () #t)
+                       (lambda () (exit 5))
+                       (lambda () (set! log (cons 'after log))))",
+        &mut env_tree,
+        env_id,
+    );
+    assert!(matches!(result, Err(VowError::Exit(5))));
+    let log = vow_core::eval_str("log", &mut env_tree, env_id).unwrap();
+    assert_eq!(log[0].to_string(), "(after)");
+}
+
+#[test]
+fn command_line() {
+    vow_core::set_command_line(vec!["prog".to_string(), "arg".to_string()]);
+    assert_eq!(runner::eval_to_string("(command-line)").unwrap(), "(\"prog\" \"arg\")");
+}
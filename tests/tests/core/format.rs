@@ -0,0 +1,78 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn display_and_write_directives() {
+    assert_eq!(eval("(format #f \"~a and ~s\" \"x\" \"y\")"), "\"x and \\\"y\\\"\"");
+    assert_eq!(eval("(format #f \"list: ~a\" '(1 #\\a \"b\"))"), "\"list: (1 a b)\"");
+}
+
+#[test]
+fn newline_and_tilde_directives() {
+    assert_eq!(eval("(format #f \"a~%b~nc~~\")"), "\"a\\nb\\nc~\"");
+}
+
+#[test]
+fn integer_directives() {
+    assert_eq!(eval("(format #f \"~d ~b ~o ~x\" 255 5 8 255)"), "\"255 101 10 ff\"");
+    assert_eq!(eval("(format #f \"~x\" -26)"), "\"-1a\"");
+    assert!(runner::eval_to_string("(format #f \"~d\" 1.5)").is_err());
+}
+
+#[test]
+fn fixed_point_directive() {
+    assert_eq!(eval("(format #f \"~f ~f\" 2.5 3)"), "\"2.5 3.0\"");
+    assert_eq!(eval("(format #f \"~2f\" 3.14159)"), "\"3.14\"");
+    assert_eq!(eval("(format #f \"~f\" 1e21)"), "\"1000000000000000000000.0\"");
+}
+
+#[test]
+fn destinations() {
+    assert_eq!(eval("(with-output-to-string (lambda () (format #t \"~a!\" 'hi)))"), "\"hi!\"");
+    assert_eq!(
+        eval(
+            "(define out (open-output-string))
+             (format out \"~s\" 'x)
+             (get-output-string out)"
+        ),
+        "\"x\""
+    );
+    assert!(runner::eval_to_string("(format 1 \"x\")").is_err());
+}
+
+#[test]
+fn argument_count_mismatch_is_an_error() {
+    assert_eq!(
+        runner::eval_to_string("(format #f \"~a ~a\" 1)").unwrap_err().to_string(),
+        "format: missing argument for ~a"
+    );
+    assert_eq!(
+        runner::eval_to_string("(format #f \"~a\" 1 2)").unwrap_err().to_string(),
+        "format: 1 unused argument(s)"
+    );
+}
+
+#[test]
+fn unknown_directive_is_an_error() {
+    assert_eq!(
+        runner::eval_to_string("(format #f \"~q\" 1)").unwrap_err().to_string(),
+        "format: unknown directive ~q"
+    );
+    assert!(runner::eval_to_string("(format #f \"~\")").is_err());
+}
@@ -0,0 +1,234 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner::{self, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn set_and_ref() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 'a 1)
+             (hash-table-set! t 'b 2)
+             (list (hash-table-ref t 'a) (hash-table-ref t 'b) (hash-table-size t))"
+        ),
+        "(1 2 2)"
+    );
+}
+
+#[test]
+fn hash_table_predicate() {
+    assert_eq!(eval("(hash-table? (make-hash-table))"), "#t");
+    assert_eq!(eval("(hash-table? (make-equal-hash-table))"), "#t");
+    assert_eq!(eval("(hash-table? (vector))"), "#f");
+}
+
+#[test]
+fn ref_of_missing_key() {
+    let result = runner::eval_to_string("(hash-table-ref (make-hash-table) 'missing)");
+    assert!(matches!(result, Err(VowError::Custom(..))));
+    assert_eq!(eval("(hash-table-ref (make-hash-table) 'missing (lambda () 'failed))"), "failed");
+}
+
+#[test]
+fn ref_with_default() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 1 'one)
+             (list (hash-table-ref/default t 1 'none) (hash-table-ref/default t 2 'none))"
+        ),
+        "(one none)"
+    );
+}
+
+#[test]
+fn setting_existing_key_replaces_value() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 'a 1)
+             (hash-table-set! t 'a 2)
+             (list (hash-table-ref t 'a) (hash-table-size t))"
+        ),
+        "(2 1)"
+    );
+}
+
+#[test]
+fn distinct_keys_do_not_collide() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 1 'exact)
+             (hash-table-set! t 1.0 'inexact)
+             (hash-table-set! t \"1\" 'string)
+             (hash-table-set! t #\\1 'char)
+             (hash-table-set! t '|1| 'symbol)
+             (list (hash-table-size t) (hash-table-ref t 1) (hash-table-ref t 1.0) (hash-table-ref t #\\1))"
+        ),
+        "(5 exact inexact char)"
+    );
+}
+
+#[test]
+fn eqv_table_compares_strings_by_identity() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (define key \"key\")
+             (hash-table-set! t key 1)
+             (list (hash-table-ref/default t key 'none)
+                   (hash-table-ref/default t (string-copy key) 'none))"
+        ),
+        "(1 none)"
+    );
+}
+
+#[test]
+fn equal_table_compares_contents() {
+    assert_eq!(
+        eval(
+            "(define t (make-equal-hash-table))
+             (hash-table-set! t \"key\" 1)
+             (hash-table-set! t '(1 2) 2)
+             (hash-table-set! t (vector 'a) 3)
+             (list (hash-table-ref t (string-copy \"key\"))
+                   (hash-table-ref t (list 1 2))
+                   (hash-table-ref t (vector 'a)))"
+        ),
+        "(1 2 3)"
+    );
+}
+
+#[test]
+fn delete() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 'a 1)
+             (hash-table-set! t 'b 2)
+             (hash-table-delete! t 'a)
+             (hash-table-delete! t 'missing)
+             (list (hash-table-ref/default t 'a 'none) (hash-table-size t))"
+        ),
+        "(none 1)"
+    );
+}
+
+#[test]
+fn keys_values_and_alist() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 1 10)
+             (hash-table-set! t 2 20)
+             (define (sum l) (if (null? l) 0 (+ (car l) (sum (cdr l)))))
+             (list (sum (hash-table-keys t))
+                   (sum (hash-table-values t))
+                   (sum (map (lambda (entry) (* (car entry) (cdr entry)))
+                             (hash-table->alist t))))"
+        ),
+        "(3 30 50)"
+    );
+}
+
+#[test]
+fn walk() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 1 10)
+             (hash-table-set! t 2 20)
+             (define total 0)
+             (hash-table-walk t (lambda (k v) (set! total (+ total k v))))
+             total"
+        ),
+        "33"
+    );
+}
+
+#[test]
+fn update() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 'count 1)
+             (hash-table-update! t 'count (lambda (n) (+ n 1)))
+             (hash-table-update! t 'other (lambda (n) (+ n 1)) (lambda () 100))
+             (list (hash-table-ref t 'count) (hash-table-ref t 'other))"
+        ),
+        "(2 101)"
+    );
+    let result = runner::eval_to_string(
+        "(hash-table-update! (make-hash-table) 'missing (lambda (n) (+ n 1)))",
+    );
+    assert!(matches!(result, Err(VowError::Custom(..))));
+}
+
+#[test]
+fn copy_is_independent() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (hash-table-set! t 'a 1)
+             (define c (hash-table-copy t))
+             (hash-table-set! c 'a 2)
+             (hash-table-set! c 'b 3)
+             (list (hash-table-ref t 'a) (hash-table-size t) (hash-table-ref c 'a))"
+        ),
+        "(1 1 2)"
+    );
+}
+
+#[test]
+fn merge() {
+    assert_eq!(
+        eval(
+            "(define a (make-hash-table))
+             (define b (make-hash-table))
+             (hash-table-set! a 'x 1)
+             (hash-table-set! a 'y 2)
+             (hash-table-set! b 'y 20)
+             (hash-table-set! b 'z 30)
+             (hash-table-merge! a b)
+             (list (hash-table-ref a 'x) (hash-table-ref a 'y) (hash-table-ref a 'z)
+                   (hash-table-size a) (hash-table-size b))"
+        ),
+        "(1 20 30 3 2)"
+    );
+}
+
+#[test]
+fn tables_are_shared() {
+    assert_eq!(
+        eval(
+            "(define t (make-hash-table))
+             (define (add! table) (hash-table-set! table 'k 'v))
+             (add! t)
+             (hash-table-ref t 'k)"
+        ),
+        "v"
+    );
+}
+
+#[test]
+fn procedures_are_not_hashable() {
+    let result = runner::eval_to_string("(hash-table-set! (make-hash-table) (lambda () 1) 1)");
+    assert!(matches!(result, Err(VowError::TypeError { expected: "hashable key", .. })));
+}
@@ -0,0 +1,127 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn lambda_fixed_parameters() {
+    assert_eq!(eval("((lambda (x y) (list y x)) 1 2)"), "(2 1)");
+}
+
+#[test]
+fn lambda_variadic_parameters() {
+    assert_eq!(eval("((lambda (x y . rest) (list x y rest)) 1 2 3 4)"), "(1 2 (3 4))");
+    assert_eq!(eval("((lambda (x . rest) rest) 1)"), "()");
+}
+
+#[test]
+fn lambda_rest_parameter() {
+    assert_eq!(eval("((lambda args args) 1 2 3)"), "(1 2 3)");
+    assert_eq!(eval("((lambda args args))"), "()");
+}
+
+#[test]
+fn lambda_variadic_arity_mismatch() {
+    assert!(runner::eval_to_string("((lambda (x y . rest) x) 1)").is_err());
+}
+
+#[test]
+fn variadic_builtins() {
+    assert_eq!(eval("(list 1 2 3 4)"), "(1 2 3 4)");
+    assert_eq!(eval("(+ 1 2 3 4)"), "10");
+    assert_eq!(eval("(* 1 2 3 4)"), "24");
+    assert_eq!(eval("(- 10 1 2 3)"), "4");
+}
+
+#[test]
+fn lambda_multiple_body_expressions() {
+    assert_eq!(eval("((lambda (x) (define y 1) (+ x y)) 41)"), "42");
+}
+
+#[test]
+fn lambda_internal_defines_are_local() {
+    assert!(runner::eval_to_string("((lambda () (define y 1) y)) y").is_err());
+}
+
+#[test]
+fn lambda_internal_defines_mutual_recursion() {
+    assert_eq!(
+        eval(
+            "(define (parity n)
+               (define (even? n) (if (= n 0) 'even (odd? (- n 1))))
+               (define (odd? n) (if (= n 0) 'odd (even? (- n 1))))
+               (even? n))
+             (list (parity 10) (parity 7))"
+        ),
+        "(even odd)"
+    );
+}
+
+#[test]
+fn apply_spreads_final_list() {
+    assert_eq!(eval("(apply + '(1 2 3))"), "6");
+    assert_eq!(eval("(apply list 1 2 '(3 4))"), "(1 2 3 4)");
+    assert_eq!(eval("(apply list '())"), "()");
+}
+
+#[test]
+fn apply_collects_leading_arguments() {
+    assert_eq!(eval("(apply + 1 2 '(3 4))"), "10");
+    assert_eq!(eval("(apply max 1 9 '())"), "9");
+    assert_eq!(
+        eval(
+            "(define (tagged tag . values) (cons tag values))
+             (list (apply tagged 'a 'b '(c d)) (apply tagged '(x)))"
+        ),
+        "((a b c d) (x))"
+    );
+}
+
+#[test]
+fn apply_variadic_procedure() {
+    assert_eq!(eval("(apply (lambda (x . rest) (list x rest)) 1 '(2 3))"), "(1 (2 3))");
+}
+
+#[test]
+fn apply_requires_final_list() {
+    assert!(runner::eval_to_string("(apply + 1 2)").is_err());
+    assert!(runner::eval_to_string("(apply +)").is_err());
+}
+
+#[test]
+fn traced_procedure_returns_its_value() {
+    assert_eq!(
+        eval(
+            "(define (square x) (* x x))
+             (trace square)
+             (define traced (square 4))
+             (untrace square)
+             (list traced (square 5) (procedure? square))"
+        ),
+        "(16 25 #t)"
+    );
+    assert_eq!(eval("(trace car) (car '(1 2))"), "1");
+}
+
+#[test]
+fn trace_errors() {
+    assert!(runner::eval_to_string("(define x 1) (trace x)").is_err());
+    assert!(runner::eval_to_string("(trace undefined-procedure)").is_err());
+    assert!(runner::eval_to_string("(define (f) 1) (untrace f)").is_err());
+    assert!(runner::eval_to_string("(trace 'f)").is_err());
+}
@@ -0,0 +1,161 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn let_binds_values() {
+    assert_eq!(eval("(let ((x 1) (y 2)) (+ x y))"), "3");
+}
+
+#[test]
+fn let_evaluates_inits_in_outer_environment() {
+    assert_eq!(eval("(define x 10) (let ((x 1) (y x)) y)"), "10");
+}
+
+#[test]
+fn let_does_not_leak_bindings() {
+    assert!(runner::eval_to_string("(let ((x 1)) x) x").is_err());
+}
+
+#[test]
+fn let_multiple_body_expressions() {
+    assert_eq!(eval("(let ((x 1)) (set! x (+ x 1)) (* x 10))"), "20");
+}
+
+#[test]
+fn let_star_sequential_bindings() {
+    assert_eq!(eval("(let* ((x 1) (y (+ x 1)) (z (* y 10))) z)"), "20");
+}
+
+#[test]
+fn let_star_shadowing() {
+    assert_eq!(eval("(let* ((x 1) (x (+ x 1))) x)"), "2");
+}
+
+#[test]
+fn letrec_recursive_function() {
+    assert_eq!(
+        eval("(letrec ((fact (lambda (n) (if (= n 0) 1 (* n (fact (- n 1))))))) (fact 5))"),
+        "120"
+    );
+}
+
+#[test]
+fn letrec_mutual_recursion() {
+    assert_eq!(
+        eval(
+            "(letrec ((even? (lambda (n) (if (= n 0) #t (odd? (- n 1)))))
+                      (odd? (lambda (n) (if (= n 0) #f (even? (- n 1))))))
+               (list (even? 100) (odd? 7) (even? 7)))"
+        ),
+        "(#t #t #f)"
+    );
+}
+
+#[test]
+fn letrec_uninitialized_reference_is_an_error() {
+    assert!(runner::eval_to_string("(letrec ((a b) (b 1)) a)").is_err());
+}
+
+#[test]
+fn letrec_initializers_cannot_use_earlier_values() {
+    assert!(runner::eval_to_string("(letrec ((a 1) (b (+ a 1))) b)").is_err());
+}
+
+#[test]
+fn letrec_star_sequential_initializers() {
+    assert_eq!(eval("(letrec* ((a 1) (b (+ a 1))) (list a b))"), "(1 2)");
+}
+
+#[test]
+fn letrec_star_forward_reference_to_lambda() {
+    assert_eq!(
+        eval(
+            "(letrec* ((get-b (lambda () b))
+                       (b 2)
+                       (c (get-b)))
+               c)"
+        ),
+        "2"
+    );
+}
+
+#[test]
+fn letrec_star_mutual_recursion() {
+    assert_eq!(
+        eval(
+            "(letrec* ((even? (lambda (n) (if (= n 0) #t (odd? (- n 1)))))
+                       (odd? (lambda (n) (if (= n 0) #f (even? (- n 1))))))
+               (list (even? 10) (odd? 10)))"
+        ),
+        "(#t #f)"
+    );
+}
+
+#[test]
+fn letrec_star_uninitialized_reference_is_an_error() {
+    assert!(runner::eval_to_string("(letrec* ((a b) (b 1)) a)").is_err());
+}
+
+#[test]
+fn letrec_star_shadows_outer_bindings() {
+    assert!(runner::eval_to_string("(define b 5) (letrec* ((a b) (b 1)) a)").is_err());
+}
+
+#[test]
+fn let_body_is_tail_position() {
+    assert_eq!(
+        eval(
+            "(define loop (lambda (n) (let ((m (+ n 1))) (if (= m 100000) m (loop m)))))
+             (loop 0)"
+        ),
+        "100000"
+    );
+}
+
+#[test]
+fn named_let_loop() {
+    assert_eq!(
+        eval("(let loop ((i 0) (acc '())) (if (= i 3) acc (loop (+ i 1) (cons i acc))))"),
+        "(2 1 0)"
+    );
+}
+
+#[test]
+fn named_let_multiple_body_expressions() {
+    assert_eq!(
+        eval("(define n 0) (let loop ((i 0)) (set! n (+ n i)) (if (= i 4) n (loop (+ i 1))))"),
+        "10"
+    );
+}
+
+#[test]
+fn named_let_name_is_not_visible_outside() {
+    assert!(runner::eval_to_string("(let loop ((i 0)) i) (loop 1)").is_err());
+}
+
+#[test]
+fn named_let_inits_use_outer_environment() {
+    assert_eq!(eval("(define loop 5) (let loop ((i loop)) i)"), "5");
+}
+
+#[test]
+fn named_let_one_million_iterations() {
+    assert_eq!(eval("(let loop ((i 0)) (if (= i 1000000) i (loop (+ i 1))))"), "1000000");
+}
@@ -0,0 +1,276 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn member_returns_tail() {
+    assert_eq!(eval("(member 2 '(1 2 3))"), "(2 3)");
+    assert_eq!(eval("(member '(a) '(b (a) c))"), "((a) c)");
+    assert_eq!(eval("(cdr (member \"b\" '(\"a\" \"b\" \"c\")))"), "(\"c\")");
+}
+
+#[test]
+fn member_not_found() {
+    assert_eq!(eval("(member 4 '(1 2 3))"), "#f");
+    assert_eq!(eval("(member 1 '())"), "#f");
+}
+
+#[test]
+fn memq_and_memv() {
+    assert_eq!(eval("(memq 'c '(a b c d))"), "(c d)");
+    assert_eq!(eval("(memq 'e '(a b c d))"), "#f");
+    assert_eq!(eval("(memv 101 '(100 101 102))"), "(101 102)");
+    assert_eq!(eval("(memv 1.5 '(1 2))"), "#f");
+}
+
+#[test]
+fn memv_does_not_compare_structure() {
+    assert_eq!(eval("(memv '(a) '(b (a) c))"), "#f");
+}
+
+#[test]
+fn assoc_returns_pair() {
+    assert_eq!(eval("(assoc 2.0 '((1 one) (2.0 two)))"), "(2.0 two)");
+    assert_eq!(eval("(assoc '(a) '(((a)) ((b))))"), "((a))");
+    assert_eq!(eval("(cdr (assoc \"b\" '((\"a\" . 1) (\"b\" . 2))))"), "2");
+}
+
+#[test]
+fn assoc_not_found() {
+    assert_eq!(eval("(assoc 'd '((a 1) (b 2)))"), "#f");
+    assert_eq!(eval("(assoc 'a '())"), "#f");
+}
+
+#[test]
+fn assq_and_assv() {
+    assert_eq!(eval("(assq 'b '((a 1) (b 2)))"), "(b 2)");
+    assert_eq!(eval("(assq 'c '((a 1) (b 2)))"), "#f");
+    assert_eq!(eval("(assv 5 '((2 3) (5 7) (11 13)))"), "(5 7)");
+    assert_eq!(eval("(assv '(a) '(((a)) ((b))))"), "#f");
+}
+
+#[test]
+fn assoc_requires_pairs() {
+    assert!(runner::eval_to_string("(assoc 1 '(1 2))").is_err());
+}
+
+#[test]
+fn filter_list() {
+    assert_eq!(eval("(filter (lambda (x) (> x 2)) '(1 2 3 4))"), "(3 4)");
+    assert_eq!(eval("(filter number? '())"), "()");
+}
+
+#[test]
+fn fold_left_accumulates_from_left() {
+    assert_eq!(eval("(fold-left + 0 '(1 2 3))"), "6");
+    assert_eq!(eval("(fold-left cons '() '(1 2 3))"), "(((() . 1) . 2) . 3)");
+    assert_eq!(eval("(fold-left list 'init '())"), "init");
+}
+
+#[test]
+fn fold_right_accumulates_from_right() {
+    assert_eq!(eval("(fold-right cons '() '(1 2 3))"), "(1 2 3)");
+    assert_eq!(eval("(fold-right list 'init '(a b))"), "(a (b init))");
+}
+
+#[test]
+fn fold_multiple_lists() {
+    assert_eq!(eval("(fold-left (lambda (acc a b) (+ acc (* a b))) 0 '(1 2) '(3 4))"), "11");
+    assert_eq!(
+        eval("(fold-right (lambda (a b acc) (cons (+ a b) acc)) '() '(1 2) '(3 4))"),
+        "(4 6)"
+    );
+}
+
+#[test]
+fn reduce_list() {
+    assert_eq!(eval("(reduce + 0 '(1 2 3 4))"), "10");
+    assert_eq!(eval("(reduce + 0 '())"), "0");
+    assert_eq!(eval("(reduce list 'none '(a))"), "a");
+    assert_eq!(eval("(reduce list 'none '(1 2 3))"), "(3 (2 1))");
+}
+
+#[test]
+fn for_each_multiple_lists() {
+    assert_eq!(
+        eval(
+            "(define total 0)
+             (for-each (lambda (a b) (set! total (+ total (* a b)))) '(1 2 3) '(4 5 6))
+             total"
+        ),
+        "32"
+    );
+}
+
+#[test]
+fn map_multiple_lists() {
+    assert_eq!(eval("(map + '(1 2) '(3 4))"), "(4 6)");
+    assert_eq!(eval("(map list '(1 2 3) '(a b))"), "((1 a) (2 b))");
+    assert_eq!(eval("(map + '(1 2) '(3 4) '(5 6))"), "(9 12)");
+    assert_eq!(eval("(map + '(1 2 3) '(4 5 6))"), "(5 7 9)");
+    assert_eq!(eval("(map list '(a b c) '(1 2 3))"), "((a 1) (b 2) (c 3))");
+}
+
+#[test]
+fn map_and_for_each_stop_at_shortest_list() {
+    assert_eq!(eval("(map cons '(1) '(a b c) '())"), "()");
+    assert_eq!(
+        eval(
+            "(define pairs '())
+             (for-each (lambda (x y) (set! pairs (cons (list x y) pairs))) '(1 2 3) '(a b))
+             pairs"
+        ),
+        "((2 b) (1 a))"
+    );
+}
+
+#[test]
+fn map_and_for_each_require_a_list() {
+    assert!(runner::eval_to_string("(map car)").is_err());
+    assert!(runner::eval_to_string("(for-each car)").is_err());
+}
+
+#[test]
+fn sort_numbers() {
+    assert_eq!(eval("(sort '(3 1 2) <)"), "(1 2 3)");
+    assert_eq!(eval("(sort '(3 1 2 5 4) >)"), "(5 4 3 2 1)");
+}
+
+#[test]
+fn sort_strings() {
+    assert_eq!(
+        eval("(sort '(\"pear\" \"apple\" \"fig\") string<?)"),
+        "(\"apple\" \"fig\" \"pear\")"
+    );
+}
+
+#[test]
+fn sort_trivial_lists() {
+    assert_eq!(eval("(sort '() <)"), "()");
+    assert_eq!(eval("(sort '(1) <)"), "(1)");
+}
+
+#[test]
+fn sort_is_stable() {
+    assert_eq!(
+        eval("(sort '((b 1) (a 2) (c 1) (d 2)) (lambda (x y) (< (car (cdr x)) (car (cdr y)))))"),
+        "((b 1) (c 1) (a 2) (d 2))"
+    );
+}
+
+#[test]
+fn sort_with_closure() {
+    assert_eq!(
+        eval(
+            "(define (distance-from n) (lambda (a b) (< (abs (- a n)) (abs (- b n)))))
+             (sort '(1 5 9 4) (distance-from 6))"
+        ),
+        "(5 4 9 1)"
+    );
+}
+
+#[test]
+fn sort_vector() {
+    assert_eq!(eval("(sort #(3 1 2) <)"), "#(1 2 3)");
+    assert_eq!(eval("(define v (vector 3 1 2)) (sort! v <) v"), "#(1 2 3)");
+}
+
+#[test]
+fn list_sort() {
+    assert_eq!(eval("(list-sort < '(3 1 2))"), "(1 2 3)");
+}
+
+#[test]
+fn sort_comparator_must_return_boolean() {
+    assert!(runner::eval_to_string("(sort '(3 1 2) +)").is_err());
+}
+
+#[test]
+fn list_tail_and_list_ref() {
+    assert_eq!(eval("(list-tail '(a b c d) 2)"), "(c d)");
+    assert_eq!(eval("(list-tail '(a b) 2)"), "()");
+    assert_eq!(eval("(list-tail '(a b . c) 2)"), "c");
+    assert_eq!(eval("(list-ref '(a b c d) 2)"), "c");
+    assert_eq!(eval("(list-ref '(a b . c) 1)"), "b");
+}
+
+#[test]
+fn list_tail_and_list_ref_out_of_range() {
+    assert!(runner::eval_to_string("(list-tail '(a b) 3)").is_err());
+    assert!(runner::eval_to_string("(list-tail '(a b) -1)").is_err());
+    assert!(runner::eval_to_string("(list-ref '(a b) 2)").is_err());
+    assert!(runner::eval_to_string("(list-ref '(a b) -1)").is_err());
+    assert!(runner::eval_to_string("(list-ref '(a . b) 1)").is_err());
+    assert!(runner::eval_to_string("(list-ref '() 0)").is_err());
+}
+
+#[test]
+fn last_pair() {
+    assert_eq!(eval("(last-pair '(1 2 3))"), "(3)");
+    assert_eq!(eval("(last-pair '(1 2 . 3))"), "(2 . 3)");
+    assert!(runner::eval_to_string("(last-pair '())").is_err());
+}
+
+#[test]
+fn iota() {
+    assert_eq!(eval("(iota 5)"), "(0 1 2 3 4)");
+    assert_eq!(eval("(iota 3 1)"), "(1 2 3)");
+    assert_eq!(eval("(iota 4 10 -2)"), "(10 8 6 4)");
+    assert_eq!(eval("(iota 3 0 0.5)"), "(0.0 0.5 1.0)");
+    assert_eq!(eval("(iota 0)"), "()");
+    assert!(runner::eval_to_string("(iota -1)").is_err());
+    assert_eq!(
+        eval("(iota 3 9223372036854775807)"),
+        "(9223372036854775807 9.223372036854776e18 9.223372036854776e18)"
+    );
+    assert_eq!(
+        eval("(iota 3 0 9223372036854775807)"),
+        "(0 9223372036854775807 1.8446744073709552e19)"
+    );
+}
+
+#[test]
+fn list_copy() {
+    assert_eq!(eval("(list-copy '(1 (2) 3))"), "(1 (2) 3)");
+    assert_eq!(eval("(list-copy '())"), "()");
+    assert_eq!(eval("(list-copy '(1 . 2))"), "(1 . 2)");
+    assert_eq!(eval("(list-copy 5)"), "5");
+    assert_eq!(
+        eval(
+            "(define v (vector 1))
+             (define original (list v))
+             (define copy (list-copy original))
+             (vector-set! (car copy) 0 2)
+             (list original (eq? (car original) (car copy)))"
+        ),
+        "((#(2)) #t)"
+    );
+}
+
+#[test]
+fn list_head() {
+    assert_eq!(eval("(list-head '(1 2 3 4) 2)"), "(1 2)");
+    assert_eq!(eval("(list-head '(1 2) 0)"), "()");
+    assert_eq!(eval("(list-head '(1 2) 2)"), "(1 2)");
+    assert_eq!(eval("(list-head '(1 2 . 3) 2)"), "(1 2)");
+    assert_eq!(eval("(define l '(1 2 3)) (list-head l 1) l"), "(1 2 3)");
+    assert!(runner::eval_to_string("(list-head '(1 2) 3)").is_err());
+    assert!(runner::eval_to_string("(list-head '(1 . 2) 2)").is_err());
+    assert!(runner::eval_to_string("(list-head '(1 2) -1)").is_err());
+}
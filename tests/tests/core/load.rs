@@ -0,0 +1,72 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{env, fs, io};
+
+use vow_core::{runner, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+/// Writes `contents` to a temporary file unique to `name`, returning its path
+/// quoted as a string literal.
+fn write_temp_file(name: &str, contents: &str) -> String {
+    let path = env::temp_dir().join(format!("vow-load-{}-{name}.scm", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    format!("{:?}", path.to_str().unwrap())
+}
+
+#[test]
+fn load_returns_last_value() {
+    let path = write_temp_file("last_value", "(define x 1)\n(+ x 41)\n");
+    assert_eq!(eval(&format!("(load {path})")), "42");
+}
+
+#[test]
+fn load_defines_in_current_environment() {
+    let path = write_temp_file("defines", "(define (square x) (* x x))\n");
+    assert_eq!(eval(&format!("(load {path}) (square 5)")), "25");
+}
+
+#[test]
+fn load_missing_file_is_an_error() {
+    let result = runner::eval_to_string("(load \"/nonexistent/vow/file.scm\")");
+    assert!(matches!(
+        result,
+        Err(VowError::IoError(path, error))
+            if path == "/nonexistent/vow/file.scm" && error.kind() == io::ErrorKind::NotFound
+    ));
+}
+
+#[test]
+fn load_parse_error_is_an_error() {
+    let path = write_temp_file("parse_error", "(define x 1))\n");
+    assert!(runner::eval_to_string(&format!("(load {path})")).is_err());
+}
+
+#[test]
+fn load_runtime_error_is_an_error() {
+    let path = write_temp_file("runtime_error", "(car '())\n");
+    assert!(runner::eval_to_string(&format!("(load {path})")).is_err());
+}
+
+#[test]
+fn load_parse_error_reports_file_location() {
+    let path = write_temp_file("parse_error", "(define x 1)\n(display #\\frobble)\n");
+    let error = runner::eval_to_string(&format!("(load {path})")).unwrap_err();
+    let message = error.to_string();
+    assert!(message.starts_with("parse error at line 2, col 10 of "), "{message}");
+    assert!(message.ends_with("parse_error.scm: Unknown character #\\frobble"), "{message}");
+}
@@ -0,0 +1,267 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner::{self, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn simple_macro() {
+    assert_eq!(
+        eval(
+            "(define-syntax my-if
+               (syntax-rules ()
+                 ((_ c a b) (cond (c a) (else b)))))
+             (list (my-if #t 1 2) (my-if #f 1 2))"
+        ),
+        "(1 2)"
+    );
+}
+
+#[test]
+fn macro_does_not_evaluate_arguments() {
+    assert_eq!(
+        eval(
+            "(define-syntax my-unless
+               (syntax-rules ()
+                 ((_ test body ...) (if test #f (begin body ...)))))
+             (my-unless #t (car '()))"
+        ),
+        "#f"
+    );
+}
+
+#[test]
+fn ellipsis() {
+    assert_eq!(
+        eval(
+            "(define-syntax my-let
+               (syntax-rules ()
+                 ((_ ((name value) ...) body1 body2 ...)
+                  ((lambda (name ...) body1 body2 ...) value ...))))
+             (my-let ((a 1) (b 2)) (+ a b))"
+        ),
+        "3"
+    );
+}
+
+#[test]
+fn ellipsis_matches_zero_forms() {
+    assert_eq!(
+        eval(
+            "(define-syntax my-list
+               (syntax-rules ()
+                 ((_ x ...) (list x ...))))
+             (my-list)"
+        ),
+        "()"
+    );
+}
+
+#[test]
+fn nested_ellipsis() {
+    assert_eq!(
+        eval(
+            "(define-syntax flatten
+               (syntax-rules ()
+                 ((_ (x ...) ...) '(x ... ...))))
+             (flatten (1 2) () (3))"
+        ),
+        "(1 2 3)"
+    );
+}
+
+#[test]
+fn literals() {
+    assert_eq!(
+        eval(
+            "(define-syntax arrow
+               (syntax-rules (=>)
+                 ((_ a => b) (list 'to a b))
+                 ((_ a b) (list 'plain a b))))
+             (list (arrow 1 => 2) (arrow 1 2))"
+        ),
+        "((to 1 2) (plain 1 2))"
+    );
+}
+
+#[test]
+fn recursive_macro() {
+    assert_eq!(
+        eval(
+            "(define-syntax my-or
+               (syntax-rules ()
+                 ((_) #f)
+                 ((_ e) e)
+                 ((_ e r ...) (let ((t e)) (if t t (my-or r ...))))))
+             (list (my-or) (my-or #f 2) (my-or #f #f))"
+        ),
+        "(#f 2 #f)"
+    );
+}
+
+#[test]
+fn introduced_binding_does_not_capture() {
+    assert_eq!(
+        eval(
+            "(define-syntax swap!
+               (syntax-rules ()
+                 ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))
+             (define tmp 1)
+             (define other 2)
+             (swap! tmp other)
+             (list tmp other)"
+        ),
+        "(2 1)"
+    );
+    assert_eq!(
+        eval(
+            "(define-syntax my-or
+               (syntax-rules ()
+                 ((_ a b) (let ((t a)) (if t t b)))))
+             (define t 5)
+             (my-or #f t)"
+        ),
+        "5"
+    );
+}
+
+#[test]
+fn free_identifier_refers_to_definition_environment() {
+    assert_eq!(
+        eval(
+            "(define-syntax add-one
+               (syntax-rules ()
+                 ((_ x) (+ x 1))))
+             (let ((+ *)) (add-one 5))"
+        ),
+        "6"
+    );
+}
+
+#[test]
+fn quoted_template_symbols_are_not_renamed() {
+    assert_eq!(
+        eval(
+            "(define-syntax tag
+               (syntax-rules ()
+                 ((_ x) (list 'tagged `(value ,x)))))
+             (tag 3)"
+        ),
+        "(tagged (value 3))"
+    );
+}
+
+#[test]
+fn dotted_pattern() {
+    assert_eq!(
+        eval(
+            "(define-syntax rest-of
+               (syntax-rules ()
+                 ((_ a . rest) 'rest)))
+             (rest-of 1 2 3)"
+        ),
+        "(2 3)"
+    );
+}
+
+#[test]
+fn no_matching_rule() {
+    let result = runner::eval_to_string(
+        "(define-syntax two (syntax-rules () ((_ a b) a)))
+         (two 1)",
+    );
+    assert!(matches!(result, Err(VowError::Custom(..))));
+}
+
+#[test]
+fn let_syntax() {
+    assert_eq!(
+        eval(
+            "(define x 1)
+             (define y 2)
+             (let-syntax ((swap! (syntax-rules ()
+                                   ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp))))))
+               (swap! x y))
+             (list x y)"
+        ),
+        "(2 1)"
+    );
+}
+
+#[test]
+fn let_syntax_is_not_visible_outside_body() {
+    let result = runner::eval_to_string(
+        "(define x 1)
+         (define y 2)
+         (let-syntax ((swap! (syntax-rules ()
+                               ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp))))))
+           (swap! x y))
+         (swap! x y)",
+    );
+    assert!(matches!(result, Err(VowError::UndefinedSymbol(s)) if s == "swap!"));
+}
+
+#[test]
+fn let_syntax_shadows_outer_macro() {
+    assert_eq!(
+        eval(
+            "(define-syntax m (syntax-rules () ((_) 'outer)))
+             (list (let-syntax ((m (syntax-rules () ((_) 'inner)))) (m)) (m))"
+        ),
+        "(inner outer)"
+    );
+}
+
+#[test]
+fn let_syntax_transformers_use_outer_bindings() {
+    assert_eq!(
+        eval(
+            "(define-syntax m (syntax-rules () ((_) 'outer)))
+             (let-syntax ((m (syntax-rules () ((_) 'inner)))
+                          (n (syntax-rules () ((_) (m)))))
+               (n))"
+        ),
+        "outer"
+    );
+}
+
+#[test]
+fn letrec_syntax() {
+    assert_eq!(
+        eval(
+            "(letrec-syntax ((my-or (syntax-rules ()
+                                      ((_) #f)
+                                      ((_ e) e)
+                                      ((_ e r ...) (let ((t e)) (if t t (my-or r ...)))))))
+               (my-or #f #f 3))"
+        ),
+        "3"
+    );
+}
+
+#[test]
+fn letrec_syntax_transformers_use_each_other() {
+    assert_eq!(
+        eval(
+            "(define-syntax m (syntax-rules () ((_) 'outer)))
+             (letrec-syntax ((m (syntax-rules () ((_) 'inner)))
+                             (n (syntax-rules () ((_) (m)))))
+               (n))"
+        ),
+        "inner"
+    );
+}
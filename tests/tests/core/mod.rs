@@ -12,4 +12,49 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod and_or;
+pub mod arithmetic;
+pub mod booleans;
+pub mod bytevectors;
+pub mod case;
+pub mod chars;
+pub mod complex;
+pub mod cond;
+pub mod continuations;
+pub mod define;
+pub mod do_loop;
+pub mod dynamic_wind;
+pub mod embedding;
+pub mod environments;
+pub mod equivalence;
+pub mod errors;
+pub mod exceptions;
+pub mod exit;
+pub mod format;
+pub mod hash_tables;
 pub mod hello;
+pub mod lambda;
+pub mod let_forms;
+pub mod lists;
+pub mod load;
+pub mod macros;
+pub mod pairs;
+pub mod parameters;
+pub mod ports;
+pub mod pretty_print;
+pub mod promises;
+pub mod quasiquote;
+pub mod reader;
+pub mod records;
+pub mod recursion_limit;
+pub mod sandbox;
+pub mod stdlib;
+pub mod step;
+pub mod streams;
+pub mod strings;
+pub mod symbols;
+pub mod tail_calls;
+pub mod time;
+pub mod values;
+pub mod vectors;
+pub mod when_unless;
@@ -0,0 +1,91 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn cons_onto_list() {
+    assert_eq!(eval("(cons 1 '(2 3))"), "(1 2 3)");
+    assert_eq!(eval("(cons 1 '())"), "(1)");
+}
+
+#[test]
+fn cons_dotted_pair() {
+    assert_eq!(eval("(cons 1 2)"), "(1 . 2)");
+    assert_eq!(eval("(cons 1 (cons 2 3))"), "(1 2 . 3)");
+}
+
+#[test]
+fn car_and_cdr_of_pair() {
+    assert_eq!(eval("(car (cons 1 2))"), "1");
+    assert_eq!(eval("(cdr (cons 1 2))"), "2");
+    assert_eq!(eval("(cdr (cons 1 (cons 2 3)))"), "(2 . 3)");
+}
+
+#[test]
+fn car_of_empty_list_is_an_error() {
+    assert!(runner::eval_to_string("(car '())").is_err());
+    assert!(runner::eval_to_string("(cdr '())").is_err());
+}
+
+#[test]
+fn read_dotted_literals() {
+    assert_eq!(eval("'(1 . 2)"), "(1 . 2)");
+    assert_eq!(eval("'(1 2 . 3)"), "(1 2 . 3)");
+    assert_eq!(eval("'(1 . (2 3))"), "(1 2 3)");
+    assert_eq!(eval("(equal? '(1 . 2) (cons 1 2))"), "#t");
+}
+
+#[test]
+fn read_invalid_dotted_literals() {
+    assert!(runner::eval_to_string("'(1 . 2 3)").is_err());
+    assert!(runner::eval_to_string("'(1 .)").is_err());
+}
+
+#[test]
+fn pair_predicates() {
+    assert_eq!(
+        eval("(list (pair? (cons 1 2)) (pair? '(1)) (pair? '()) (pair? 1))"),
+        "(#t #t #f #f)"
+    );
+    assert_eq!(eval("(list (list? (cons 1 2)) (list? '(1)) (list? '()))"), "(#f #t #t)");
+    assert_eq!(eval("(list (null? (cons 1 2)) (null? '()) (null? 0))"), "(#f #t #f)");
+}
+
+#[test]
+fn append_improper_tail() {
+    assert_eq!(eval("(append '(1 2) 3)"), "(1 2 . 3)");
+    assert_eq!(eval("(append '(1) '(2) '(3 4))"), "(1 2 3 4)");
+    assert_eq!(eval("(append)"), "()");
+}
+
+#[test]
+fn sicp_pairs() {
+    assert_eq!(
+        eval(
+            "(define (make-rat n d) (cons n d))
+             (define (numer x) (car x))
+             (define (denom x) (cdr x))
+             (define (add-rat x y)
+               (make-rat (+ (* (numer x) (denom y)) (* (numer y) (denom x)))
+                         (* (denom x) (denom y))))
+             (add-rat (make-rat 1 2) (make-rat 1 3))"
+        ),
+        "(5 . 6)"
+    );
+}
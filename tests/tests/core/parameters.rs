@@ -0,0 +1,121 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+
+use vow_core::runner::{self, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn make_parameter() {
+    assert_eq!(eval("(define p (make-parameter 10)) (p)"), "10");
+    assert_eq!(eval("(procedure? (make-parameter 10))"), "#t");
+}
+
+#[test]
+fn parameter_converter() {
+    assert_eq!(
+        eval(
+            "(define p (make-parameter 10 (lambda (x) (* x 2))))
+             (list (p) (parameterize ((p 3)) (p)) (p))"
+        ),
+        "(20 6 20)"
+    );
+}
+
+#[test]
+fn parameterize_restores_value() {
+    assert_eq!(
+        eval(
+            "(define p (make-parameter 1))
+             (define (show) (p))
+             (list (parameterize ((p 2)) (show)) (show))"
+        ),
+        "(2 1)"
+    );
+}
+
+#[test]
+fn nested_parameterize() {
+    assert_eq!(
+        eval(
+            "(define p (make-parameter 'outer))
+             (parameterize ((p 'middle))
+               (list (p) (parameterize ((p 'inner)) (p)) (p)))"
+        ),
+        "(middle inner middle)"
+    );
+}
+
+#[test]
+fn parameterize_restores_value_after_error() {
+    assert_eq!(
+        eval(
+            "(define p (make-parameter 1))
+             (guard (e (#t 'caught)) (parameterize ((p 2)) (raise 'oops)))
+             (p)"
+        ),
+        "1"
+    );
+}
+
+#[test]
+fn parameterize_restores_value_after_escape() {
+    assert_eq!(
+        eval(
+            "(define p (make-parameter 1))
+             (call/cc (lambda (k) (parameterize ((p 2)) (k 'escaped))))
+             (p)"
+        ),
+        "1"
+    );
+}
+
+#[test]
+fn setting_a_parameter() {
+    assert_eq!(eval("(define p (make-parameter 1)) (p 5) (p)"), "5");
+}
+
+#[test]
+fn parameterize_requires_parameter() {
+    let result = runner::eval_to_string("(parameterize ((car 1)) 2)");
+    assert!(matches!(result, Err(VowError::TypeError { expected: "parameter", .. })));
+}
+
+#[test]
+fn parameterize_current_output_port() {
+    let path = env::temp_dir().join(format!("vow-parameters-{}", std::process::id()));
+    let path = format!("{:?}", path.to_str().unwrap());
+    assert_eq!(
+        eval(&format!(
+            "(define out (open-output-file {path}))
+             (parameterize ((current-output-port out))
+               (display \"redirected\")
+               (write 'symbol))
+             (close-output-port out)
+             (define in (open-input-file {path}))
+             (read in)"
+        )),
+        "redirectedsymbol"
+    );
+}
+
+#[test]
+fn current_output_port_requires_port() {
+    let result = runner::eval_to_string("(parameterize ((current-output-port 1)) 2)");
+    assert!(matches!(result, Err(VowError::TypeError { expected: "output port", .. })));
+}
@@ -0,0 +1,528 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+
+use vow_core::{runner, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+/// Returns a quoted path for a temporary file unique to `name`.
+fn temp_path(name: &str) -> String {
+    let path = env::temp_dir().join(format!("vow-ports-{}-{name}", std::process::id()));
+    format!("{:?}", path.to_str().unwrap())
+}
+
+#[test]
+fn write_and_read_file() {
+    let path = temp_path("write_and_read_file");
+    let program = format!(
+        "(define out (open-output-file {path}))
+         (write '(1 \"two\" #\\3) out)
+         (newline out)
+         (display \"hello\" out)
+         (close-output-port out)
+         (define in (open-input-file {path}))
+         (define first (read in))
+         (define second (read in))
+         (define third (read in))
+         (close-input-port in)
+         (list first second (eof-object? third))"
+    );
+    assert_eq!(eval(&program), "((1 \"two\" #\\3) hello #t)");
+}
+
+#[test]
+fn read_and_peek_chars() {
+    let path = temp_path("read_and_peek_chars");
+    let program = format!(
+        "(define out (open-output-file {path}))
+         (write-char #\\a out)
+         (write-char #\\b out)
+         (close-output-port out)
+         (define in (open-input-file {path}))
+         (define peeked (peek-char in))
+         (define first (read-char in))
+         (define second (read-char in))
+         (list peeked first second (eof-object? (read-char in)) (char-ready? in))"
+    );
+    assert_eq!(eval(&program), "(#\\a #\\a #\\b #t #t)");
+}
+
+#[test]
+fn port_predicates() {
+    assert_eq!(eval("(input-port? (current-input-port))"), "#t");
+    assert_eq!(eval("(output-port? (current-output-port))"), "#t");
+    assert_eq!(eval("(input-port? (current-output-port))"), "#f");
+    assert_eq!(eval("(eof-object? (eof-object))"), "#t");
+    assert_eq!(eval("(eof-object? 'eof)"), "#f");
+}
+
+#[test]
+fn closed_port_is_an_error() {
+    let path = temp_path("closed_port_is_an_error");
+    let program = format!(
+        "(define out (open-output-file {path}))
+         (close-output-port out)
+         (close-output-port out)
+         (display 1 out)"
+    );
+    assert!(runner::eval_to_string(&program).is_err());
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    let result = runner::eval_to_string("(open-input-file \"/nonexistent/vow/file.scm\")");
+    match result {
+        Err(error @ VowError::IoError(..)) => {
+            assert_eq!(
+                error.to_string(),
+                "/nonexistent/vow/file.scm: No such file or directory (os error 2)"
+            );
+        }
+        result => panic!("expected an I/O error, got {result:?}"),
+    }
+}
+
+#[test]
+fn missing_file_error_is_catchable() {
+    assert_eq!(
+        eval("(guard (e ((error-object? e) 'caught)) (open-input-file \"/nonexistent/vow/file.scm\"))"),
+        "caught"
+    );
+}
+
+#[test]
+fn file_exists() {
+    let path = temp_path("file_exists");
+    let program = format!(
+        "(define before (file-exists? {path}))
+         (call-with-output-file {path} (lambda (out) (display 1 out)))
+         (list before (file-exists? {path}))"
+    );
+    assert_eq!(eval(&program), "(#f #t)");
+}
+
+#[test]
+fn delete_file() {
+    let path = temp_path("delete_file");
+    let program = format!(
+        "(call-with-output-file {path} (lambda (out) (display 1 out)))
+         (delete-file {path})
+         (file-exists? {path})"
+    );
+    assert_eq!(eval(&program), "#f");
+    let result = runner::eval_to_string(&format!("(delete-file {path})"));
+    assert!(matches!(result, Err(VowError::IoError(..))));
+}
+
+#[test]
+fn rename_file() {
+    let from = temp_path("rename_file_from");
+    let to = temp_path("rename_file_to");
+    let program = format!(
+        "(call-with-output-file {from} (lambda (out) (display \"moved\" out)))
+         (rename-file {from} {to})
+         (list (file-exists? {from}) (call-with-input-file {to} read-line))"
+    );
+    assert_eq!(eval(&program), "(#f \"moved\")");
+    assert!(runner::eval_to_string(&format!("(rename-file {from} {to})")).is_err());
+}
+
+#[test]
+fn create_directory() {
+    let path = temp_path("create_directory");
+    let file = temp_path("create_directory/file");
+    let program = format!(
+        "(create-directory {path})
+         (call-with-output-file {file} (lambda (out) (display 1 out)))
+         (let ((exists (file-exists? {file}))) (delete-file {file}) exists)"
+    );
+    assert_eq!(eval(&program), "#t");
+    assert!(runner::eval_to_string(&format!("(create-directory {path})")).is_err());
+    std::fs::remove_dir(path.trim_matches('"')).unwrap();
+}
+
+#[test]
+fn string_output_port() {
+    assert_eq!(
+        eval(
+            "(define out (open-output-string))
+             (write 'a out)
+             (display \" and \" out)
+             (write \"b\" out)
+             (get-output-string out)"
+        ),
+        "\"a and \\\"b\\\"\""
+    );
+    assert_eq!(eval("(get-output-string (open-output-string))"), "\"\"");
+    assert!(runner::eval_to_string("(get-output-string (current-output-port))").is_err());
+}
+
+#[test]
+fn string_input_port() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"(1 2) x\"))
+             (define first (read in))
+             (define second (read in))
+             (list first second (eof-object? (read in)))"
+        ),
+        "((1 2) x #t)"
+    );
+    assert_eq!(eval("(read-char (open-input-string \"λx\"))"), "#\\λ");
+}
+
+#[test]
+fn read_line_strips_line_endings() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"one\\ntwo\\r\\n\\nlast\"))
+             (list (read-line in) (read-line in) (read-line in) (read-line in)
+                   (eof-object? (read-line in)))"
+        ),
+        "(\"one\" \"two\" \"\" \"last\" #t)"
+    );
+}
+
+#[test]
+fn read_line_from_file() {
+    let path = temp_path("read_line_from_file");
+    let program = format!(
+        "(call-with-output-file {path} (lambda (out) (display \"first line\\nsecond\\n\" out)))
+         (call-with-input-file {path} (lambda (in) (let* ((a (read-line in)) (b (read-line in)))
+                                                     (list a b (eof-object? (read-line in))))))"
+    );
+    assert_eq!(eval(&program), "(\"first line\" \"second\" #t)");
+}
+
+#[test]
+fn read_line_after_read_returns_rest_of_line() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"42 rest\\nnext\"))
+             (list (read in) (read-line in) (read-line in))"
+        ),
+        "(42 \" rest\" \"next\")"
+    );
+    assert_eq!(
+        eval("(define in (open-input-string \"ab\\ncd\")) (read-char in) (list (read-line in) (read in))"),
+        "(\"b\" cd)"
+    );
+}
+
+#[test]
+fn read_line_uses_current_input_port() {
+    assert_eq!(
+        eval(
+            "(parameterize ((current-input-port (open-input-string \"hi\\nthere\"))) (read-line))"
+        ),
+        "\"hi\""
+    );
+}
+
+#[test]
+fn read_string_reads_up_to_count() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"λbc\\ndef\"))
+             (list (read-string 2 in) (read-string 3 in) (read-string 10 in) (eof-object? (read-string 1 in)))"
+        ),
+        "(\"λb\" \"c\\nd\" \"ef\" #t)"
+    );
+    assert_eq!(eval("(read-string 0 (open-input-string \"\"))"), "\"\"");
+    assert!(runner::eval_to_string("(read-string -1 (open-input-string \"a\"))").is_err());
+}
+
+#[test]
+fn read_and_peek_u8() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-bytevector (bytevector 1 255 0)))
+             (list (peek-u8 in) (read-u8 in) (read-u8 in) (u8-ready? in) (read-u8 in)
+                   (eof-object? (peek-u8 in)) (eof-object? (read-u8 in)))"
+        ),
+        "(1 1 255 #t 0 #t #t)"
+    );
+}
+
+#[test]
+fn read_u8_after_text() {
+    assert_eq!(
+        eval("(define in (open-input-string \"a\\nb\")) (list (read-char in) (read-u8 in) (read-u8 in))"),
+        "(#\\a 10 98)"
+    );
+}
+
+#[test]
+fn read_bytevector_reads_up_to_count() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-bytevector (bytevector 1 2 3 4 5)))
+             (list (read-bytevector 2 in) (read-bytevector 10 in) (eof-object? (read-bytevector 1 in)))"
+        ),
+        "(#u8(1 2) #u8(3 4 5) #t)"
+    );
+    assert_eq!(eval("(read-bytevector 0 (open-input-bytevector (bytevector)))"), "#u8()");
+}
+
+#[test]
+fn write_u8_and_bytevector() {
+    assert_eq!(
+        eval(
+            "(define out (open-output-bytevector))
+             (write-u8 7 out)
+             (write-bytevector (bytevector 1 2 3 4) out)
+             (write-bytevector (bytevector 5 6 7 8) out 1 3)
+             (get-output-bytevector out)"
+        ),
+        "#u8(7 1 2 3 4 6 7)"
+    );
+    assert!(runner::eval_to_string("(write-u8 256 (open-output-bytevector))").is_err());
+}
+
+#[test]
+fn write_string_with_range() {
+    assert_eq!(
+        eval(
+            "(call-with-string-output-port
+               (lambda (out) (write-string \"hello\" out) (write-string \"λ world!\" out 1 7)))"
+        ),
+        "\"hello world\""
+    );
+    assert_eq!(eval("(with-output-to-string (lambda () (write-string \"x\")))"), "\"x\"");
+}
+
+#[test]
+fn binary_file_round_trip() {
+    let path = temp_path("binary_file_round_trip");
+    let program = format!(
+        "(define out (open-binary-output-file {path}))
+         (write-bytevector (bytevector 0 128 255) out)
+         (close-port out)
+         (define in (open-binary-input-file {path}))
+         (let ((bytes (read-bytevector 10 in))) (close-port in) bytes)"
+    );
+    assert_eq!(eval(&program), "#u8(0 128 255)");
+}
+
+#[test]
+fn with_output_to_file_and_with_input_from_file() {
+    let path = temp_path("with_output_to_file_and_with_input_from_file");
+    let program = format!(
+        "(with-output-to-file {path} (lambda () (write '(a \"b\")) (newline) (display \"line two\")))
+         (with-input-from-file {path} (lambda () (let* ((first (read)) (rest (read-line)) (second (read-line)))
+                                                  (list first rest second))))"
+    );
+    assert_eq!(eval(&program), "((a \"b\") \"\" \"line two\")");
+}
+
+#[test]
+fn with_output_to_file_closes_port_and_restores_output() {
+    let path = temp_path("with_output_to_file_closes_port_and_restores_output");
+    let program = format!(
+        "(define before (current-output-port))
+         (define inside #f)
+         (define result (with-output-to-file {path} (lambda () (set! inside (current-output-port)) 42)))
+         (list result (eq? before (current-output-port))
+               (guard (e (#t 'closed)) (display 1 inside)))"
+    );
+    assert_eq!(eval(&program), "(42 #t closed)");
+}
+
+#[test]
+fn with_input_from_file_closes_port_on_escape() {
+    let path = temp_path("with_input_from_file_closes_port_on_escape");
+    let program = format!(
+        "(with-output-to-file {path} (lambda () (display \"abc\")))
+         (define before (current-input-port))
+         (define inside #f)
+         (define result
+           (call/cc (lambda (k)
+             (with-input-from-file {path}
+               (lambda () (set! inside (current-input-port)) (k (read-char)))))))
+         (list result (eq? before (current-input-port))
+               (guard (e (#t 'closed)) (read-char inside)))"
+    );
+    assert_eq!(eval(&program), "(#\\a #t closed)");
+}
+
+#[test]
+fn with_input_from_missing_file_is_an_error() {
+    assert!(runner::eval_to_string(
+        "(with-input-from-file \"/nonexistent/vow/file.scm\" (lambda () 1))"
+    )
+    .is_err());
+}
+
+#[test]
+fn current_error_port() {
+    assert_eq!(eval("(output-port? (current-error-port))"), "#t");
+    assert_eq!(
+        eval(
+            "(define err (open-output-string))
+             (parameterize ((current-error-port err))
+               (display \"warning\" (current-error-port))
+               (newline (current-error-port)))
+             (get-output-string err)"
+        ),
+        "\"warning\\n\""
+    );
+}
+
+#[test]
+fn with_output_to_string_captures_current_output() {
+    assert_eq!(
+        eval("(with-output-to-string (lambda () (display \"total: \") (write (+ 1 2)) (newline)))"),
+        "\"total: 3\\n\""
+    );
+    assert_eq!(
+        eval("(with-output-to-string (lambda () (with-output-to-string (lambda () (display 1))) (display 2)))"),
+        "\"2\""
+    );
+}
+
+#[test]
+fn with_output_to_string_restores_port_after_error() {
+    assert_eq!(
+        eval(
+            "(define before (current-output-port))
+             (guard (e (#t (eq? before (current-output-port))))
+               (with-output-to-string (lambda () (raise 'oops))))"
+        ),
+        "#t"
+    );
+}
+
+#[test]
+fn call_with_string_output_port_returns_output() {
+    assert_eq!(
+        eval(
+            "(call-with-string-output-port (lambda (port) (display \"x = \" port) (write 1 port)))"
+        ),
+        "\"x = 1\""
+    );
+    assert_eq!(eval("(call-with-string-output-port (lambda (port) 'ignored))"), "\"\"");
+}
+
+#[test]
+fn call_with_string_output_port_leaves_current_output() {
+    assert_eq!(
+        eval(
+            "(with-output-to-string
+               (lambda ()
+                 (display (string-length (call-with-string-output-port (lambda (p) (display \"abc\" p)))))))"
+        ),
+        "\"3\""
+    );
+}
+
+#[test]
+fn call_with_output_file_flushes_and_closes() {
+    let path = temp_path("call_with_output_file_flushes_and_closes");
+    let program = format!(
+        "(define saved #f)
+         (call-with-output-file {path} (lambda (out) (set! saved out) (write '(a b) out)))
+         (list (guard (e (#t 'closed)) (display 1 saved))
+               (call-with-input-file {path} read))"
+    );
+    assert_eq!(eval(&program), "(closed (a b))");
+}
+
+#[test]
+fn call_with_port_returns_procedure_values() {
+    assert_eq!(
+        eval("(call-with-port (open-input-string \"1 2\") (lambda (in) (+ (read in) (read in))))"),
+        "3"
+    );
+    assert!(runner::eval_to_string("(call-with-port 1 (lambda (p) p))").is_err());
+}
+
+#[test]
+fn call_with_port_closes_on_error_and_escape() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"x\"))
+             (guard (e (#t #f)) (call-with-port in (lambda (p) (raise 'oops))))
+             (guard (e (#t 'closed)) (read-char in))"
+        ),
+        "closed"
+    );
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"x\"))
+             (call/cc (lambda (k) (call-with-port in (lambda (p) (k 1)))))
+             (guard (e (#t 'closed)) (read-char in))"
+        ),
+        "closed"
+    );
+}
+
+#[test]
+fn close_port_closes_either_kind() {
+    assert_eq!(
+        eval(
+            "(define in (open-input-string \"x\"))
+             (define out (open-output-string))
+             (close-port in)
+             (close-port out)
+             (close-port out)
+             (list (guard (e (#t 'closed)) (read-char in))
+                   (guard (e (#t 'closed)) (write-char #\\a out)))"
+        ),
+        "(closed closed)"
+    );
+}
+
+#[test]
+fn write_then_read_round_trips() {
+    assert_eq!(
+        eval(
+            "(equal? '(1 \"two\" #\\3)
+                     (read (open-input-string
+                             (with-output-to-string (lambda () (write '(1 \"two\" #\\3)))))))"
+        ),
+        "#t"
+    );
+    assert_eq!(
+        eval(
+            "(define value '(#(1.5 \"a\\nb\") (x . y) #\\space #t))
+             (equal? value (read (open-input-string
+                                   (with-output-to-string (lambda () (write value))))))"
+        ),
+        "#t"
+    );
+}
+
+#[test]
+fn display_writes_strings_and_chars_unquoted() {
+    assert_eq!(
+        eval("(with-output-to-string (lambda () (display '(\"a\" #\\b)) (newline) (write #\\b)))"),
+        "\"(a b)\\n#\\\\b\""
+    );
+}
+
+#[test]
+fn read_uses_current_input_port() {
+    assert_eq!(
+        eval(
+            "(parameterize ((current-input-port (open-input-string \"(a) b\")))
+               (let* ((first (read)) (second (read)))
+                 (list first second (eof-object? (read)))))"
+        ),
+        "((a) b #t)"
+    );
+}
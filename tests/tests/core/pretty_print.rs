@@ -0,0 +1,80 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::env;
+
+use vow_core::{pretty_print, runner, Exp};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+fn pretty(input: &str, width: usize) -> String {
+    pretty_print(&input.parse::<Exp>().unwrap(), width)
+}
+
+#[test]
+fn short_list_stays_on_one_line() {
+    assert_eq!(pretty("(1 \"two\" #\\3 (4 5))", 80), "(1 \"two\" #\\3 (4 5))");
+}
+
+#[test]
+fn atoms_print_as_written() {
+    assert_eq!(
+        pretty("\"a long string that does not fit\"", 10),
+        "\"a long string that does not fit\""
+    );
+    assert_eq!(pretty("()", 0), "()");
+}
+
+#[test]
+fn symbol_headed_form_indents_by_two() {
+    assert_eq!(pretty("(define (square x) (* x x))", 20), "(define\n  (square x)\n  (* x x))");
+}
+
+#[test]
+fn other_lists_align_under_first_element() {
+    assert_eq!(pretty("(1 2 3)", 5), "(1\n 2\n 3)");
+    assert_eq!(pretty("#(1 2 3)", 5), "#(1\n  2\n  3)");
+}
+
+#[test]
+fn deeply_nested_list_indents_each_level() {
+    assert_eq!(
+        pretty("(a (b (c (d 1 2) 3) 4) 5)", 14),
+        "(a\n  (b\n    (c\n      (d 1 2)\n      3)\n    4)\n  5)"
+    );
+}
+
+#[test]
+fn improper_list_puts_tail_on_its_own_line() {
+    assert_eq!(pretty("(1 2 . 3)", 6), "(1\n 2\n . 3)");
+}
+
+#[test]
+fn pp_output_reads_back_as_same_value() {
+    let path = env::temp_dir().join(format!("vow-pp-{}", std::process::id()));
+    let path = format!("{:?}", path.to_str().unwrap());
+    let program = format!(
+        "(define value '(define (f x) (let loop ((i 0) (acc '())) (if (= i x) acc
+           (loop (+ i 1) (cons (list i (* i i) \"some padding text\") acc))))))
+         (define out (open-output-file {path}))
+         (pp value out)
+         (close-output-port out)
+         (define in (open-input-file {path}))
+         (define result (read in))
+         (close-input-port in)
+         (equal? result value)"
+    );
+    assert_eq!(eval(&program), "#t");
+}
@@ -0,0 +1,106 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn delay_does_not_evaluate() {
+    assert_eq!(eval("(define p (delay (car '()))) (promise? p)"), "#t");
+}
+
+#[test]
+fn force_evaluates_once() {
+    assert_eq!(
+        eval(
+            "(define count 0)
+             (define p (delay (begin (set! count (+ count 1)) 'value)))
+             (list (force p) (force p) count)"
+        ),
+        "(value value 1)"
+    );
+}
+
+#[test]
+fn delay_captures_environment() {
+    assert_eq!(eval("(define p (let ((x 5)) (delay (* x x)))) (force p)"), "25");
+}
+
+#[test]
+fn infinite_stream_of_integers() {
+    assert_eq!(
+        eval(
+            "(define (integers-from n) (cons n (delay (integers-from (+ n 1)))))
+             (define (stream-nth stream n)
+               (if (= n 0) (car stream) (stream-nth (force (cdr stream)) (- n 1))))
+             (stream-nth (integers-from 0) 1000)"
+        ),
+        "1000"
+    );
+}
+
+#[test]
+fn delay_force_runs_in_constant_space() {
+    assert_eq!(
+        eval(
+            "(define (loop n)
+               (delay-force (if (= n 0) (delay 'done) (loop (- n 1)))))
+             (force (loop 100000))"
+        ),
+        "done"
+    );
+}
+
+#[test]
+fn delay_force_memoizes_chain() {
+    assert_eq!(
+        eval(
+            "(define count 0)
+             (define p (delay-force (begin (set! count (+ count 1)) (delay count))))
+             (list (force p) (force p) count)"
+        ),
+        "(1 1 1)"
+    );
+}
+
+#[test]
+fn make_promise_and_force_of_values() {
+    assert_eq!(eval("(force (make-promise 3))"), "3");
+    assert_eq!(eval("(force (make-promise (delay (+ 1 1))))"), "2");
+    assert_eq!(eval("(force 7)"), "7");
+    assert_eq!(eval("(promise? 7)"), "#f");
+}
+
+#[test]
+fn make_promise_returns_promises_unchanged() {
+    assert_eq!(eval("(define p (delay 1)) (eq? p (make-promise p))"), "#t");
+    assert_eq!(
+        eval("(map promise? (list (delay 1) (delay-force (delay 2)) (make-promise 3) 4))"),
+        "(#t #t #t #f)"
+    );
+}
+
+#[test]
+fn lazy_is_delay_force() {
+    assert_eq!(
+        eval(
+            "(define (countdown n) (lazy (if (= n 0) (delay 'done) (countdown (- n 1)))))
+             (force (countdown 1000000))"
+        ),
+        "done"
+    );
+}
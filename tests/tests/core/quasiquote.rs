@@ -0,0 +1,56 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn quasiquote_without_unquote() {
+    assert_eq!(eval("`(a b (c d))"), "(a b (c d))");
+    assert_eq!(eval("`a"), "a");
+}
+
+#[test]
+fn unquote() {
+    assert_eq!(eval("(define x 5) `(x is ,x)"), "(x is 5)");
+    assert_eq!(eval("`(1 ,(+ 1 1) (3 ,(* 2 2)))"), "(1 2 (3 4))");
+}
+
+#[test]
+fn unquote_splicing() {
+    assert_eq!(eval("(define xs (list 2 3)) `(1 ,@xs 4)"), "(1 2 3 4)");
+    assert_eq!(eval("`(1 ,@(list) 2)"), "(1 2)");
+}
+
+#[test]
+fn unquote_splicing_requires_list() {
+    assert!(runner::eval_to_string("`(1 ,@2)").is_err());
+}
+
+#[test]
+fn nested_quasiquote() {
+    assert_eq!(eval("(define x 1) `(a `(b ,(c ,x)))"), "(a (quasiquote (b (unquote (c 1)))))");
+    assert_eq!(eval("(define x 1) `(a `(b ,,x))"), "(a (quasiquote (b (unquote 1))))");
+}
+
+#[test]
+fn quasiquote_code_generation() {
+    assert_eq!(
+        eval("(define (make-adder n) `(lambda (x) (+ x ,n))) (make-adder 3)"),
+        "(lambda (x) (+ x 3))"
+    );
+}
@@ -0,0 +1,65 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn single_letter_symbols_are_not_numbers() {
+    assert_eq!(eval("(define i 1) (define inf 2) (+ i inf)"), "3");
+}
+
+#[test]
+fn quote_abbreviation() {
+    assert_eq!(eval("'(a b c)"), "(a b c)");
+}
+
+#[test]
+fn radix_prefixes() {
+    assert_eq!(eval("#b1010"), "10");
+    assert_eq!(eval("#o17"), "15");
+    assert_eq!(eval("#d10"), "10");
+    assert_eq!(eval("#xff"), "255");
+    assert_eq!(eval("#XFF"), "255");
+    assert_eq!(eval("#x-1a"), "-26");
+}
+
+#[test]
+fn exactness_prefixes() {
+    assert_eq!(eval("#i10"), "10.0");
+    assert_eq!(eval("#e10.0"), "10");
+    assert_eq!(eval("#x#i10"), "16.0");
+    assert_eq!(eval("#e#b101"), "5");
+    assert!(runner::eval_to_string("#e1.5").is_err());
+}
+
+#[test]
+fn invalid_radix_literals() {
+    assert!(runner::eval_to_string("#b102").is_err());
+    assert!(runner::eval_to_string("#x").is_err());
+    assert!(runner::eval_to_string("#x#x10").is_err());
+    assert!(runner::eval_to_string("#b1.0").is_err());
+}
+
+#[test]
+fn deeply_nested_input_is_an_error() {
+    let nested = format!("'{}x{}", "(".repeat(500), ")".repeat(500));
+    assert_eq!(vow_core::parse_str(&nested).unwrap().len(), 1);
+    for input in ["(".repeat(100_000), "#(".repeat(100_000), "'".repeat(100_000) + "x"] {
+        assert!(matches!(vow_core::parse_str(&input), Err(runner::VowError::ParseError(..))));
+    }
+}
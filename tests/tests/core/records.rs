@@ -0,0 +1,111 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner::{self, VowError};
+
+const POINT: &str = "(define-record-type point
+                       (make-point x y)
+                       point?
+                       (x point-x set-point-x!)
+                       (y point-y set-point-y!))";
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(&format!("{POINT} {input}")).unwrap()
+}
+
+#[test]
+fn constructor_and_accessors() {
+    assert_eq!(eval("(define p (make-point 1 2)) (list (point-x p) (point-y p))"), "(1 2)");
+}
+
+#[test]
+fn predicate() {
+    assert_eq!(eval("(point? (make-point 1 2))"), "#t");
+    assert_eq!(eval("(point? (vector 1 2))"), "#f");
+    assert_eq!(eval("(point? 'point)"), "#f");
+}
+
+#[test]
+fn modifiers() {
+    assert_eq!(
+        eval(
+            "(define p (make-point 1 2))
+             (define q p)
+             (set-point-x! p 10)
+             (list (point-x q) (point-y q))"
+        ),
+        "(10 2)"
+    );
+}
+
+#[test]
+fn distinct_types_with_same_fields() {
+    assert_eq!(
+        eval(
+            "(define-record-type vec2 (make-vec2 x y) vec2? (x vec2-x) (y vec2-y))
+             (list (point? (make-vec2 1 2)) (vec2? (make-vec2 1 2)))"
+        ),
+        "(#f #t)"
+    );
+}
+
+#[test]
+fn accessor_rejects_other_records() {
+    let result = runner::eval_to_string(&format!(
+        "{POINT}
+         (define-record-type vec2 (make-vec2 x y) vec2? (x vec2-x) (y vec2-y))
+         (point-x (make-vec2 1 2))"
+    ));
+    assert!(matches!(result, Err(VowError::Custom(..))));
+}
+
+#[test]
+fn constructor_arity() {
+    let result = runner::eval_to_string(&format!("{POINT} (make-point 1)"));
+    assert!(matches!(
+        result,
+        Err(VowError::ArityMismatch { name, expected: 2, got: 1 }) if name == "make-point"
+    ));
+}
+
+#[test]
+fn constructor_with_subset_of_fields() {
+    assert_eq!(
+        runner::eval_to_string(
+            "(define-record-type <node> (make-node value) node?
+               (value node-value)
+               (next node-next set-node-next!))
+             (define n (make-node 1))
+             (set-node-next! n (make-node 2))
+             (node-value (node-next n))"
+        )
+        .unwrap(),
+        "2"
+    );
+}
+
+#[test]
+fn records_are_procedures_and_print() {
+    assert_eq!(eval("(procedure? point-x)"), "#t");
+    assert_eq!(eval("(make-point 1 2)"), "#<point 1 2>");
+    assert_eq!(eval("point"), "#<record-type point>");
+}
+
+#[test]
+fn records_compare_by_identity() {
+    assert_eq!(
+        eval("(define p (make-point 1 2)) (list (equal? p p) (equal? p (make-point 1 2)))"),
+        "(#t #f)"
+    );
+}
@@ -0,0 +1,73 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::{runner, VowError};
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+const UNBOUNDED: &str = "(set-recursion-limit! 100) (define (f n) (+ 1 (f n)))";
+
+#[test]
+fn unbounded_recursion_exceeds_limit() {
+    let result = runner::eval_to_string(&format!("{UNBOUNDED} (f 0)"));
+    assert!(matches!(result, Err(VowError::RecursionDepthExceeded(100))));
+}
+
+#[test]
+fn nested_expression_exceeds_limit() {
+    let nested = format!("{}#t{}", "(if ".repeat(200), " 1 2)".repeat(200));
+    let result = runner::eval_to_string(&format!("(set-recursion-limit! 100) {nested}"));
+    assert!(matches!(result, Err(VowError::RecursionDepthExceeded(100))));
+    assert_eq!(eval(&format!("(set-recursion-limit! 1000) {nested}")), "1");
+}
+
+#[test]
+fn tail_calls_do_not_count() {
+    assert_eq!(
+        eval(
+            "(set-recursion-limit! 100)
+             (define (count-down n) (if (= n 0) 'done (count-down (- n 1))))
+             (count-down 10000)"
+        ),
+        "done"
+    );
+}
+
+#[test]
+fn recursion_limit_error_is_catchable() {
+    assert_eq!(
+        eval(&format!("{UNBOUNDED} (guard (e (#t (error-object-message e))) (f 0))")),
+        "\"maximum recursion depth of 100 exceeded\""
+    );
+}
+
+#[test]
+fn depth_is_restored_after_error() {
+    assert_eq!(
+        eval(&format!(
+            "{UNBOUNDED}
+             (define (g n) (if (= n 0) 0 (+ 1 (g (- n 1)))))
+             (guard (e (#t #f)) (f 0))
+             (g 90)"
+        )),
+        "90"
+    );
+}
+
+#[test]
+fn set_recursion_limit_rejects_non_positive() {
+    assert!(runner::eval_to_string("(set-recursion-limit! 0)").is_err());
+    assert!(runner::eval_to_string("(set-recursion-limit! -5)").is_err());
+}
@@ -0,0 +1,89 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::{sandboxed_eval, Exp, SandboxConfig, VowError};
+
+fn restricted() -> SandboxConfig {
+    SandboxConfig::default()
+}
+
+#[test]
+fn sandboxed_eval_returns_each_value() {
+    let values = sandboxed_eval("(define (square x) (* x x)) (square 12)", &restricted()).unwrap();
+    assert_eq!(values.last(), Some(&Exp::int(144)));
+}
+
+#[test]
+fn sandbox_includes_prelude() {
+    let values = sandboxed_eval("((compose car cdr) '(1 2))", &restricted()).unwrap();
+    assert_eq!(values, vec![Exp::int(2)]);
+}
+
+#[test]
+fn file_io_disallowed() {
+    for name in ["load", "open-input-file", "call-with-output-file"] {
+        let result = sandboxed_eval(&format!("({name} \"Cargo.toml\")"), &restricted());
+        assert!(matches!(result, Err(VowError::UndefinedSymbol(symbol)) if symbol == name));
+    }
+}
+
+#[test]
+fn file_io_allowed() {
+    let config = SandboxConfig { allow_file_io: true, ..SandboxConfig::default() };
+    let values = sandboxed_eval("(procedure? open-input-file)", &config).unwrap();
+    assert_eq!(values, vec![Exp::bool(true)]);
+}
+
+#[test]
+fn exit_disallowed() {
+    let result = sandboxed_eval("(exit 3)", &restricted());
+    assert!(matches!(result, Err(VowError::UndefinedSymbol(symbol)) if symbol == "exit"));
+    let config = SandboxConfig { allow_exit: true, ..SandboxConfig::default() };
+    assert!(matches!(sandboxed_eval("(exit 3)", &config), Err(VowError::Exit(3))));
+}
+
+#[test]
+fn report_environment_is_restricted() {
+    let result =
+        sandboxed_eval("(eval '(load \"x.scm\") (scheme-report-environment 5))", &restricted());
+    assert!(matches!(result, Err(VowError::UndefinedSymbol(..))));
+}
+
+#[test]
+fn restrictions_end_with_sandboxed_eval() {
+    sandboxed_eval("1", &restricted()).unwrap();
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let values = vow_core::eval_str("(procedure? load)", &mut env_tree, env_id).unwrap();
+    assert_eq!(values, vec![Exp::bool(true)]);
+}
+
+#[test]
+fn time_limit_stops_infinite_loop() {
+    let config = SandboxConfig { max_eval_ms: Some(50), ..SandboxConfig::default() };
+    let result = sandboxed_eval("(let loop () (loop))", &config);
+    assert!(matches!(result, Err(VowError::TimeLimitExceeded(50))));
+}
+
+#[test]
+fn time_limit_cannot_be_caught() {
+    let config = SandboxConfig { max_eval_ms: Some(50), ..SandboxConfig::default() };
+    let result =
+        sandboxed_eval("(let loop () (guard (e (#t #f)) (let spin () (spin))) (loop))", &config);
+    assert!(matches!(result, Err(VowError::TimeLimitExceeded(50))));
+}
+
+#[test]
+fn time_limit_allows_quick_evaluation() {
+    let config = SandboxConfig { max_eval_ms: Some(10_000), ..SandboxConfig::default() };
+    assert_eq!(sandboxed_eval("(+ 1 2)", &config).unwrap(), vec![Exp::int(3)]);
+}
@@ -0,0 +1,134 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn make_counter() {
+    assert_eq!(eval("(define c (make-counter)) (c) (c) (c)"), "3");
+    assert_eq!(eval("(define a (make-counter)) (define b (make-counter)) (a) (a) (b)"), "1");
+}
+
+#[test]
+fn make_accumulator() {
+    assert_eq!(eval("(define acc (make-accumulator 100)) (acc 10)"), "110");
+    assert_eq!(eval("(define acc (make-accumulator 100)) (acc 10) (acc 10)"), "120");
+}
+
+#[test]
+fn make_monitored() {
+    assert_eq!(eval("(define s (make-monitored sqrt)) (s 100)"), "10");
+    assert_eq!(eval("(define s (make-monitored +)) (s 1 2) (s 3) (s 'how-many-calls?)"), "2");
+    assert_eq!(
+        eval("(define s (make-monitored +)) (s 1) (s 'reset-count) (s 2) (s 'how-many-calls?)"),
+        "1"
+    );
+}
+
+#[test]
+fn memoize_calls_once_per_argument_list() {
+    assert_eq!(
+        eval(
+            "(define calls 0)
+             (define square (memoize (lambda (x) (set! calls (+ calls 1)) (* x x))))
+             (list (square 3) (square 3) (square 4) calls)"
+        ),
+        "(9 9 16 2)"
+    );
+}
+
+#[test]
+fn memoize_compares_arguments_with_equal() {
+    assert_eq!(
+        eval(
+            "(define calls 0)
+             (define f (memoize (lambda (l) (set! calls (+ calls 1)) (length l))))
+             (f (list 1 2)) (f (list 1 2))
+             calls"
+        ),
+        "1"
+    );
+}
+
+#[test]
+fn memoize_recursive() {
+    assert_eq!(
+        eval(
+            "(define fib (memoize (lambda (n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))))
+             (fib 60)"
+        ),
+        "1548008755920"
+    );
+}
+
+#[test]
+fn memoize_remembers_false() {
+    assert_eq!(
+        eval(
+            "(define calls 0)
+             (define f (memoize (lambda (x) (set! calls (+ calls 1)) #f)))
+             (f 1) (f 1)
+             calls"
+        ),
+        "1"
+    );
+}
+
+#[test]
+fn once() {
+    assert_eq!(
+        eval(
+            "(define calls 0)
+             (define init (once (lambda (x) (set! calls (+ calls 1)) x)))
+             (list (init 1) (init 2) calls)"
+        ),
+        "(1 1 1)"
+    );
+}
+
+#[test]
+fn compose() {
+    assert_eq!(eval("((compose car cdr) '(1 2 3))"), "2");
+    assert_eq!(eval("((compose (lambda (x) (* x 2)) +) 1 2 3)"), "12");
+    assert_eq!(eval("((compose) 5)"), "5");
+    assert_eq!(eval("((compose abs) -5)"), "5");
+}
+
+#[test]
+fn negate() {
+    assert_eq!(eval("((negate even?) 3)"), "#t");
+    assert_eq!(eval("(filter (negate odd?) '(1 2 3 4))"), "(2 4)");
+}
+
+#[test]
+fn flip() {
+    assert_eq!(eval("((flip -) 1 10)"), "9");
+    assert_eq!(eval("((flip list) 1 2 3 4)"), "(2 1 3 4)");
+}
+
+#[test]
+fn curry() {
+    assert_eq!(eval("(((curry -) 10) 3)"), "7");
+    assert_eq!(eval("(map ((curry *) 2) '(1 2 3))"), "(2 4 6)");
+}
+
+#[test]
+fn partial() {
+    assert_eq!(eval("((partial - 10) 3)"), "7");
+    assert_eq!(eval("((partial list 1 2) 3 4)"), "(1 2 3 4)");
+    assert_eq!(eval("((partial +))"), "0");
+}
@@ -0,0 +1,106 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+/// Evaluates `(step expr)` with debugger commands read from `commands`,
+/// returning everything the debugger printed followed by the result.
+fn step(expr: &str, commands: &str) -> String {
+    eval(&format!(
+        r#"(let* ((result #f)
+                  (output (with-output-to-string
+                            (lambda ()
+                              (parameterize ((current-input-port (open-input-string {commands:?})))
+                                (set! result (step {expr})))))))
+             (string-append output "=> " (number->string result)))"#
+    ))
+}
+
+#[test]
+fn step_continue() {
+    assert_eq!(step("(+ 1 2)", "c\n"), "\"[STEP] (+ 1 2)\\nstep> => 3\"");
+}
+
+#[test]
+fn step_continue_at_end_of_input() {
+    assert_eq!(step("(* 2 3)", ""), "\"[STEP] (* 2 3)\\nstep> => 6\"");
+}
+
+#[test]
+fn step_next_then_continue() {
+    assert_eq!(
+        step("(+ 1 (* 2 3))", "n\nn\nc\n"),
+        "\"[STEP] (+ 1 (* 2 3))\\nstep> [STEP] +\\nstep> [STEP] (* 2 3)\\nstep> => 7\""
+    );
+}
+
+#[test]
+fn step_shows_local_bindings() {
+    assert_eq!(
+        step("((lambda (x y) (+ x y)) 1 2)", "n\nn\nn\nn\nc\n"),
+        "\"[STEP] ((lambda (x y) (+ x y)) 1 2)\\nstep> [STEP] (lambda (x y) (+ x y))\\nstep> \
+         [STEP] (+ x y)\\n  x = 1\\n  y = 2\\nstep> [STEP] +\\n  x = 1\\n  y = 2\\nstep> [STEP] x\\n  x = 1\\n  y = 2\\nstep> => 3\""
+    );
+}
+
+#[test]
+fn step_print_evaluates_in_current_environment() {
+    assert_eq!(
+        step("((lambda (x) (* x x)) 4)", "n\nn\np (+ x 1)\nc\n"),
+        "\"[STEP] ((lambda (x) (* x x)) 4)\\nstep> [STEP] (lambda (x) (* x x))\\nstep> \
+         [STEP] (* x x)\\n  x = 4\\nstep> 5\\nstep> => 16\""
+    );
+}
+
+#[test]
+fn step_unknown_command() {
+    assert_eq!(
+        step("(+ 1 2)", "z\nc\n"),
+        "\"[STEP] (+ 1 2)\\nstep> Unknown command 'z': use n, c, p <expr> or q\\nstep> => 3\""
+    );
+}
+
+#[test]
+fn step_quit_aborts() {
+    let result = runner::eval_to_string(
+        r#"(parameterize ((current-input-port (open-input-string "q\n")))
+             (with-output-to-string (lambda () (step (+ 1 2)))))"#,
+    );
+    assert!(result.unwrap_err().to_string().contains("step: aborted"));
+}
+
+#[test]
+fn step_stops_stepping_after_the_form() {
+    assert_eq!(
+        eval(
+            r#"(define output
+                 (with-output-to-string
+                   (lambda ()
+                     (parameterize ((current-input-port (open-input-string "")))
+                       (step 1)
+                       (+ 1 2)))))
+               output"#
+        ),
+        "\"\""
+    );
+}
+
+#[test]
+fn user_procedure_named_step() {
+    assert_eq!(eval("(define (step x) (+ x 1)) (step 5)"), "6");
+    assert_eq!(eval("(let ((step (lambda (x) (* x 2)))) (step 5))"), "10");
+}
@@ -0,0 +1,114 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::thread;
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+const INTEGERS: &str = "(define (integers-from n) (stream-cons n (integers-from (+ n 1))))
+                        (define naturals (integers-from 0))";
+
+fn eval_with_integers(input: &str) -> String {
+    eval(&format!("{INTEGERS} {input}"))
+}
+
+#[test]
+fn stream_cons_delays_tail() {
+    assert_eq!(eval("(define s (stream-cons 1 (car '()))) (stream-car s)"), "1");
+    assert_eq!(eval("(promise? (stream-cdr (stream-cons 1 2)))"), "#t");
+}
+
+#[test]
+fn stream_accessors() {
+    assert_eq!(eval_with_integers("(stream-car (stream-cdr (stream-cdr naturals)))"), "2");
+    assert_eq!(eval_with_integers("(stream-ref naturals 50)"), "50");
+    assert_eq!(eval("(stream-null? the-empty-stream)"), "#t");
+    assert_eq!(eval("(stream-null? (stream-cdr (stream-cons 1 '())))"), "#t");
+    assert_eq!(eval("(stream-pair? (stream-cons 1 '()))"), "#t");
+}
+
+#[test]
+fn stream_take() {
+    assert_eq!(eval_with_integers("(stream-take naturals 5)"), "(0 1 2 3 4)");
+    assert_eq!(eval("(stream-take (stream-cons 1 '()) 5)"), "(1)");
+}
+
+#[test]
+fn stream_map_is_lazy() {
+    assert_eq!(
+        eval_with_integers(
+            "(define count 0)
+             (define squares (stream-map (lambda (x) (set! count (+ count 1)) (* x x)) naturals))
+             (define before count)
+             (list before (stream-take squares 4) count)"
+        ),
+        "(0 (0 1 4 9) 4)"
+    );
+}
+
+#[test]
+fn stream_filter() {
+    assert_eq!(
+        eval_with_integers("(stream-take (stream-filter (lambda (x) (> x 1000)) naturals) 2)"),
+        "(1001 1002)"
+    );
+}
+
+#[test]
+fn stream_fold_and_for_each() {
+    let finite = "(define (range a b) (if (= a b) '() (stream-cons a (range (+ a 1) b))))";
+    assert_eq!(eval(&format!("{finite} (stream-fold + 0 (range 0 10))")), "45");
+    assert_eq!(
+        eval(&format!(
+            "{finite} (define total 0) (stream-for-each (lambda (x) (set! total (+ total x))) (range 0 5)) total"
+        )),
+        "10"
+    );
+}
+
+#[test]
+fn stream_append() {
+    assert_eq!(
+        eval_with_integers(
+            "(stream-take (stream-append (stream-cons 'a (stream-cons 'b '())) '() naturals) 4)"
+        ),
+        "(a b 0 1)"
+    );
+}
+
+#[test]
+fn sieve_of_eratosthenes() {
+    // Each prime adds a stream-filter which forces the filter beneath it, so
+    // finding the 100th prime nests 100 deep and needs more than the default
+    // test thread stack in debug builds.
+    let sieve = thread::Builder::new().stack_size(64 * 1024 * 1024).spawn(|| {
+        eval(
+            "(define (integers-from n) (stream-cons n (integers-from (+ n 1))))
+             (define (divisible? x p) (integer? (/ x p)))
+             (define (sieve stream)
+               (stream-cons
+                 (stream-car stream)
+                 (sieve (stream-filter (lambda (x) (not (divisible? x (stream-car stream))))
+                                       (stream-cdr stream)))))
+             (define primes (sieve (integers-from 2)))
+             (define first-100 (stream-take primes 100))
+             (list (length first-100) (stream-ref primes 9) (stream-ref primes 99))",
+        )
+    });
+    assert_eq!(sieve.unwrap().join().unwrap(), "(100 29 541)");
+}
@@ -0,0 +1,248 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn string_literals() {
+    assert_eq!(eval(r#""hello""#), r#""hello""#);
+    assert_eq!(eval(r#""""#), r#""""#);
+    assert_eq!(eval(r#"#(1 2 "hello" #t)"#), r#"#(1 2 "hello" #t)"#);
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(eval(r#"(string-length "a\nb\tc")"#), "5");
+    assert_eq!(eval(r#""say \"hi\"""#), r#""say \"hi\"""#);
+    assert_eq!(eval(r#""back\\slash""#), r#""back\\slash""#);
+    assert_eq!(eval(r#"(string->list "\x41;\a")"#), "(#\\A #\\alarm)");
+    assert!(runner::eval_to_string(r#""\q""#).is_err());
+}
+
+#[test]
+fn string_length_and_ref() {
+    assert_eq!(eval(r#"(string-length "héllo")"#), "5");
+    assert_eq!(eval(r#"(string-ref "héllo" 1)"#), "#\\é");
+    assert!(runner::eval_to_string(r#"(string-ref "abc" 3)"#).is_err());
+}
+
+#[test]
+fn string_set_mutates_shared_string() {
+    assert_eq!(
+        eval(r#"(define s (make-string 3 #\a)) (define t s) (string-set! t 1 #\b) s"#),
+        r#""aba""#
+    );
+}
+
+#[test]
+fn string_append_and_substring() {
+    assert_eq!(eval(r#"(string-append "foo" "" "bar")"#), r#""foobar""#);
+    assert_eq!(eval(r#"(substring "hello" 1 3)"#), r#""el""#);
+    assert!(runner::eval_to_string(r#"(substring "hello" 3 1)"#).is_err());
+}
+
+#[test]
+fn string_copy_is_independent() {
+    assert_eq!(
+        eval(r#"(define s "abc") (define t (string-copy s)) (string-set! t 0 #\z) s"#),
+        r#""abc""#
+    );
+    assert_eq!(eval(r#"(string-copy "hello" 2)"#), r#""llo""#);
+}
+
+#[test]
+fn string_list_conversion() {
+    assert_eq!(eval(r#"(string->list "abc")"#), "(#\\a #\\b #\\c)");
+    assert_eq!(eval(r#"(list->string (list #\a #\b))"#), r#""ab""#);
+    assert!(runner::eval_to_string("(list->string '(1 2))").is_err());
+}
+
+#[test]
+fn string_number_conversion() {
+    assert_eq!(eval(r#"(string->number "42")"#), "42");
+    assert_eq!(eval(r##"(string->number "#xff")"##), "255");
+    assert_eq!(eval(r#"(string->number "abc")"#), "#f");
+    assert_eq!(eval("(number->string 1.5)"), r#""1.5""#);
+}
+
+#[test]
+fn number_to_string_radix() {
+    assert_eq!(eval("(number->string 10 2)"), r#""1010""#);
+    assert_eq!(eval("(number->string 64 8)"), r#""100""#);
+    assert_eq!(eval("(number->string 255 10)"), r#""255""#);
+    assert_eq!(eval("(number->string 255 16)"), r#""ff""#);
+    assert_eq!(eval("(number->string -255 16)"), r#""-ff""#);
+    assert_eq!(eval("(number->string 0 2)"), r#""0""#);
+}
+
+#[test]
+fn number_to_string_digits() {
+    assert_eq!(eval("(number->string 3.14159 10 2)"), r#""3.14""#);
+    assert_eq!(eval("(number->string 2.5 10 0)"), r#""2""#);
+    assert_eq!(eval("(number->string 1.0 10 3)"), r#""1.000""#);
+    assert_eq!(eval("(number->string 42 10 2)"), r#""42""#);
+    assert_eq!(eval("(number->string +inf.0 10 2)"), r#""+inf.0""#);
+    assert!(runner::eval_to_string("(number->string 1.5 10 -1)").is_err());
+    assert!(runner::eval_to_string("(number->string 1.5 2 2)").is_err());
+}
+
+#[test]
+fn string_to_number_radix() {
+    assert_eq!(eval(r#"(string->number "1010" 2)"#), "10");
+    assert_eq!(eval(r#"(string->number "100" 8)"#), "64");
+    assert_eq!(eval(r#"(string->number "255" 10)"#), "255");
+    assert_eq!(eval(r#"(string->number "FF" 16)"#), "255");
+    assert_eq!(eval(r#"(string->number "-ff" 16)"#), "-255");
+    assert_eq!(eval(r##"(string->number "#b11" 16)"##), "3");
+}
+
+#[test]
+fn string_to_number_invalid() {
+    assert_eq!(eval(r#"(string->number "12" 2)"#), "#f");
+    assert_eq!(eval(r#"(string->number "1.5" 16)"#), "#f");
+    assert_eq!(eval(r#"(string->number "")"#), "#f");
+    assert_eq!(eval(r#"(string->number "1.2.3")"#), "#f");
+}
+
+#[test]
+fn number_string_round_trip() {
+    assert_eq!(eval("(string->number (number->string 0.1))"), "0.1");
+    assert_eq!(eval("(= (/ 1.0 3) (string->number (number->string (/ 1.0 3))))"), "#t");
+    assert_eq!(eval("(string->number (number->string 1e21))"), "1e21");
+    assert_eq!(eval("(string->number (number->string (/ 1.0 0)))"), "+inf.0");
+    assert_eq!(eval("(string->number (number->string (/ -1.0 0)))"), "-inf.0");
+}
+
+#[test]
+fn number_radix_errors() {
+    assert!(runner::eval_to_string("(number->string 10 3)").is_err());
+    assert!(runner::eval_to_string("(number->string 1.5 2)").is_err());
+    assert!(runner::eval_to_string(r#"(string->number "10" 7)"#).is_err());
+}
+
+#[test]
+fn string_case_conversion() {
+    assert_eq!(eval(r#"(string-upcase "Hello")"#), r#""HELLO""#);
+    assert_eq!(eval(r#"(string-downcase "Hello")"#), r#""hello""#);
+}
+
+#[test]
+fn string_comparisons() {
+    assert_eq!(eval(r#"(string=? "a" "a" "a")"#), "#t");
+    assert_eq!(eval(r#"(string<? "apple" "banana")"#), "#t");
+    assert_eq!(eval(r#"(string>? "apple" "banana")"#), "#f");
+    assert_eq!(eval(r#"(string<=? "a" "a" "b")"#), "#t");
+    assert_eq!(eval(r#"(string>=? "b" "c")"#), "#f");
+}
+
+#[test]
+fn string_contains() {
+    assert_eq!(eval(r#"(string-contains "héllo world" "world")"#), "6");
+    assert_eq!(eval(r#"(string-contains "hello" "xyz")"#), "#f");
+}
+
+#[test]
+fn string_split() {
+    assert_eq!(eval(r#"(string-split "a,b,,c" #\,)"#), r#"("a" "b" "" "c")"#);
+    assert_eq!(eval(r#"(string-split "one -> two -> three" " -> ")"#), r#"("one" "two" "three")"#);
+    assert_eq!(eval(r#"(string-split "" #\,)"#), r#"("")"#);
+    assert!(runner::eval_to_string(r#"(string-split "abc" "")"#).is_err());
+}
+
+#[test]
+fn string_join() {
+    assert_eq!(eval(r#"(string-join '("a" "b" "c") ", ")"#), r#""a, b, c""#);
+    assert_eq!(eval(r#"(string-join '("a" "b") #\-)"#), r#""a-b""#);
+    assert_eq!(eval(r#"(string-join '("x" "y"))"#), r#""x y""#);
+    assert_eq!(eval(r#"(string-join '() ",")"#), r#""""#);
+    assert!(runner::eval_to_string(r#"(string-join '("a" 1) ",")"#).is_err());
+}
+
+#[test]
+fn string_prefix_and_suffix() {
+    assert_eq!(
+        eval(r#"(list (string-prefix? "he" "hello") (string-prefix? "lo" "hello"))"#),
+        "(#t #f)"
+    );
+    assert_eq!(
+        eval(r#"(list (string-suffix? "lo" "hello") (string-suffix? "he" "hello"))"#),
+        "(#t #f)"
+    );
+    assert_eq!(eval(r#"(string-prefix? "" "")"#), "#t");
+}
+
+#[test]
+fn string_trim() {
+    assert_eq!(
+        eval(
+            r#"(list (string-trim "  a b  ") (string-trim-right "  a b  ") (string-trim-both "\t a b\n"))"#
+        ),
+        r#"("a b  " "  a b" "a b")"#
+    );
+}
+
+#[test]
+fn string_map() {
+    assert_eq!(eval("(string-map char-upcase \"abc\")"), "\"ABC\"");
+    assert_eq!(
+        eval("(string-map (lambda (a b) (if (char<? a b) a b)) \"adcλ\" \"bbbbb\")"),
+        "\"abbb\""
+    );
+    assert!(runner::eval_to_string("(string-map (lambda (c) 1) \"a\")").is_err());
+    assert!(runner::eval_to_string("(string-map char-upcase)").is_err());
+}
+
+#[test]
+fn string_for_each() {
+    assert_eq!(
+        eval(
+            "(define chars '())
+             (string-for-each (lambda (a b) (set! chars (cons (list a b) chars))) \"ab\" \"xyz\")
+             chars"
+        ),
+        "((#\\b #\\y) (#\\a #\\x))"
+    );
+    assert!(runner::eval_to_string("(string-for-each display)").is_err());
+}
+
+#[test]
+fn string_foldcase() {
+    assert_eq!(eval("(string-foldcase \"Hello World\")"), "\"hello world\"");
+    assert_eq!(eval("(string-foldcase \"Straße\")"), "\"strasse\"");
+    assert_eq!(eval("(string-foldcase \"ΟΔΟΣ\")"), "\"οδοσ\"");
+}
+
+#[test]
+fn string_ci_comparisons() {
+    assert_eq!(eval("(string-ci=? \"Hello\" \"hELLO\" \"hello\")"), "#t");
+    assert_eq!(eval("(string-ci=? \"Hello\" \"world\")"), "#f");
+    assert_eq!(eval("(string-ci<? \"apple\" \"BANANA\")"), "#t");
+    assert_eq!(eval("(string<? \"apple\" \"BANANA\")"), "#f");
+    assert_eq!(eval("(string-ci>? \"b\" \"A\")"), "#t");
+    assert_eq!(eval("(string-ci<=? \"a\" \"A\" \"b\")"), "#t");
+    assert_eq!(eval("(string-ci>=? \"a\" \"B\")"), "#f");
+    assert!(runner::eval_to_string("(string-ci=? \"a\")").is_err());
+}
+
+#[test]
+fn string_ci_comparisons_unicode() {
+    assert_eq!(eval("(string-ci=? \"STRASSE\" \"straße\")"), "#t");
+    assert_eq!(eval("(string-ci=? \"ΟΔΟΣ\" \"οδος\" \"οδοσ\")"), "#t");
+    assert_eq!(eval("(string-ci=? \"ÄPFEL\" \"äpfel\")"), "#t");
+    assert_eq!(eval("(string-ci<? \"äpfel\" \"ÖL\")"), "#t");
+}
@@ -0,0 +1,56 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn symbol_to_string() {
+    assert_eq!(eval("(symbol->string 'hello)"), "\"hello\"");
+    assert!(runner::eval_to_string("(symbol->string \"hello\")").is_err());
+}
+
+#[test]
+fn string_to_symbol() {
+    assert_eq!(eval("(string->symbol \"hello\")"), "hello");
+    assert_eq!(eval("(eq? (string->symbol \"abc\") 'abc)"), "#t");
+    assert!(runner::eval_to_string("(string->symbol 'hello)").is_err());
+}
+
+#[test]
+fn symbol_string_round_trip() {
+    assert_eq!(eval("(string->symbol (symbol->string 'round-trip))"), "round-trip");
+    assert_eq!(eval("(symbol->string (string->symbol \"two words\"))"), "\"two words\"");
+}
+
+#[test]
+fn gensym_returns_fresh_symbols() {
+    assert_eq!(eval("(symbol? (gensym))"), "#t");
+    assert_eq!(eval("(equal? (gensym) (gensym))"), "#f");
+    assert_eq!(eval("(equal? (gensym 'tmp) (gensym 'tmp))"), "#f");
+}
+
+#[test]
+fn gensym_prefix() {
+    assert!(eval("(symbol->string (gensym 'tmp))").starts_with("\"#:tmp"));
+    assert!(eval("(symbol->string (gensym \"x\"))").starts_with("\"#:x"));
+}
+
+#[test]
+fn generated_symbols_cannot_be_read() {
+    assert!(runner::eval_to_string("'#:g1").is_err());
+}
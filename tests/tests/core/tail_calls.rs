@@ -0,0 +1,116 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn recursive_count_to_one_million() {
+    let result = runner::eval_to_string(
+        "(define count (lambda (n) (if (= n 1000000) n (count (+ n 1)))))
+         (count 0)",
+    )
+    .unwrap();
+    assert_eq!(result, "1000000");
+}
+
+#[test]
+fn tail_call_in_begin() {
+    let result = runner::eval_to_string(
+        "(define count (lambda (n) (begin n (if (= n 100000) n (begin (count (+ n 1)))))))
+         (count 0)",
+    )
+    .unwrap();
+    assert_eq!(result, "100000");
+}
+
+#[test]
+fn tail_call_through_apply() {
+    let result = runner::eval_to_string(
+        "(define count (lambda (n) (if (= n 1000000) n (apply count (list (+ n 1))))))
+         (count 0)",
+    )
+    .unwrap();
+    assert_eq!(result, "1000000");
+}
+
+/// Defines `(loop n)` with the given body, which must call `(loop (+ n 1))`
+/// in tail position until `n` is one million, and returns the result of
+/// `(loop 0)`.
+fn loop_to_one_million(body: &str) -> String {
+    eval(&format!("(define (loop n) {body}) (loop 0)"))
+}
+
+#[test]
+fn tail_call_in_and() {
+    assert_eq!(loop_to_one_million("(if (= n 1000000) n (and #t (loop (+ n 1))))"), "1000000");
+}
+
+#[test]
+fn tail_call_in_or() {
+    assert_eq!(loop_to_one_million("(if (= n 1000000) n (or #f (loop (+ n 1))))"), "1000000");
+}
+
+#[test]
+fn tail_call_in_when_and_unless() {
+    assert_eq!(loop_to_one_million("(if (= n 1000000) n (when #t n (loop (+ n 1))))"), "1000000");
+    assert_eq!(loop_to_one_million("(if (= n 1000000) n (unless #f n (loop (+ n 1))))"), "1000000");
+}
+
+#[test]
+fn tail_call_in_cond() {
+    assert_eq!(loop_to_one_million("(cond ((= n 1000000) n) (else (loop (+ n 1))))"), "1000000");
+}
+
+#[test]
+fn tail_call_in_cond_arrow_receiver() {
+    assert_eq!(loop_to_one_million("(cond ((= n 1000000) n) ((+ n 1) => loop))"), "1000000");
+}
+
+#[test]
+fn tail_call_in_case() {
+    assert_eq!(loop_to_one_million("(case n ((1000000) n) (else (loop (+ n 1))))"), "1000000");
+}
+
+#[test]
+fn tail_call_in_let_bodies() {
+    assert_eq!(loop_to_one_million("(let ((m (+ n 1))) (if (= n 1000000) n (loop m)))"), "1000000");
+    assert_eq!(
+        loop_to_one_million("(let* ((m (+ n 1))) (if (= n 1000000) n (loop m)))"),
+        "1000000"
+    );
+    assert_eq!(
+        loop_to_one_million("(letrec ((m (+ n 1))) (if (= n 1000000) n (loop m)))"),
+        "1000000"
+    );
+}
+
+#[test]
+fn tail_call_in_let_values() {
+    assert_eq!(
+        loop_to_one_million("(let-values (((m) (+ n 1))) (if (= n 1000000) n (loop m)))"),
+        "1000000"
+    );
+}
+
+#[test]
+fn tail_call_in_do_result() {
+    assert_eq!(
+        loop_to_one_million("(if (= n 1000000) n (do ((i 0 (+ i 1))) ((= i 1) (loop (+ n 1)))))"),
+        "1000000"
+    );
+}
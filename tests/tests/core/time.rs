@@ -0,0 +1,51 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn time_returns_value() {
+    assert_eq!(eval("(time (* 6 7))"), "42");
+}
+
+#[test]
+fn time_evaluates_in_current_environment() {
+    assert_eq!(eval("(let ((x 5)) (time (+ x 1)))"), "6");
+}
+
+#[test]
+fn time_arity() {
+    assert!(runner::eval_to_string("(time)").is_err());
+}
+
+#[test]
+fn runtime_is_non_decreasing() {
+    assert_eq!(
+        eval("(let* ((a (runtime)) (b (runtime))) (and (exact-integer? a) (>= a 0) (>= b a)))"),
+        "#t"
+    );
+}
+
+#[test]
+fn cpu_time_is_integer() {
+    assert_eq!(eval("(let ((t (cpu-time))) (and (exact-integer? t) (> t 0)))"), "#t");
+}
+
+#[test]
+fn user_procedure_named_time() {
+    assert_eq!(eval("(define (time) 42) (time)"), "42");
+}
@@ -0,0 +1,126 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn call_with_values_spreads_values() {
+    assert_eq!(eval("(call-with-values (lambda () (values 1 2)) +)"), "3");
+    assert_eq!(eval("(call-with-values (lambda () (values 1 2 3)) list)"), "(1 2 3)");
+}
+
+#[test]
+fn call_with_values_single_and_zero_values() {
+    assert_eq!(eval("(call-with-values (lambda () 5) list)"), "(5)");
+    assert_eq!(eval("(call-with-values (lambda () (values 5)) list)"), "(5)");
+    assert_eq!(eval("(call-with-values (lambda () (values)) list)"), "()");
+}
+
+#[test]
+fn call_with_values_user_consumer() {
+    assert_eq!(eval("(call-with-values (lambda () (values 10 3)) (lambda (a b) (- a b)))"), "7");
+    assert!(runner::eval_to_string("(call-with-values (lambda () (values 1 2)) (lambda (a) a))")
+        .is_err());
+}
+
+#[test]
+fn single_value_is_not_wrapped() {
+    assert_eq!(eval("(+ (values 1) 2)"), "3");
+}
+
+#[test]
+fn define_values() {
+    assert_eq!(eval("(define-values (a b c) (values 1 2 3)) (list c b a)"), "(3 2 1)");
+    assert_eq!(eval("(define-values (x) 7) x"), "7");
+    assert_eq!(eval("(define-values () (values)) 'ok"), "ok");
+}
+
+#[test]
+fn define_values_rest_formals() {
+    assert_eq!(eval("(define-values (a . rest) (values 1 2 3)) (list a rest)"), "(1 (2 3))");
+    assert_eq!(eval("(define-values all (values 1 2)) all"), "(1 2)");
+}
+
+#[test]
+fn define_values_count_mismatch_is_an_error() {
+    assert!(runner::eval_to_string("(define-values (a b) (values 1 2 3))").is_err());
+    assert!(runner::eval_to_string("(define-values (a b) 1)").is_err());
+}
+
+#[test]
+fn continuation_with_multiple_values() {
+    assert_eq!(eval("(call-with-values (lambda () (call/cc (lambda (k) (k 1 2)))) list)"), "(1 2)");
+}
+
+#[test]
+fn let_values() {
+    assert_eq!(eval("(let-values (((q r) (floor/ 17 5))) (list q r))"), "(3 2)");
+    assert_eq!(
+        eval("(let-values (((a b) (values 1 2)) ((c) (values 3))) (list a b c))"),
+        "(1 2 3)"
+    );
+    assert_eq!(eval("(let-values () 'empty)"), "empty");
+}
+
+#[test]
+fn let_values_rest_formals() {
+    assert_eq!(eval("(let-values (((a . rest) (values 1 2 3))) (list a rest))"), "(1 (2 3))");
+    assert_eq!(eval("(let-values ((all (values 1 2))) all)"), "(1 2)");
+}
+
+#[test]
+fn let_values_binds_in_parallel() {
+    assert_eq!(
+        eval("(define a 'outer) (let-values (((a) (values 1)) ((b) (values a))) (list a b))"),
+        "(1 outer)"
+    );
+}
+
+#[test]
+fn let_star_values_binds_sequentially() {
+    assert_eq!(
+        eval("(let*-values (((a b) (values 1 2)) ((c) (values (+ a b)))) (list a b c))"),
+        "(1 2 3)"
+    );
+}
+
+#[test]
+fn let_values_count_mismatch_is_an_error() {
+    assert!(runner::eval_to_string("(let-values (((a b) (values 1 2 3))) a)").is_err());
+}
+
+#[test]
+fn receive() {
+    assert_eq!(eval("(receive (a b . rest) (values 1 2 3 4) (list a b rest))"), "(1 2 (3 4))");
+    assert_eq!(eval("(receive all (values 1 2) all)"), "(1 2)");
+    assert_eq!(eval("(receive (q r) (truncate/ 17 5) (+ q r))"), "5");
+}
+
+#[test]
+fn receive_with_empty_formals() {
+    assert_eq!(eval("(receive () (values) 'done)"), "done");
+    assert!(runner::eval_to_string("(receive () (values 1) 'done)").is_err());
+}
+
+#[test]
+fn let_values_star_alias() {
+    assert_eq!(
+        eval("(let-values* (((a b) (values 1 2)) ((c) (values (+ a b)))) (list a b c))"),
+        "(1 2 3)"
+    );
+}
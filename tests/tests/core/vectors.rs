@@ -0,0 +1,158 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn vector_literals() {
+    assert_eq!(eval("#(1 2 hello #t)"), "#(1 2 hello #t)");
+    assert_eq!(eval("#()"), "#()");
+    assert_eq!(eval("'(a #(b c))"), "(a #(b c))");
+}
+
+#[test]
+fn vector_construction() {
+    assert_eq!(eval("(make-vector 3 'a)"), "#(a a a)");
+    assert_eq!(eval("(vector-length (make-vector 4))"), "4");
+    assert_eq!(eval("(vector 1 (+ 1 1) 3)"), "#(1 2 3)");
+    assert_eq!(eval("(vector? #(1))"), "#t");
+    assert_eq!(eval("(vector? '(1))"), "#f");
+}
+
+#[test]
+fn vector_ref_and_set() {
+    assert_eq!(eval("(vector-ref #(1 2 3) 1)"), "2");
+    assert_eq!(eval("(define v (vector 1 2 3)) (vector-set! v 0 'x) v"), "#(x 2 3)");
+    assert!(runner::eval_to_string("(vector-ref #(1 2 3) 3)").is_err());
+    assert!(runner::eval_to_string("(vector-ref #(1 2 3) -1)").is_err());
+}
+
+#[test]
+fn vector_set_is_visible_through_shared_references() {
+    assert_eq!(eval("(define a (vector 1 2)) (define b a) (vector-set! b 1 'y) a"), "#(1 y)");
+}
+
+#[test]
+fn vector_list_conversion() {
+    assert_eq!(eval("(vector->list #(1 2 3))"), "(1 2 3)");
+    assert_eq!(eval("(vector->list #(1 2 3) 1)"), "(2 3)");
+    assert_eq!(eval("(vector->list #(1 2 3) 1 2)"), "(2)");
+    assert_eq!(eval("(list->vector '(a b))"), "#(a b)");
+}
+
+#[test]
+fn vector_fill() {
+    assert_eq!(eval("(define v (vector 1 2 3)) (vector-fill! v 0) v"), "#(0 0 0)");
+    assert_eq!(eval("(define v (vector 1 2 3)) (vector-fill! v 0 1 2) v"), "#(1 0 3)");
+}
+
+#[test]
+fn vector_fill_with_do() {
+    assert_eq!(
+        eval("(do ((v (make-vector 5)) (i 0 (+ i 1))) ((= i 5) v) (vector-set! v i i))"),
+        "#(0 1 2 3 4)"
+    );
+}
+
+#[test]
+fn vector_copy() {
+    assert_eq!(
+        eval("(define a #(1 2 3)) (define b (vector-copy a)) (vector-set! b 0 9) a"),
+        "#(1 2 3)"
+    );
+    assert_eq!(eval("(vector-copy #(1 2 3) 1)"), "#(2 3)");
+    assert_eq!(
+        eval("(define v (vector 1 2 3 4 5)) (vector-copy! v 0 #(a b c) 1) v"),
+        "#(b c 3 4 5)"
+    );
+    assert_eq!(eval("(define v (vector 1 2 3 4 5)) (vector-copy! v 1 v 0 3) v"), "#(1 1 2 3 5)");
+    assert!(runner::eval_to_string("(vector-copy! (vector 1) 0 #(1 2))").is_err());
+}
+
+#[test]
+fn vector_append() {
+    assert_eq!(eval("(vector-append #(1) #() #(2 3))"), "#(1 2 3)");
+}
+
+#[test]
+fn vector_equality() {
+    assert_eq!(eval("(equal? #(1 (2)) (vector 1 '(2)))"), "#t");
+    assert_eq!(eval("(equal? #(1) #(2))"), "#f");
+}
+
+#[test]
+fn vector_map() {
+    assert_eq!(eval("(vector-map (lambda (x) (* x x)) #(1 2 3))"), "#(1 4 9)");
+    assert_eq!(eval("(vector-map + #(1 2 3) #(10 20))"), "#(11 22)");
+    assert_eq!(eval("(vector-map car #())"), "#()");
+    assert!(runner::eval_to_string("(vector-map car)").is_err());
+    assert!(runner::eval_to_string("(vector-map car '(1))").is_err());
+}
+
+#[test]
+fn vector_for_each() {
+    assert_eq!(
+        eval(
+            "(define total 0)
+             (vector-for-each (lambda (a b) (set! total (+ total (* a b)))) #(1 2 3) #(4 5 6 7))
+             total"
+        ),
+        "32"
+    );
+    assert!(runner::eval_to_string("(vector-for-each display)").is_err());
+}
+
+#[test]
+fn vector_map_in_place() {
+    assert_eq!(
+        eval("(define v (vector 1 2 3)) (vector-map! (lambda (x) (* x 10)) v) v"),
+        "#(10 20 30)"
+    );
+    assert_eq!(eval("(define v (vector 1 2 3)) (vector-map! + v #(10 20)) v"), "#(11 22 3)");
+    assert_eq!(
+        eval(
+            "(define v (vector 1 2)) (define copy (vector-copy v)) (vector-map! - v) (list v copy)"
+        ),
+        "(#(-1 -2) #(1 2))"
+    );
+}
+
+#[test]
+fn vector_for_each_in_place() {
+    assert_eq!(
+        eval("(define v (vector 1 2 3)) (vector-for-each! + v #(10 20 30) #(100 200 300)) v"),
+        "#(111 222 333)"
+    );
+    assert_eq!(
+        eval("(define v (vector 1 2)) (define w (vector 3 4)) (vector-for-each! * v w) (list v w)"),
+        "(#(3 8) #(3 4))"
+    );
+    assert!(runner::eval_to_string("(vector-for-each! + '(1 2))").is_err());
+    let error = runner::eval_to_string("(vector-for-each! +)").unwrap_err();
+    assert!(error.to_string().starts_with("vector-for-each!:"), "{error}");
+}
+
+#[test]
+fn vector_string_conversion() {
+    assert_eq!(eval("(vector->string #(#\\a #\\λ #\\c))"), "\"aλc\"");
+    assert_eq!(eval("(vector->string #(#\\a #\\b #\\c) 1)"), "\"bc\"");
+    assert_eq!(eval("(string->vector \"aλc\")"), "#(#\\a #\\λ #\\c)");
+    assert_eq!(eval("(string->vector \"abcd\" 1 3)"), "#(#\\b #\\c)");
+    assert_eq!(eval("(vector->string (string->vector \"round trip\"))"), "\"round trip\"");
+    assert!(runner::eval_to_string("(vector->string #(1 2))").is_err());
+}
@@ -0,0 +1,39 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use vow_core::runner;
+
+fn eval(input: &str) -> String {
+    runner::eval_to_string(input).unwrap()
+}
+
+#[test]
+fn when_true_evaluates_body() {
+    assert_eq!(eval("(define x 0) (when #t (set! x 1) (+ x 1))"), "2");
+}
+
+#[test]
+fn when_false_returns_unspecified() {
+    assert_eq!(eval("(when #f (undefined-function))"), "()");
+}
+
+#[test]
+fn unless_false_evaluates_body() {
+    assert_eq!(eval("(define x 0) (unless #f (set! x 1) (+ x 1))"), "2");
+}
+
+#[test]
+fn unless_true_returns_unspecified() {
+    assert_eq!(eval("(unless #t (undefined-function))"), "()");
+}
@@ -0,0 +1,158 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Property-based tests of the core language.
+
+use proptest::prelude::*;
+use vow_core::{Exp, WriteFormat};
+
+/// Evaluates `input` in a new standard environment, returning the value of its
+/// last expression.
+fn eval(input: &str) -> Exp {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let mut values = vow_core::eval_str(input, &mut env_tree, env_id).unwrap();
+    values.pop().unwrap()
+}
+
+/// Formats `exp` as source code which reads back as the same value.
+fn source(exp: &Exp) -> String {
+    WriteFormat(exp).to_string()
+}
+
+fn symbol() -> impl Strategy<Value = Exp> {
+    "[a-z][a-z0-9?!*<>=/+-]{0,8}".prop_map(Exp::symbol)
+}
+
+/// Data which can be written and read back: atoms, and lists and vectors of
+/// them nested at most four deep.
+fn datum() -> impl Strategy<Value = Exp> {
+    let leaf = prop_oneof![
+        any::<i64>().prop_map(Exp::int),
+        any::<f64>().prop_filter("NaN is not equal to itself", |n| !n.is_nan()).prop_map(Exp::num),
+        any::<bool>().prop_map(Exp::bool),
+        any::<char>().prop_map(|c| Exp::Atom(vow_core::Atom::Char(c))),
+        any::<String>().prop_map(Exp::string),
+        symbol(),
+    ];
+    leaf.prop_recursive(4, 32, 6, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..6).prop_map(Exp::List),
+            prop::collection::vec(inner, 0..6).prop_map(Exp::vector),
+        ]
+    })
+}
+
+fn integer_list() -> impl Strategy<Value = Vec<i64>> {
+    prop::collection::vec(-1000i64..1000, 0..20)
+}
+
+fn list_source(list: &[i64]) -> String {
+    format!("'({})", list.iter().map(i64::to_string).collect::<Vec<_>>().join(" "))
+}
+
+proptest! {
+    #[test]
+    fn addition_commutes(a in -1_000_000_000i64..1_000_000_000, b in -1_000_000_000i64..1_000_000_000) {
+        prop_assert_eq!(eval(&format!("(+ {a} {b})")), eval(&format!("(+ {b} {a})")));
+    }
+
+    #[test]
+    fn multiplication_commutes(a in any::<i32>(), b in any::<f64>()) {
+        let b = Exp::num(b);
+        prop_assert_eq!(
+            source(&eval(&format!("(* {a} {})", source(&b)))),
+            source(&eval(&format!("(* {} {a})", source(&b))))
+        );
+    }
+
+    #[test]
+    fn subtraction_inverts_addition(a in any::<i32>(), b in any::<i32>()) {
+        prop_assert_eq!(eval(&format!("(- (+ {a} {b}) {b})")), Exp::int(a.into()));
+    }
+
+    #[test]
+    fn division_identity(a in any::<i32>(), b in any::<i32>().prop_filter("non-zero", |b| *b != 0)) {
+        prop_assert_eq!(
+            eval(&format!("(+ (* (quotient {a} {b}) {b}) (remainder {a} {b}))")),
+            Exp::int(a.into())
+        );
+    }
+
+    #[test]
+    fn number_string_round_trip(n in any::<i64>(), radix in prop::sample::select(vec![2, 8, 10, 16])) {
+        prop_assert_eq!(eval(&format!("(string->number (number->string {n} {radix}) {radix})")), Exp::int(n));
+    }
+
+    #[test]
+    fn append_adds_lengths(l1 in integer_list(), l2 in integer_list()) {
+        prop_assert_eq!(
+            eval(&format!("(length (append {} {}))", list_source(&l1), list_source(&l2))),
+            eval(&format!("(+ (length {}) (length {}))", list_source(&l1), list_source(&l2)))
+        );
+    }
+
+    #[test]
+    fn apply_sums_list(list in integer_list()) {
+        prop_assert_eq!(
+            eval(&format!("(apply + {})", list_source(&list))),
+            Exp::int(list.iter().sum())
+        );
+    }
+
+    #[test]
+    fn sort_orders_a_permutation(list in integer_list()) {
+        let mut expected = list.clone();
+        expected.sort();
+        prop_assert_eq!(eval(&format!("(sort {} <)", list_source(&list))), eval(&list_source(&expected)));
+    }
+
+    #[test]
+    fn quote_evaluates_to_datum(x in datum()) {
+        prop_assert_eq!(eval(&format!("(quote {})", source(&x))), x);
+    }
+
+    #[test]
+    fn eval_of_quote_is_identity(x in datum()) {
+        prop_assert_eq!(eval(&format!("(eval '(quote {}) (interaction-environment))", source(&x))), x);
+    }
+
+    #[test]
+    fn write_then_read_round_trips(x in datum()) {
+        prop_assert_eq!(vow_core::parse_str(&source(&x)).unwrap(), vec![x]);
+    }
+
+    #[test]
+    fn equal_is_reflexive(x in datum()) {
+        prop_assert_eq!(eval(&format!("(let ((x '{})) (equal? x x))", source(&x))), Exp::bool(true));
+    }
+
+    #[test]
+    fn string_list_round_trip(s in any::<String>()) {
+        let s = source(&Exp::string(s));
+        prop_assert_eq!(eval(&format!("(list->string (string->list {s}))")), eval(&s));
+    }
+
+    #[test]
+    fn string_length_counts_chars(s in any::<String>()) {
+        prop_assert_eq!(
+            eval(&format!("(string-length {})", source(&Exp::string(s.clone())))),
+            Exp::int(s.chars().count() as i64)
+        );
+    }
+
+    #[test]
+    fn vector_list_round_trip(x in prop::collection::vec(datum(), 0..8)) {
+        let list = source(&Exp::List(x));
+        prop_assert_eq!(eval(&format!("(vector->list (list->vector '{list}))")), eval(&format!("'{list}")));
+    }
+}
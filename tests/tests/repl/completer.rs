@@ -0,0 +1,71 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reedline::{Completer, Span};
+use vow_repl::completer::VowCompleter;
+
+/// Returns the completions offered at the end of `line` in a new standard
+/// environment, after evaluating `definitions`.
+fn complete(definitions: &str, line: &str) -> Vec<String> {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    vow_core::eval_str(definitions, &mut env_tree, env_id).unwrap();
+    let mut completer = VowCompleter::default();
+    completer.set_symbols(vow_core::defined_symbols(&env_tree, env_id));
+    completer.complete(line, line.len()).into_iter().map(|s| s.value).collect()
+}
+
+#[test]
+fn completes_builtins() {
+    let completions = complete("", "(string-app");
+    assert_eq!(completions, vec!["string-append"]);
+}
+
+#[test]
+fn completions_are_sorted() {
+    let completions = complete("", "(vector-");
+    assert!(completions.contains(&"vector-ref".to_string()));
+    assert!(completions.contains(&"vector-set!".to_string()));
+    assert!(completions.windows(2).all(|pair| pair[0] < pair[1]), "{completions:?}");
+}
+
+#[test]
+fn completes_definitions() {
+    let completions = complete("(define frobnicate 1) (define (frobble) 2)", "(+ frob");
+    assert_eq!(completions, vec!["frobble", "frobnicate"]);
+}
+
+#[test]
+fn no_completions() {
+    assert!(complete("", "(zzz-undefined").is_empty());
+}
+
+#[test]
+fn replaces_partial_symbol() {
+    let mut completer = VowCompleter::default();
+    completer.set_symbols(vec!["display".to_string(), "define".to_string()]);
+    let suggestions = completer.complete("(list 'a (disp x)", 14);
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].value, "display");
+    assert_eq!(suggestions[0].span, Span::new(10, 14));
+}
+
+#[test]
+fn macro_renamed_symbols_are_omitted() {
+    let completions = complete(
+        "(define-syntax def-temp (syntax-rules () ((_ v) (define temp v))))
+         (def-temp 1)",
+        "(tem",
+    );
+    assert!(completions.iter().all(|c| !c.contains('·')), "{completions:?}");
+}
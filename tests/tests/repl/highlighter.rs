@@ -0,0 +1,86 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use nu_ansi_term::{Color, Style};
+use reedline::Highlighter;
+use vow_repl::highlighter::VowHighlighter;
+
+/// Returns the styled segments of `line`.
+fn highlight(line: &str) -> Vec<(Style, String)> {
+    let styled = VowHighlighter::new(true).highlight(line, line.len());
+    assert_eq!(styled.buffer.iter().map(|(_, text)| text.as_str()).collect::<String>(), line);
+    styled.buffer
+}
+
+/// Returns the style of the segment `text` in the highlighted `line`.
+fn style_of(line: &str, text: &str) -> Style {
+    highlight(line).into_iter().find(|(_, t)| t == text).expect("segment").0
+}
+
+#[test]
+fn keywords() {
+    assert_eq!(style_of("(define x 1)", "define"), Color::Yellow.bold());
+    assert_eq!(style_of("(lambda (x) x)", "lambda"), Color::Yellow.bold());
+    assert_eq!(style_of("(list x 1)", "list"), Color::White.normal());
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(style_of("(+ 42 x)", "42"), Color::Cyan.normal());
+    assert_eq!(style_of("(+ -1.5 x)", "-1.5"), Color::Cyan.normal());
+    assert_eq!(style_of("(+ 1+2i x)", "1+2i"), Color::Cyan.normal());
+    assert_eq!(style_of("(+ x1 x)", "x1"), Color::White.normal());
+}
+
+#[test]
+fn strings_and_comments() {
+    assert_eq!(style_of("(display \"a (b) \\\" c\")", "\"a (b) \\\" c\""), Color::Green.normal());
+    assert_eq!(style_of("(f) ; note (x", "; note (x"), Color::DarkGray.normal());
+}
+
+#[test]
+fn parentheses_by_depth() {
+    let parens: Vec<Style> = highlight("(a (b (c (d))))")
+        .into_iter()
+        .filter(|(_, t)| t == "(")
+        .map(|(s, _)| s)
+        .collect();
+    assert_eq!(parens[0], parens[3]);
+    assert_ne!(parens[0], parens[1]);
+    assert_ne!(parens[1], parens[2]);
+    let segments = highlight("(a (b))");
+    assert_eq!(segments[0].0, segments[6].0);
+    assert_eq!(segments[3].0, segments[5].0);
+}
+
+#[test]
+fn character_literals() {
+    assert_eq!(style_of("(list #\\( #\\a)", "#\\("), Color::White.normal());
+    assert_eq!(style_of("(list #\\space)", "#\\space"), Color::White.normal());
+}
+
+#[test]
+fn incomplete_input() {
+    highlight("(define (f x) \"unterminated");
+    highlight("))) (");
+    highlight("#\\");
+    highlight("(λ \"é");
+    highlight("; comment only");
+}
+
+#[test]
+fn no_color() {
+    let styled = VowHighlighter::new(false).highlight("(define x 1)", 0);
+    assert_eq!(styled.buffer, vec![(Style::new(), "(define x 1)".to_string())]);
+}
@@ -0,0 +1,107 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{env, fs};
+
+use vow_repl::metacommand::Metacommand;
+
+fn parse(line: &str) -> Result<Metacommand, String> {
+    Metacommand::parse(line).expect("metacommand")
+}
+
+/// Parses and runs `line` in a new standard environment, after evaluating
+/// `definitions`.
+fn run(definitions: &str, line: &str) -> Result<String, String> {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    vow_core::eval_str(definitions, &mut env_tree, env_id).unwrap();
+    parse(line)?.run(&mut env_tree, env_id)
+}
+
+#[test]
+fn scheme_code_is_not_a_metacommand() {
+    assert!(Metacommand::parse("(define x 1)").is_none());
+    assert!(Metacommand::parse("  (car '(1 ,2))").is_none());
+}
+
+#[test]
+fn parse_commands() {
+    assert_eq!(parse(",load foo.scm"), Ok(Metacommand::Load("foo.scm".to_string())));
+    assert_eq!(parse(",env"), Ok(Metacommand::Env(None)));
+    assert_eq!(parse("  ,env car "), Ok(Metacommand::Env(Some("car".to_string()))));
+    assert_eq!(parse(",quit"), Ok(Metacommand::Quit));
+    assert_eq!(parse(",exit"), Ok(Metacommand::Quit));
+    assert_eq!(parse(",help"), Ok(Metacommand::Help));
+    assert_eq!(parse(",trace f"), Ok(Metacommand::Trace("f".to_string())));
+    assert_eq!(parse(",untrace f"), Ok(Metacommand::Untrace("f".to_string())));
+}
+
+#[test]
+fn parse_errors() {
+    assert!(parse(",frobble").unwrap_err().contains("Unknown command ',frobble'"));
+    assert!(parse(",load").is_err());
+    assert!(parse(",quit now").is_err());
+    assert!(parse(",env a b").is_err());
+}
+
+#[test]
+fn help_lists_commands() {
+    let help = run("", ",help").unwrap();
+    for command in [",load", ",env", ",trace", ",untrace", ",help", ",quit"] {
+        assert!(help.contains(command), "{command}");
+    }
+}
+
+#[test]
+fn env_lists_symbols() {
+    let output = run("(define frobble 1)", ",env").unwrap();
+    let symbols: Vec<&str> = output.lines().collect();
+    assert!(symbols.contains(&"frobble"));
+    assert!(symbols.contains(&"car"));
+}
+
+#[test]
+fn env_shows_symbol() {
+    assert_eq!(
+        run("(define frobble \"x\")", ",env frobble"),
+        Ok("frobble = \"x\" : string".into())
+    );
+    assert_eq!(run("", ",env car"), Ok("car = <function> : built-in procedure".into()));
+    assert!(run("", ",env frobble").unwrap_err().contains("undefined symbol"));
+}
+
+#[test]
+fn load_file() {
+    let path = env::temp_dir().join(format!("vow-metacommand-{}.scm", std::process::id()));
+    fs::write(&path, "(define loaded 7)\n(* loaded 6)\n").unwrap();
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    let command = parse(&format!(",load {}", path.display())).unwrap();
+    assert_eq!(command.run(&mut env_tree, env_id), Ok("42".to_string()));
+    let values = vow_core::eval_str("loaded", &mut env_tree, env_id).unwrap();
+    assert_eq!(values, vec![vow_core::Exp::int(7)]);
+}
+
+#[test]
+fn trace_and_untrace() {
+    let (mut env_tree, env_id) = vow_core::standard_env();
+    vow_core::eval_str("(define (square x) (* x x))", &mut env_tree, env_id).unwrap();
+    assert_eq!(
+        parse(",trace square").unwrap().run(&mut env_tree, env_id),
+        Ok("Tracing square".into())
+    );
+    let values = vow_core::eval_str("(square 3)", &mut env_tree, env_id).unwrap();
+    assert_eq!(values, vec![vow_core::Exp::int(9)]);
+    assert!(parse(",untrace square").unwrap().run(&mut env_tree, env_id).is_ok());
+    assert!(parse(",untrace square").unwrap().run(&mut env_tree, env_id).is_err());
+    assert!(run("(define x 1)", ",trace x").is_err());
+}
@@ -12,4 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub mod runner;
+pub mod completer;
+pub mod highlighter;
+pub mod metacommand;
+pub mod validator;
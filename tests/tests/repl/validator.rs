@@ -0,0 +1,72 @@
+// Copyright © Vow 2024-present
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+
+//    https://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reedline::{ValidationResult, Validator};
+use vow_repl::validator::{is_balanced, BalancedParenValidator};
+
+#[test]
+fn balanced_input() {
+    assert!(is_balanced(""));
+    assert!(is_balanced("42"));
+    assert!(is_balanced("(define (f x)\n  (* x x))"));
+    assert!(is_balanced("#(1 2) '(a . b)"));
+}
+
+#[test]
+fn unclosed_parentheses() {
+    assert!(!is_balanced("(define (f x)"));
+    assert!(!is_balanced("(list 1\n  (list 2"));
+    assert!(!is_balanced("#(1 2"));
+}
+
+#[test]
+fn extra_closing_parentheses_are_complete() {
+    assert!(is_balanced("(+ 1 2))"));
+    assert!(is_balanced(")"));
+}
+
+#[test]
+fn parentheses_in_strings_are_ignored() {
+    assert!(is_balanced("(display \"(\")"));
+    assert!(is_balanced("(display \"a \\\" )\")"));
+    assert!(!is_balanced("(display \")\""));
+}
+
+#[test]
+fn unterminated_string() {
+    assert!(!is_balanced("(display \"abc"));
+    assert!(!is_balanced("\"abc\\\""));
+    assert!(is_balanced("\"multi\nline\""));
+}
+
+#[test]
+fn parentheses_in_comments_are_ignored() {
+    assert!(is_balanced("(f x) ; (unclosed"));
+    assert!(!is_balanced("(f x ; )\n"));
+    assert!(is_balanced("(f x ; )\n)"));
+}
+
+#[test]
+fn parentheses_in_characters_are_ignored() {
+    assert!(is_balanced("(list #\\( #\\))"));
+    assert!(!is_balanced("(list #\\)"));
+    assert!(is_balanced(r#"(list #\" #\;)"#));
+}
+
+#[test]
+fn validator() {
+    let validator = BalancedParenValidator;
+    assert!(matches!(validator.validate("(car '(1 2))"), ValidationResult::Complete));
+    assert!(matches!(validator.validate("(car '(1 2)"), ValidationResult::Incomplete));
+}